@@ -32,6 +32,7 @@ pub use self::{
     opening_key::OpeningKey,
     sealing_key::SealingKey,
     unbound_key::UnboundKey,
+    usage_limits::{UsageLimited, UsageLimits},
 };
 
 /// A sequences of unique nonces.
@@ -143,6 +144,12 @@ pub struct Algorithm {
         src: RangeFrom<usize>,
     ) -> Tag,
 
+    seal_vectored: VectoredFn,
+    open_vectored: VectoredFn,
+
+    seal_to: ToFn,
+    open_to: ToFn,
+
     key_len: usize,
     id: AlgorithmID,
 
@@ -151,6 +158,23 @@ pub struct Algorithm {
     max_input_len: u64,
 }
 
+// Seals or opens `in_out`, which is the concatenation of the fragments, in
+// place. The AAD is the concatenation of the elements of `aad`.
+type VectoredFn = fn(key: &KeyInner, nonce: Nonce, aad: &[&[u8]], in_out: &mut [&mut [u8]]) -> Tag;
+
+// Seals or opens `input` into `output`, which has the same length.
+type ToFn =
+    fn(key: &KeyInner, nonce: Nonce, aad: Aad<&[u8]>, input: &[u8], output: &mut [u8]) -> Tag;
+
+/// Whether an operation is sealing or opening. Implementations that don't
+/// otherwise distinguish the two need this to know whether the ciphertext is
+/// the input or the output.
+#[derive(Clone, Copy, PartialEq)]
+enum Direction {
+    Sealing,
+    Opening,
+}
+
 const fn max_input_len(block_len: usize, overhead_blocks_per_nonce: usize) -> u64 {
     // Each of our AEADs use a 32-bit block counter so the maximum is the
     // largest input that will not overflow the counter.
@@ -233,18 +257,30 @@ const TAG_LEN: usize = 16;
 /// The maximum length of a tag for the algorithms in this module.
 pub const MAX_TAG_LEN: usize = TAG_LEN;
 
-mod aes;
+pub mod aegis;
+pub(crate) mod aes;
 mod aes_gcm;
+pub mod aes_gcm_openssh;
 mod block;
 mod chacha;
 mod chacha20_poly1305;
 pub mod chacha20_poly1305_openssh;
 mod gcm;
+pub mod gmac;
 mod less_safe_key;
+// `crypto_box` and `crypto_box_seal` use X25519, which isn't available for
+// wasm32.
+#[cfg(not(target_arch = "wasm32"))]
+pub mod nacl;
 mod nonce;
+pub mod nonce_sequence;
 mod opening_key;
-mod poly1305;
+pub mod poly1305;
 pub mod quic;
+#[cfg(any(feature = "alloc", not(target_arch = "wasm32")))]
+pub(crate) mod salsa20;
 mod sealing_key;
 mod shift;
 mod unbound_key;
+mod usage_limits;
+mod vectored;
@@ -0,0 +1,865 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! AEGIS-128L and AEGIS-256, as specified in [draft-irtf-cfrg-aegis-aead].
+//!
+//! AEGIS is built only on the AES round function, so it is much faster than
+//! AES-GCM when the AES instructions are available. Without them, a portable
+//! constant-time implementation of the AES round function is used, which is
+//! much slower.
+//!
+//! AEGIS uses longer nonces and tags than the other AEADs in `ring::aead`, so
+//! it has its own [`Algorithm`], [`LessSafeKey`], [`Nonce`], and [`Tag`]
+//! types instead of being an `aead::Algorithm`.
+//!
+//! [draft-irtf-cfrg-aegis-aead]:
+//!     https://datatracker.ietf.org/doc/draft-irtf-cfrg-aegis-aead/
+
+use super::{block::BLOCK_LEN, Aad, Direction};
+use crate::{constant_time, cpu, error, polyfill};
+use core::ops::RangeFrom;
+
+/// An AEGIS algorithm.
+pub struct Algorithm {
+    key_len: usize,
+    id: AlgorithmID,
+}
+
+impl Algorithm {
+    /// The length of the key.
+    #[inline(always)]
+    pub fn key_len(&self) -> usize {
+        self.key_len
+    }
+
+    /// The length of a tag.
+    ///
+    /// This is the same for both algorithms; see also `TAG_LEN`.
+    #[inline(always)]
+    pub fn tag_len(&self) -> usize {
+        TAG_LEN
+    }
+
+    /// The length of the nonces, which is the same as the length of the key.
+    #[inline(always)]
+    pub fn nonce_len(&self) -> usize {
+        self.key_len
+    }
+}
+
+derive_debug_via_id!(Algorithm);
+
+#[derive(Debug, Eq, PartialEq)]
+enum AlgorithmID {
+    AEGIS_128L,
+    AEGIS_256,
+}
+
+impl PartialEq for Algorithm {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Algorithm {}
+
+/// AEGIS-128L with 256-bit tags.
+///
+/// The keys and nonces are 128 bits long.
+pub static AEGIS_128L: Algorithm = Algorithm {
+    key_len: AEGIS_128L_KEY_LEN,
+    id: AlgorithmID::AEGIS_128L,
+};
+
+/// AEGIS-256 with 256-bit tags.
+///
+/// The keys and nonces are 256 bits long.
+pub static AEGIS_256: Algorithm = Algorithm {
+    key_len: AEGIS_256_KEY_LEN,
+    id: AlgorithmID::AEGIS_256,
+};
+
+/// The length of an AEGIS tag.
+pub const TAG_LEN: usize = 256 / 8;
+
+const AEGIS_128L_KEY_LEN: usize = 16;
+const AEGIS_256_KEY_LEN: usize = 32;
+
+// The lengths of the input and of the AAD in bits must each fit in 64 bits.
+const AEGIS_MAX_INPUT_LEN: u64 = (1 << 61) - 1;
+
+/// A nonce for a single AEGIS opening or sealing operation.
+///
+/// The user must ensure, for a particular key, that each nonce is unique.
+///
+/// `Nonce` intentionally doesn't implement `Clone` to ensure that each one is
+/// consumed at most once.
+pub struct Nonce(NonceValue);
+
+enum NonceValue {
+    Aegis128L([u8; AEGIS_128L_KEY_LEN]),
+    Aegis256([u8; AEGIS_256_KEY_LEN]),
+}
+
+impl Nonce {
+    /// Constructs a `Nonce` with the given value, assuming that the value is
+    /// unique for the lifetime of the key it is being used with.
+    ///
+    /// Fails if `value` isn't 16 bytes long (for AEGIS-128L) or 32 bytes long
+    /// (for AEGIS-256). Using the nonce with a key of the other algorithm
+    /// fails.
+    #[inline]
+    pub fn try_assume_unique_for_key(value: &[u8]) -> Result<Self, error::Unspecified> {
+        let value = match value.len() {
+            AEGIS_128L_KEY_LEN => NonceValue::Aegis128L(value.try_into()?),
+            AEGIS_256_KEY_LEN => NonceValue::Aegis256(value.try_into()?),
+            _ => return Err(error::Unspecified),
+        };
+        Ok(Self(value))
+    }
+}
+
+impl AsRef<[u8]> for Nonce {
+    fn as_ref(&self) -> &[u8] {
+        match &self.0 {
+            NonceValue::Aegis128L(value) => value,
+            NonceValue::Aegis256(value) => value,
+        }
+    }
+}
+
+/// A possibly valid AEGIS authentication tag.
+#[must_use]
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Tag([u8; TAG_LEN]);
+
+impl AsRef<[u8]> for Tag {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl TryFrom<&[u8]> for Tag {
+    type Error = error::Unspecified;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let raw_tag: [u8; TAG_LEN] = value.try_into().map_err(|_| error::Unspecified)?;
+        Ok(Self::from(raw_tag))
+    }
+}
+
+impl From<[u8; TAG_LEN]> for Tag {
+    #[inline]
+    fn from(value: [u8; TAG_LEN]) -> Self {
+        Self(value)
+    }
+}
+
+/// Immutable keys for use in situations where `OpeningKey`/`SealingKey` and
+/// `NonceSequence` cannot reasonably be used, like
+/// [`aead::LessSafeKey`](super::LessSafeKey), for AEGIS.
+#[derive(Clone)]
+pub struct LessSafeKey {
+    key: Key,
+    algorithm: &'static Algorithm,
+}
+
+impl LessSafeKey {
+    /// Constructs a `LessSafeKey` for `algorithm` from `key_bytes`.
+    ///
+    /// Fails if `key_bytes.len() != algorithm.key_len()`.
+    pub fn new(
+        algorithm: &'static Algorithm,
+        key_bytes: &[u8],
+    ) -> Result<Self, error::Unspecified> {
+        let value = match algorithm.id {
+            AlgorithmID::AEGIS_128L => KeyValue::Aegis128L(key_bytes.try_into()?),
+            AlgorithmID::AEGIS_256 => KeyValue::Aegis256(key_bytes.try_into()?),
+        };
+        Ok(Self {
+            key: Key {
+                value,
+                cpu_features: cpu::features(),
+            },
+            algorithm,
+        })
+    }
+
+    /// Like [`aead::LessSafeKey::seal_in_place_append_tag()`].
+    ///
+    /// Fails if `nonce` is for the other AEGIS algorithm.
+    ///
+    /// [`aead::LessSafeKey::seal_in_place_append_tag()`]:
+    ///     super::LessSafeKey::seal_in_place_append_tag
+    #[inline]
+    pub fn seal_in_place_append_tag<A, InOut>(
+        &self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        in_out: &mut InOut,
+    ) -> Result<(), error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+        InOut: AsMut<[u8]> + for<'in_out> Extend<&'in_out u8>,
+    {
+        self.seal_in_place_separate_tag(nonce, aad, in_out.as_mut())
+            .map(|tag| in_out.extend(tag.as_ref()))
+    }
+
+    /// Like [`aead::LessSafeKey::seal_in_place_separate_tag()`].
+    ///
+    /// Fails if `nonce` is for the other AEGIS algorithm.
+    ///
+    /// [`aead::LessSafeKey::seal_in_place_separate_tag()`]:
+    ///     super::LessSafeKey::seal_in_place_separate_tag
+    #[inline]
+    pub fn seal_in_place_separate_tag<A>(
+        &self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        in_out: &mut [u8],
+    ) -> Result<Tag, error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        aegis(
+            &self.key,
+            nonce,
+            aad.as_ref(),
+            in_out,
+            0..,
+            Direction::Sealing,
+        )
+    }
+
+    /// Like [`aead::LessSafeKey::open_in_place()`].
+    ///
+    /// Fails if `nonce` is for the other AEGIS algorithm.
+    ///
+    /// [`aead::LessSafeKey::open_in_place()`]: super::LessSafeKey::open_in_place
+    #[inline]
+    pub fn open_in_place<'in_out, A>(
+        &self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        in_out: &'in_out mut [u8],
+    ) -> Result<&'in_out mut [u8], error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        self.open_within(nonce, aad, in_out, 0..)
+    }
+
+    /// Like [`aead::LessSafeKey::open_within()`].
+    ///
+    /// Fails if `nonce` is for the other AEGIS algorithm.
+    ///
+    /// [`aead::LessSafeKey::open_within()`]: super::LessSafeKey::open_within
+    #[inline]
+    pub fn open_within<'in_out, A>(
+        &self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        in_out: &'in_out mut [u8],
+        ciphertext_and_tag: RangeFrom<usize>,
+    ) -> Result<&'in_out mut [u8], error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        let tag_offset = in_out
+            .len()
+            .checked_sub(TAG_LEN)
+            .ok_or(error::Unspecified)?;
+
+        // Split the tag off the end of `in_out`.
+        let (in_out, received_tag) = in_out.split_at_mut(tag_offset);
+        let received_tag = (*received_tag).try_into()?;
+
+        self.open_in_place_separate_tag(nonce, aad, received_tag, in_out, ciphertext_and_tag)
+    }
+
+    /// Like [`aead::LessSafeKey::open_in_place_separate_tag()`].
+    ///
+    /// Fails if `nonce` is for the other AEGIS algorithm.
+    ///
+    /// [`aead::LessSafeKey::open_in_place_separate_tag()`]:
+    ///     super::LessSafeKey::open_in_place_separate_tag
+    #[inline]
+    pub fn open_in_place_separate_tag<'in_out, A>(
+        &self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        received_tag: Tag,
+        in_out: &'in_out mut [u8],
+        ciphertext: RangeFrom<usize>,
+    ) -> Result<&'in_out mut [u8], error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        let ciphertext_len = in_out
+            .get(ciphertext.clone())
+            .ok_or(error::Unspecified)?
+            .len();
+
+        let Tag(calculated_tag) = aegis(
+            &self.key,
+            nonce,
+            aad.as_ref(),
+            in_out,
+            ciphertext,
+            Direction::Opening,
+        )?;
+
+        if constant_time::verify_slices_are_equal(&calculated_tag, received_tag.as_ref()).is_err() {
+            // Zero out the plaintext, as `aead::LessSafeKey::open_within()`
+            // does.
+            for b in &mut in_out[..ciphertext_len] {
+                *b = 0;
+            }
+            return Err(error::Unspecified);
+        }
+
+        // `ciphertext_len` is also the plaintext length.
+        Ok(&mut in_out[..ciphertext_len])
+    }
+
+    /// The key's AEGIS algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.algorithm
+    }
+}
+
+impl core::fmt::Debug for LessSafeKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("LessSafeKey")
+            .field("algorithm", self.algorithm())
+            .finish()
+    }
+}
+
+#[derive(Clone)]
+struct Key {
+    value: KeyValue,
+    cpu_features: cpu::Features,
+}
+
+#[derive(Clone)]
+enum KeyValue {
+    Aegis128L([u8; AEGIS_128L_KEY_LEN]),
+    Aegis256([u8; AEGIS_256_KEY_LEN]),
+}
+
+// Encrypts or decrypts `in_out[src]` into the start of `in_out` and returns
+// the tag.
+fn aegis(
+    key: &Key,
+    nonce: Nonce,
+    aad: &[u8],
+    in_out: &mut [u8],
+    src: RangeFrom<usize>,
+    direction: Direction,
+) -> Result<Tag, error::Unspecified> {
+    let in_out_len = in_out.get(src.clone()).ok_or(error::Unspecified)?.len();
+    if polyfill::u64_from_usize(in_out_len) > AEGIS_MAX_INPUT_LEN
+        || polyfill::u64_from_usize(aad.len()) > AEGIS_MAX_INPUT_LEN
+    {
+        return Err(error::Unspecified);
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if cpu::intel::AES.available(key.cpu_features) {
+            return unsafe { aes_ni::aegis(key, &nonce, aad, in_out, src, direction) };
+        }
+    }
+
+    aegis_::<portable::Block>(key, &nonce, aad, in_out, src, direction)
+}
+
+#[inline(always)]
+fn aegis_<B: AesBlock>(
+    key: &Key,
+    nonce: &Nonce,
+    aad: &[u8],
+    in_out: &mut [u8],
+    src: RangeFrom<usize>,
+    direction: Direction,
+) -> Result<Tag, error::Unspecified> {
+    match (&key.value, &nonce.0) {
+        (KeyValue::Aegis128L(key), NonceValue::Aegis128L(nonce)) => {
+            let state = Aegis128L::<B>::new(key, nonce);
+            Ok(crypt(state, aad, in_out, src, direction))
+        }
+        (KeyValue::Aegis256(key), NonceValue::Aegis256(nonce)) => {
+            let state = Aegis256::<B>::new(key, nonce);
+            Ok(crypt(state, aad, in_out, src, direction))
+        }
+        _ => Err(error::Unspecified),
+    }
+}
+
+#[inline(always)]
+fn crypt<S: State<RATE>, const RATE: usize>(
+    mut state: S,
+    aad: &[u8],
+    in_out: &mut [u8],
+    src: RangeFrom<usize>,
+    direction: Direction,
+) -> Tag {
+    for chunk in aad.chunks(RATE) {
+        let mut block = [0u8; RATE];
+        block[..chunk.len()].copy_from_slice(chunk);
+        state.absorb(&block);
+    }
+
+    let in_out_len = in_out.len() - src.start;
+    let mut offset = 0;
+    while offset < in_out_len {
+        let n = core::cmp::min(RATE, in_out_len - offset);
+        let mut block = [0u8; RATE];
+        block[..n].copy_from_slice(&in_out[(src.start + offset)..][..n]);
+        crypt_block(&mut state, direction, &mut block, n);
+        in_out[offset..][..n].copy_from_slice(&block[..n]);
+        offset += n;
+    }
+
+    state.finalize(aad.len(), in_out_len)
+}
+
+// Encrypts or decrypts the first `len` bytes of `block`. When sealing, the
+// rest of `block` must be zero.
+#[inline(always)]
+fn crypt_block<S: State<RATE>, const RATE: usize>(
+    state: &mut S,
+    direction: Direction,
+    block: &mut [u8; RATE],
+    len: usize,
+) {
+    let keystream = state.keystream();
+    if direction == Direction::Sealing {
+        state.absorb(block);
+    }
+    block
+        .iter_mut()
+        .zip(keystream.iter())
+        .for_each(|(b, k)| *b ^= *k);
+    if direction == Direction::Opening {
+        block[len..].iter_mut().for_each(|b| *b = 0);
+        state.absorb(block);
+    }
+}
+
+/// The state of AEGIS-128L or AEGIS-256, which processes its input `RATE`
+/// bytes at a time.
+trait State<const RATE: usize> {
+    fn absorb(&mut self, input: &[u8; RATE]);
+    fn keystream(&self) -> [u8; RATE];
+    fn finalize(self, aad_len: usize, in_out_len: usize) -> Tag;
+}
+
+/// A 128-bit AES block.
+trait AesBlock: Copy {
+    fn from_bytes(bytes: &[u8; BLOCK_LEN]) -> Self;
+    fn to_bytes(self) -> [u8; BLOCK_LEN];
+    fn xor(self, other: Self) -> Self;
+    fn and(self, other: Self) -> Self;
+
+    /// Computes `AESRound(block, round_key)` for each of the blocks, i.e. one
+    /// AES encryption round, like the AESENC instruction.
+    fn aes_rounds<const N: usize>(blocks: [Self; N], round_keys: [Self; N]) -> [Self; N];
+}
+
+// The constants C0 and C1, which are the Fibonacci sequence modulo 256.
+const C0: [u8; BLOCK_LEN] = [
+    0x00, 0x01, 0x01, 0x02, 0x03, 0x05, 0x08, 0x0d, 0x15, 0x22, 0x37, 0x59, 0x90, 0xe9, 0x79, 0x62,
+];
+const C1: [u8; BLOCK_LEN] = [
+    0xdb, 0x3d, 0x18, 0x55, 0x6d, 0xc2, 0x2f, 0xf1, 0x20, 0x11, 0x31, 0x42, 0x73, 0xb5, 0x28, 0xdd,
+];
+
+// The lengths of the AAD and the input in bits, as the `Finalize` functions
+// use them.
+fn lengths<B: AesBlock>(aad_len: usize, in_out_len: usize) -> B {
+    let mut lengths = [0u8; BLOCK_LEN];
+    let (aad_bits, in_out_bits) = lengths.split_at_mut(8);
+    aad_bits.copy_from_slice(&(polyfill::u64_from_usize(aad_len) << 3).to_le_bytes());
+    in_out_bits.copy_from_slice(&(polyfill::u64_from_usize(in_out_len) << 3).to_le_bytes());
+    B::from_bytes(&lengths)
+}
+
+fn tag<B: AesBlock>(first_half: B, second_half: B) -> Tag {
+    let mut tag = [0u8; TAG_LEN];
+    let (first, second) = tag.split_at_mut(BLOCK_LEN);
+    first.copy_from_slice(&first_half.to_bytes());
+    second.copy_from_slice(&second_half.to_bytes());
+    Tag(tag)
+}
+
+struct Aegis128L<B>([B; 8]);
+
+impl<B: AesBlock> Aegis128L<B> {
+    #[inline(always)]
+    fn new(key: &[u8; AEGIS_128L_KEY_LEN], nonce: &[u8; AEGIS_128L_KEY_LEN]) -> Self {
+        let key = B::from_bytes(key);
+        let nonce = B::from_bytes(nonce);
+        let c0 = B::from_bytes(&C0);
+        let c1 = B::from_bytes(&C1);
+        let mut state = Self([
+            key.xor(nonce),
+            c1,
+            c0,
+            c1,
+            key.xor(nonce),
+            key.xor(c0),
+            key.xor(c1),
+            key.xor(c0),
+        ]);
+        for _ in 0..10 {
+            state.update(nonce, key);
+        }
+        state
+    }
+
+    #[inline(always)]
+    fn update(&mut self, m0: B, m1: B) {
+        let s = &self.0;
+        self.0 = B::aes_rounds(
+            [s[7], s[0], s[1], s[2], s[3], s[4], s[5], s[6]],
+            [
+                s[0].xor(m0),
+                s[1],
+                s[2],
+                s[3],
+                s[4].xor(m1),
+                s[5],
+                s[6],
+                s[7],
+            ],
+        );
+    }
+}
+
+impl<B: AesBlock> State<{ 2 * BLOCK_LEN }> for Aegis128L<B> {
+    #[inline(always)]
+    fn absorb(&mut self, input: &[u8; 2 * BLOCK_LEN]) {
+        let (m0, m1) = input.split_at(BLOCK_LEN);
+        self.update(
+            B::from_bytes(m0.try_into().unwrap()),
+            B::from_bytes(m1.try_into().unwrap()),
+        );
+    }
+
+    #[inline(always)]
+    fn keystream(&self) -> [u8; 2 * BLOCK_LEN] {
+        let s = &self.0;
+        let z0 = s[6].xor(s[1]).xor(s[2].and(s[3]));
+        let z1 = s[2].xor(s[5]).xor(s[6].and(s[7]));
+        let mut keystream = [0u8; 2 * BLOCK_LEN];
+        let (first, second) = keystream.split_at_mut(BLOCK_LEN);
+        first.copy_from_slice(&z0.to_bytes());
+        second.copy_from_slice(&z1.to_bytes());
+        keystream
+    }
+
+    #[inline(always)]
+    fn finalize(mut self, aad_len: usize, in_out_len: usize) -> Tag {
+        let t = self.0[2].xor(lengths(aad_len, in_out_len));
+        for _ in 0..7 {
+            self.update(t, t);
+        }
+        let s = &self.0;
+        tag(
+            s[0].xor(s[1]).xor(s[2]).xor(s[3]),
+            s[4].xor(s[5]).xor(s[6]).xor(s[7]),
+        )
+    }
+}
+
+struct Aegis256<B>([B; 6]);
+
+impl<B: AesBlock> Aegis256<B> {
+    #[inline(always)]
+    fn new(key: &[u8; AEGIS_256_KEY_LEN], nonce: &[u8; AEGIS_256_KEY_LEN]) -> Self {
+        let (k0, k1) = key.split_at(BLOCK_LEN);
+        let (n0, n1) = nonce.split_at(BLOCK_LEN);
+        let k0 = B::from_bytes(k0.try_into().unwrap());
+        let k1 = B::from_bytes(k1.try_into().unwrap());
+        let n0 = B::from_bytes(n0.try_into().unwrap());
+        let n1 = B::from_bytes(n1.try_into().unwrap());
+        let c0 = B::from_bytes(&C0);
+        let c1 = B::from_bytes(&C1);
+        let mut state = Self([k0.xor(n0), k1.xor(n1), c1, c0, k0.xor(c0), k1.xor(c1)]);
+        for _ in 0..4 {
+            state.update(k0);
+            state.update(k1);
+            state.update(k0.xor(n0));
+            state.update(k1.xor(n1));
+        }
+        state
+    }
+
+    #[inline(always)]
+    fn update(&mut self, m: B) {
+        let s = &self.0;
+        self.0 = B::aes_rounds(
+            [s[5], s[0], s[1], s[2], s[3], s[4]],
+            [s[0].xor(m), s[1], s[2], s[3], s[4], s[5]],
+        );
+    }
+}
+
+impl<B: AesBlock> State<BLOCK_LEN> for Aegis256<B> {
+    #[inline(always)]
+    fn absorb(&mut self, input: &[u8; BLOCK_LEN]) {
+        self.update(B::from_bytes(input));
+    }
+
+    #[inline(always)]
+    fn keystream(&self) -> [u8; BLOCK_LEN] {
+        let s = &self.0;
+        s[1].xor(s[4]).xor(s[5]).xor(s[2].and(s[3])).to_bytes()
+    }
+
+    #[inline(always)]
+    fn finalize(mut self, aad_len: usize, in_out_len: usize) -> Tag {
+        let t = self.0[3].xor(lengths(aad_len, in_out_len));
+        for _ in 0..7 {
+            self.update(t);
+        }
+        let s = &self.0;
+        tag(s[0].xor(s[1]).xor(s[2]), s[3].xor(s[4]).xor(s[5]))
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod aes_ni {
+    use super::{AesBlock, Direction, Key, Nonce, Tag, BLOCK_LEN};
+    use crate::error;
+    use core::ops::RangeFrom;
+
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
+
+    #[derive(Clone, Copy)]
+    pub(super) struct Block(__m128i);
+
+    // These are only called from `aegis`, where the AES instructions are
+    // enabled, so they are inlined there.
+    impl AesBlock for Block {
+        #[inline(always)]
+        fn from_bytes(bytes: &[u8; BLOCK_LEN]) -> Self {
+            Self(unsafe { _mm_loadu_si128(bytes.as_ptr().cast()) })
+        }
+
+        #[inline(always)]
+        fn to_bytes(self) -> [u8; BLOCK_LEN] {
+            let mut bytes = [0u8; BLOCK_LEN];
+            unsafe { _mm_storeu_si128(bytes.as_mut_ptr().cast(), self.0) };
+            bytes
+        }
+
+        #[inline(always)]
+        fn xor(self, other: Self) -> Self {
+            Self(unsafe { _mm_xor_si128(self.0, other.0) })
+        }
+
+        #[inline(always)]
+        fn and(self, other: Self) -> Self {
+            Self(unsafe { _mm_and_si128(self.0, other.0) })
+        }
+
+        #[inline(always)]
+        fn aes_rounds<const N: usize>(blocks: [Self; N], round_keys: [Self; N]) -> [Self; N] {
+            let mut out = blocks;
+            out.iter_mut()
+                .zip(round_keys.iter())
+                .for_each(|(block, round_key)| {
+                    *block = Self(unsafe { _mm_aesenc_si128(block.0, round_key.0) })
+                });
+            out
+        }
+    }
+
+    /// # Safety
+    ///
+    /// The CPU must support the AES instructions.
+    #[target_feature(enable = "aes,sse2")]
+    pub(super) unsafe fn aegis(
+        key: &Key,
+        nonce: &Nonce,
+        aad: &[u8],
+        in_out: &mut [u8],
+        src: RangeFrom<usize>,
+        direction: Direction,
+    ) -> Result<Tag, error::Unspecified> {
+        super::aegis_::<Block>(key, nonce, aad, in_out, src, direction)
+    }
+}
+
+mod portable {
+    use super::{
+        super::aes::portable::{shift_rows_mix_columns, sub_bytes},
+        AesBlock, BLOCK_LEN,
+    };
+
+    #[derive(Clone, Copy)]
+    pub(super) struct Block([u64; 2]);
+
+    impl AesBlock for Block {
+        #[inline(always)]
+        fn from_bytes(bytes: &[u8; BLOCK_LEN]) -> Self {
+            let (lo, hi) = bytes.split_at(8);
+            Self([
+                u64::from_le_bytes(lo.try_into().unwrap()),
+                u64::from_le_bytes(hi.try_into().unwrap()),
+            ])
+        }
+
+        #[inline(always)]
+        fn to_bytes(self) -> [u8; BLOCK_LEN] {
+            let mut bytes = [0u8; BLOCK_LEN];
+            let (lo, hi) = bytes.split_at_mut(8);
+            lo.copy_from_slice(&self.0[0].to_le_bytes());
+            hi.copy_from_slice(&self.0[1].to_le_bytes());
+            bytes
+        }
+
+        #[inline(always)]
+        fn xor(self, other: Self) -> Self {
+            Self([self.0[0] ^ other.0[0], self.0[1] ^ other.0[1]])
+        }
+
+        #[inline(always)]
+        fn and(self, other: Self) -> Self {
+            Self([self.0[0] & other.0[0], self.0[1] & other.0[1]])
+        }
+
+        fn aes_rounds<const N: usize>(blocks: [Self; N], round_keys: [Self; N]) -> [Self; N] {
+            let mut out = blocks;
+            out.iter_mut()
+                .zip(round_keys.iter())
+                .for_each(|(block, round_key)| {
+                    let sub_bytes = Self([sub_bytes(block.0[0]), sub_bytes(block.0[1])]);
+                    let mixed = shift_rows_mix_columns(sub_bytes.to_bytes());
+                    *block = Self::from_bytes(&mixed).xor(*round_key);
+                });
+            out
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        // The first round of the AES-128 example in FIPS 197 Appendix B.
+        #[test]
+        fn test_aes_round() {
+            let state = Block::from_bytes(&[
+                0x19, 0x3d, 0xe3, 0xbe, 0xa0, 0xf4, 0xe2, 0x2b, 0x9a, 0xc6, 0x8d, 0x2a, 0xe9, 0xf8,
+                0x48, 0x08,
+            ]);
+            let round_key = Block::from_bytes(&[
+                0xa0, 0xfa, 0xfe, 0x17, 0x88, 0x54, 0x2c, 0xb1, 0x23, 0xa3, 0x39, 0x39, 0x2a, 0x6c,
+                0x76, 0x05,
+            ]);
+            let expected = [
+                0xa4, 0x9c, 0x7f, 0xf2, 0x68, 0x9f, 0x35, 0x2b, 0x6b, 0x5b, 0xea, 0x43, 0x02, 0x6a,
+                0x50, 0x49,
+            ];
+            let [actual] = Block::aes_rounds([state], [round_key]);
+            assert_eq!(actual.to_bytes(), expected);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The portable implementation must match the AES-NI implementation,
+    // which is checked against the known answer tests.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[test]
+    fn test_portable_matches_aes_ni() {
+        let cpu_features = cpu::features();
+        if !cpu::intel::AES.available(cpu_features) {
+            return;
+        }
+
+        let keys = [
+            Key {
+                value: KeyValue::Aegis128L([0x42; AEGIS_128L_KEY_LEN]),
+                cpu_features,
+            },
+            Key {
+                value: KeyValue::Aegis256([0x42; AEGIS_256_KEY_LEN]),
+                cpu_features,
+            },
+        ];
+        let aad = [0x5a; 70];
+        let mut input = [0u8; 100];
+        input.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+        for key in keys.iter() {
+            let nonce = || {
+                let value = [0x24; AEGIS_256_KEY_LEN];
+                let nonce_len = match key.value {
+                    KeyValue::Aegis128L(_) => AEGIS_128L_KEY_LEN,
+                    KeyValue::Aegis256(_) => AEGIS_256_KEY_LEN,
+                };
+                Nonce::try_assume_unique_for_key(&value[..nonce_len]).unwrap()
+            };
+            for len in [0, 1, 15, 16, 17, 31, 32, 33, 100] {
+                let input = &input[..len];
+                let aad = &aad[..(len % aad.len())];
+
+                let mut portable = [0u8; 100];
+                let portable = &mut portable[..len];
+                portable.copy_from_slice(input);
+                let portable_tag = aegis_::<portable::Block>(
+                    key,
+                    &nonce(),
+                    aad,
+                    portable,
+                    0..,
+                    Direction::Sealing,
+                )
+                .unwrap();
+                let mut aes_ni = [0u8; 100];
+                let aes_ni = &mut aes_ni[..len];
+                aes_ni.copy_from_slice(input);
+                let aes_ni_tag = unsafe {
+                    aes_ni::aegis(key, &nonce(), aad, aes_ni, 0.., Direction::Sealing).unwrap()
+                };
+                assert_eq!(portable, aes_ni);
+                assert_eq!(portable_tag.as_ref(), aes_ni_tag.as_ref());
+
+                let opened_tag = aegis_::<portable::Block>(
+                    key,
+                    &nonce(),
+                    aad,
+                    portable,
+                    0..,
+                    Direction::Opening,
+                )
+                .unwrap();
+                assert_eq!(portable, input);
+                assert_eq!(opened_tag.as_ref(), aes_ni_tag.as_ref());
+            }
+        }
+    }
+}
@@ -26,6 +26,10 @@ use crate::{
 };
 use core::ops::RangeFrom;
 
+#[cfg(feature = "alloc")]
+pub(crate) mod cbc;
+pub(super) mod portable;
+
 #[derive(Clone)]
 pub(crate) struct Key {
     inner: AES_KEY,
@@ -0,0 +1,141 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! AES-256-CBC with PKCS#7 padding.
+//!
+//! CBC mode is unauthenticated; it is only here so that legacy formats like
+//! encrypted PKCS#8 can be read and written. Decryption uses the portable
+//! inverse cipher since the assembly implementations only encrypt.
+
+use super::{
+    super::block::{Block, BLOCK_LEN},
+    portable::Aes256DecryptingKey,
+    Key, Variant,
+};
+use crate::{cpu, error};
+use alloc::vec::Vec;
+
+pub(crate) const KEY_LEN: usize = 32;
+pub(crate) const IV_LEN: usize = BLOCK_LEN;
+
+/// Pads `plaintext` and encrypts it, returning the ciphertext.
+pub(crate) fn encrypt(
+    key: &[u8; KEY_LEN],
+    iv: &[u8; IV_LEN],
+    plaintext: &[u8],
+) -> Result<Vec<u8>, error::Unspecified> {
+    let key = Key::new(key, Variant::AES_256, cpu::features())?;
+
+    let padding_len = BLOCK_LEN - (plaintext.len() % BLOCK_LEN);
+    let mut in_out = Vec::with_capacity(plaintext.len() + padding_len);
+    in_out.extend_from_slice(plaintext);
+    in_out.resize(plaintext.len() + padding_len, padding_len as u8);
+
+    let mut chaining = Block::from(iv);
+    for chunk in in_out.chunks_exact_mut(BLOCK_LEN) {
+        let input: &[u8; BLOCK_LEN] = (&*chunk).try_into()?;
+        chaining = key.encrypt_block(Block::from(input) ^ chaining);
+        chunk.copy_from_slice(chaining.as_ref());
+    }
+    Ok(in_out)
+}
+
+/// Decrypts `ciphertext` and removes the padding, returning the plaintext.
+///
+/// No distinction is made between a malformed ciphertext and a wrong key.
+pub(crate) fn decrypt(
+    key: &[u8; KEY_LEN],
+    iv: &[u8; IV_LEN],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, error::Unspecified> {
+    if ciphertext.is_empty() || ciphertext.len() % BLOCK_LEN != 0 {
+        return Err(error::Unspecified);
+    }
+    let key = Aes256DecryptingKey::new(key);
+
+    let mut plaintext = Vec::with_capacity(ciphertext.len());
+    let mut chaining = *iv;
+    for chunk in ciphertext.chunks_exact(BLOCK_LEN) {
+        let input: [u8; BLOCK_LEN] = chunk.try_into()?;
+        let decrypted = key.decrypt_block(input);
+        plaintext.extend(decrypted.iter().zip(chaining.iter()).map(|(d, c)| d ^ c));
+        chaining = input;
+    }
+
+    // `plaintext` is non-empty because `ciphertext` is non-empty.
+    let padding_len = usize::from(plaintext[plaintext.len() - 1]);
+    if padding_len == 0 || padding_len > BLOCK_LEN {
+        return Err(error::Unspecified);
+    }
+    let unpadded_len = plaintext.len() - padding_len;
+    let bad_padding = plaintext[unpadded_len..]
+        .iter()
+        .fold(0, |acc, &b| acc | (b ^ (padding_len as u8)));
+    if bad_padding != 0 {
+        return Err(error::Unspecified);
+    }
+    plaintext.truncate(unpadded_len);
+    Ok(plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // NIST SP 800-38A Section F.2.5 (CBC-AES256.Encrypt), with the PKCS#7
+    // padding block appended to the plaintext.
+    #[test]
+    fn test_aes_256_cbc() {
+        let key = [
+            0x60, 0x3d, 0xeb, 0x10, 0x15, 0xca, 0x71, 0xbe, 0x2b, 0x73, 0xae, 0xf0, 0x85, 0x7d,
+            0x77, 0x81, 0x1f, 0x35, 0x2c, 0x07, 0x3b, 0x61, 0x08, 0xd7, 0x2d, 0x98, 0x10, 0xa3,
+            0x09, 0x14, 0xdf, 0xf4,
+        ];
+        let iv = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d,
+            0x0e, 0x0f,
+        ];
+        let plaintext = [
+            0x6b, 0xc1, 0xbe, 0xe2, 0x2e, 0x40, 0x9f, 0x96, 0xe9, 0x3d, 0x7e, 0x11, 0x73, 0x93,
+            0x17, 0x2a, 0xae, 0x2d, 0x8a, 0x57, 0x1e, 0x03, 0xac, 0x9c, 0x9e, 0xb7, 0x6f, 0xac,
+            0x45, 0xaf, 0x8e, 0x51,
+        ];
+        let expected_prefix = [
+            0xf5, 0x8c, 0x4c, 0x04, 0xd6, 0xe5, 0xf1, 0xba, 0x77, 0x9e, 0xab, 0xfb, 0x5f, 0x7b,
+            0xfb, 0xd6, 0x9c, 0xfc, 0x4e, 0x96, 0x7e, 0xdb, 0x80, 0x8d, 0x67, 0x9f, 0x77, 0x7b,
+            0xc6, 0x70, 0x2c, 0x7d,
+        ];
+
+        let ciphertext = encrypt(&key, &iv, &plaintext).unwrap();
+        assert_eq!(ciphertext.len(), plaintext.len() + BLOCK_LEN);
+        assert_eq!(&ciphertext[..plaintext.len()], &expected_prefix[..]);
+        assert_eq!(decrypt(&key, &iv, &ciphertext).unwrap(), &plaintext[..]);
+
+        // Every length of padding round-trips.
+        for len in 0..=(2 * BLOCK_LEN) {
+            let ciphertext = encrypt(&key, &iv, &plaintext[..len]).unwrap();
+            assert_eq!(decrypt(&key, &iv, &ciphertext).unwrap(), &plaintext[..len]);
+        }
+
+        // Truncated and corrupted ciphertexts are rejected.
+        assert!(decrypt(&key, &iv, &[]).is_err());
+        assert!(decrypt(&key, &iv, &ciphertext[1..]).is_err());
+        let ciphertext = encrypt(&key, &iv, &plaintext[..BLOCK_LEN]).unwrap();
+        let (first, padding) = ciphertext.split_at(BLOCK_LEN);
+        let mut chaining: [u8; BLOCK_LEN] = first.try_into().unwrap();
+        assert_eq!(decrypt(&key, &chaining, padding).unwrap(), &[]);
+        chaining[BLOCK_LEN - 1] ^= 0x80;
+        assert!(decrypt(&key, &chaining, padding).is_err());
+    }
+}
@@ -0,0 +1,275 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Portable, constant-time AES round functions.
+//!
+//! The S-box is computed arithmetically on eight bytes at a time instead of
+//! being looked up in a table, so that memory access patterns don't depend on
+//! secret data. This is slow compared to the assembly implementations and is
+//! only used where those aren't applicable.
+
+use super::super::block::BLOCK_LEN;
+
+const LSB: u64 = 0x0101_0101_0101_0101;
+
+// Repeats `b` in every byte.
+const fn splat(b: u8) -> u64 {
+    (b as u64) * LSB
+}
+
+// Multiplies each byte by x in GF(2^8).
+#[inline(always)]
+fn xtime(a: u64) -> u64 {
+    let carries = (a >> 7) & LSB;
+    ((a << 1) & !LSB) ^ (carries << 4) ^ (carries << 3) ^ (carries << 1) ^ carries
+}
+
+// Multiplies each byte of `a` by the corresponding byte of `b` in
+// GF(2^8).
+#[inline(always)]
+fn mul(mut a: u64, b: u64) -> u64 {
+    let mut r = 0;
+    for i in 0..8 {
+        let bits = (b >> i) & LSB;
+        let mask = (bits << 8).wrapping_sub(bits);
+        r ^= a & mask;
+        a = xtime(a);
+    }
+    r
+}
+
+// Rotates each byte left by `n` bits.
+#[inline(always)]
+fn rotate_bytes_left(x: u64, n: u32) -> u64 {
+    let high_mask = splat(0xffu8 << n);
+    ((x << n) & high_mask) | ((x >> (8 - n)) & !high_mask)
+}
+
+// Computes x^254 for each byte, which is the inverse of x for nonzero x, and
+// 0 for 0.
+#[inline(always)]
+fn inverse(x: u64) -> u64 {
+    let x2 = mul(x, x);
+    let x3 = mul(x2, x);
+    let x6 = mul(x3, x3);
+    let x12 = mul(x6, x6);
+    let x15 = mul(x12, x3);
+    let x30 = mul(x15, x15);
+    let x60 = mul(x30, x30);
+    let x120 = mul(x60, x60);
+    let x240 = mul(x120, x120);
+    let x252 = mul(x240, x12);
+    mul(x252, x2)
+}
+
+/// Applies the AES S-box to each byte.
+#[inline(always)]
+pub(in crate::aead) fn sub_bytes(x: u64) -> u64 {
+    let inverse = inverse(x);
+    inverse
+        ^ rotate_bytes_left(inverse, 1)
+        ^ rotate_bytes_left(inverse, 2)
+        ^ rotate_bytes_left(inverse, 3)
+        ^ rotate_bytes_left(inverse, 4)
+        ^ splat(0x63)
+}
+
+// Applies the inverse of the AES S-box to each byte.
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+#[inline(always)]
+fn inv_sub_bytes(x: u64) -> u64 {
+    inverse(
+        rotate_bytes_left(x, 1) ^ rotate_bytes_left(x, 3) ^ rotate_bytes_left(x, 6) ^ splat(0x05),
+    )
+}
+
+/// Applies ShiftRows and then MixColumns to `state`.
+#[inline(always)]
+pub(in crate::aead) fn shift_rows_mix_columns(state: [u8; BLOCK_LEN]) -> [u8; BLOCK_LEN] {
+    // The state is column-major: byte `r + 4*c` is in row `r` and column
+    // `c`. ShiftRows rotates row `r` left by `r` columns.
+    let mut out = [0u8; BLOCK_LEN];
+    for c in 0..4 {
+        let a = [
+            state[4 * c],
+            state[1 + 4 * ((c + 1) % 4)],
+            state[2 + 4 * ((c + 2) % 4)],
+            state[3 + 4 * ((c + 3) % 4)],
+        ];
+        for r in 0..4 {
+            out[r + 4 * c] = xtime_u8(a[r])
+                ^ xtime_u8(a[(r + 1) % 4])
+                ^ a[(r + 1) % 4]
+                ^ a[(r + 2) % 4]
+                ^ a[(r + 3) % 4];
+        }
+    }
+    out
+}
+
+#[inline(always)]
+fn xtime_u8(a: u8) -> u8 {
+    (a << 1) ^ (0u8.wrapping_sub(a >> 7) & 0x1b)
+}
+
+/// An expanded AES-256 key for decryption with the inverse cipher.
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+pub(in crate::aead) struct Aes256DecryptingKey {
+    round_keys: [[u8; BLOCK_LEN]; AES_256_ROUNDS + 1],
+}
+
+const AES_256_ROUNDS: usize = 14;
+
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+impl Aes256DecryptingKey {
+    pub(in crate::aead) fn new(key: &[u8; 32]) -> Self {
+        const RCON: [u8; 7] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40];
+        const NK: usize = 8;
+
+        fn sub_word(word: [u8; 4]) -> [u8; 4] {
+            // The upper four bytes are ignored.
+            let substituted = sub_bytes(u64::from(u32::from_le_bytes(word)));
+            (substituted as u32).to_le_bytes()
+        }
+
+        let mut words = [[0u8; 4]; 4 * (AES_256_ROUNDS + 1)];
+        words
+            .iter_mut()
+            .zip(key.chunks_exact(4))
+            .for_each(|(word, k)| word.copy_from_slice(k));
+        for i in NK..words.len() {
+            let mut temp = words[i - 1];
+            if i % NK == 0 {
+                temp.rotate_left(1);
+                temp = sub_word(temp);
+                temp[0] ^= RCON[(i / NK) - 1];
+            } else if i % NK == 4 {
+                temp = sub_word(temp);
+            }
+            let prev = words[i - NK];
+            words[i] = [
+                prev[0] ^ temp[0],
+                prev[1] ^ temp[1],
+                prev[2] ^ temp[2],
+                prev[3] ^ temp[3],
+            ];
+        }
+
+        let mut round_keys = [[0u8; BLOCK_LEN]; AES_256_ROUNDS + 1];
+        round_keys
+            .iter_mut()
+            .zip(words.chunks_exact(4))
+            .for_each(|(round_key, words)| {
+                round_key
+                    .chunks_exact_mut(4)
+                    .zip(words)
+                    .for_each(|(out, word)| out.copy_from_slice(word))
+            });
+        Self { round_keys }
+    }
+
+    pub(in crate::aead) fn decrypt_block(&self, block: [u8; BLOCK_LEN]) -> [u8; BLOCK_LEN] {
+        let (first, rest) = self.round_keys.split_first().unwrap();
+        let (last, middle) = rest.split_last().unwrap();
+        let mut state = xor(block, last);
+        for round_key in middle.iter().rev() {
+            state = inv_mix_columns(xor(inv_shift_rows_sub_bytes(state), round_key));
+        }
+        xor(inv_shift_rows_sub_bytes(state), first)
+    }
+}
+
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+#[inline(always)]
+fn xor(mut a: [u8; BLOCK_LEN], b: &[u8; BLOCK_LEN]) -> [u8; BLOCK_LEN] {
+    a.iter_mut().zip(b).for_each(|(a, b)| *a ^= b);
+    a
+}
+
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+#[inline(always)]
+fn inv_shift_rows_sub_bytes(state: [u8; BLOCK_LEN]) -> [u8; BLOCK_LEN] {
+    // InvShiftRows rotates row `r` right by `r` columns.
+    let mut shifted = [0u8; BLOCK_LEN];
+    for c in 0..4 {
+        for r in 0..4 {
+            shifted[r + 4 * c] = state[r + 4 * ((c + 4 - r) % 4)];
+        }
+    }
+    let (lo, hi) = shifted.split_at(8);
+    let lo = inv_sub_bytes(u64::from_le_bytes(lo.try_into().unwrap()));
+    let hi = inv_sub_bytes(u64::from_le_bytes(hi.try_into().unwrap()));
+    let mut out = [0u8; BLOCK_LEN];
+    out[..8].copy_from_slice(&lo.to_le_bytes());
+    out[8..].copy_from_slice(&hi.to_le_bytes());
+    out
+}
+
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+#[inline(always)]
+fn inv_mix_columns(state: [u8; BLOCK_LEN]) -> [u8; BLOCK_LEN] {
+    fn mul_u8(a: u8, b: u8) -> u8 {
+        mul(u64::from(a), u64::from(b)) as u8
+    }
+
+    let mut out = [0u8; BLOCK_LEN];
+    for c in 0..4 {
+        let a = &state[(4 * c)..][..4];
+        for r in 0..4 {
+            out[r + 4 * c] = mul_u8(a[r], 0x0e)
+                ^ mul_u8(a[(r + 1) % 4], 0x0b)
+                ^ mul_u8(a[(r + 2) % 4], 0x0d)
+                ^ mul_u8(a[(r + 3) % 4], 0x09);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sub_bytes() {
+        // Spot checks from the S-box table in FIPS 197.
+        let input = u64::from_le_bytes([0x00, 0x01, 0x53, 0x10, 0xff, 0x80, 0xc9, 0x7f]);
+        let expected = [0x63, 0x7c, 0xed, 0xca, 0x16, 0xcd, 0xdd, 0xd2];
+        assert_eq!(sub_bytes(input).to_le_bytes(), expected);
+    }
+
+    #[test]
+    fn test_inv_sub_bytes() {
+        for b in 0..=255u8 {
+            let input = splat(b);
+            assert_eq!(inv_sub_bytes(sub_bytes(input)), input);
+        }
+    }
+
+    // The AES-256 example in FIPS 197 Appendix C.3.
+    #[test]
+    fn test_aes_256_decrypt_block() {
+        let mut key = [0u8; 32];
+        key.iter_mut().enumerate().for_each(|(i, k)| *k = i as u8);
+        let ciphertext = [
+            0x8e, 0xa2, 0xb7, 0xca, 0x51, 0x67, 0x45, 0xbf, 0xea, 0xfc, 0x49, 0x90, 0x4b, 0x49,
+            0x60, 0x89,
+        ];
+        let expected = [
+            0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd,
+            0xee, 0xff,
+        ];
+        let key = Aes256DecryptingKey::new(&key);
+        assert_eq!(key.decrypt_block(ciphertext), expected);
+    }
+}
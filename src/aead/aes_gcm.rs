@@ -15,7 +15,9 @@
 use super::{
     aes::{self, Counter},
     block::{Block, BLOCK_LEN},
-    gcm, shift, Aad, Nonce, Tag,
+    gcm, shift,
+    vectored::{self, Keystream},
+    Aad, Direction, Nonce, Tag,
 };
 use crate::{aead, cpu, error, polyfill};
 use core::ops::RangeFrom;
@@ -26,6 +28,10 @@ pub static AES_128_GCM: aead::Algorithm = aead::Algorithm {
     init: init_128,
     seal: aes_gcm_seal,
     open: aes_gcm_open,
+    seal_vectored: aes_gcm_seal_vectored,
+    open_vectored: aes_gcm_open_vectored,
+    seal_to: aes_gcm_seal_to,
+    open_to: aes_gcm_open_to,
     id: aead::AlgorithmID::AES_128_GCM,
     max_input_len: AES_GCM_MAX_INPUT_LEN,
 };
@@ -36,6 +42,10 @@ pub static AES_256_GCM: aead::Algorithm = aead::Algorithm {
     init: init_256,
     seal: aes_gcm_seal,
     open: aes_gcm_open,
+    seal_vectored: aes_gcm_seal_vectored,
+    open_vectored: aes_gcm_open_vectored,
+    seal_to: aes_gcm_seal_to,
+    open_to: aes_gcm_open_to,
     id: aead::AlgorithmID::AES_256_GCM,
     max_input_len: AES_GCM_MAX_INPUT_LEN,
 };
@@ -225,6 +235,304 @@ fn aes_gcm_open(
     finish(aes_key, auth, tag_iv, aad_len, total_in_out_len)
 }
 
+fn aes_gcm_seal_to(
+    key: &aead::KeyInner,
+    nonce: Nonce,
+    aad: Aad<&[u8]>,
+    input: &[u8],
+    output: &mut [u8],
+) -> Tag {
+    aes_gcm_to(key, nonce, aad, input, output, Direction::Sealing)
+}
+
+fn aes_gcm_open_to(
+    key: &aead::KeyInner,
+    nonce: Nonce,
+    aad: Aad<&[u8]>,
+    input: &[u8],
+    output: &mut [u8],
+) -> Tag {
+    aes_gcm_to(key, nonce, aad, input, output, Direction::Opening)
+}
+
+// Encrypts or decrypts `input` into `output`, which must have the same
+// length.
+fn aes_gcm_to(
+    key: &aead::KeyInner,
+    nonce: Nonce,
+    aad: Aad<&[u8]>,
+    input: &[u8],
+    output: &mut [u8],
+    direction: Direction,
+) -> Tag {
+    let Key { gcm_key, aes_key } = match key {
+        aead::KeyInner::AesGcm(key) => key,
+        _ => unreachable!(),
+    };
+    assert_eq!(input.len(), output.len());
+
+    let mut ctr = Counter::one(nonce);
+    let tag_iv = ctr.increment();
+
+    let total_len = input.len();
+    let aad_len = aad.0.len();
+    let mut auth = gcm::Context::new(gcm_key, aad);
+
+    #[cfg(target_arch = "x86_64")]
+    let (input, output) = {
+        if !aes_key.is_aes_hw() || !auth.is_avx2() {
+            (input, output)
+        } else {
+            use crate::c;
+            prefixed_extern! {
+                fn aesni_gcm_encrypt(
+                    input: *const u8,
+                    output: *mut u8,
+                    len: c::size_t,
+                    key: &aes::AES_KEY,
+                    ivec: &mut Counter,
+                    gcm: &mut gcm::ContextInner,
+                ) -> c::size_t;
+                fn aesni_gcm_decrypt(
+                    input: *const u8,
+                    output: *mut u8,
+                    len: c::size_t,
+                    key: &aes::AES_KEY,
+                    ivec: &mut Counter,
+                    gcm: &mut gcm::ContextInner,
+                ) -> c::size_t;
+            }
+            let f = match direction {
+                Direction::Sealing => aesni_gcm_encrypt,
+                Direction::Opening => aesni_gcm_decrypt,
+            };
+            let processed = unsafe {
+                f(
+                    input.as_ptr(),
+                    output.as_mut_ptr(),
+                    input.len(),
+                    aes_key.inner_less_safe(),
+                    &mut ctr,
+                    auth.inner(),
+                )
+            };
+            (&input[processed..], &mut output[processed..])
+        }
+    };
+
+    let whole_len = input.len() - (input.len() % BLOCK_LEN);
+    let (input_whole, input_remainder) = input.split_at(whole_len);
+    let (output_whole, output_remainder) = output.split_at_mut(whole_len);
+
+    // Each chunk is copied to the output and then encrypted in place there,
+    // while it is still in the cache.
+    for (input_chunk, output_chunk) in input_whole
+        .chunks(CHUNK_BLOCKS * BLOCK_LEN)
+        .zip(output_whole.chunks_mut(CHUNK_BLOCKS * BLOCK_LEN))
+    {
+        if direction == Direction::Opening {
+            auth.update_blocks(input_chunk);
+        }
+        output_chunk.copy_from_slice(input_chunk);
+        aes_key.ctr32_encrypt_within(output_chunk, 0.., &mut ctr);
+        if direction == Direction::Sealing {
+            auth.update_blocks(output_chunk);
+        }
+    }
+
+    if !input_remainder.is_empty() {
+        let mut block = Block::zero();
+        block.overwrite_part_at(0, input_remainder);
+        if direction == Direction::Opening {
+            auth.update_block(block);
+        }
+        let mut block = aes_key.encrypt_iv_xor_block(ctr.into(), block);
+        block.zero_from(input_remainder.len());
+        if direction == Direction::Sealing {
+            auth.update_block(block);
+        }
+        output_remainder.copy_from_slice(&block.as_ref()[..input_remainder.len()]);
+    }
+
+    finish(aes_key, auth, tag_iv, aad_len, total_len)
+}
+
+fn aes_gcm_seal_vectored(
+    key: &aead::KeyInner,
+    nonce: Nonce,
+    aad: &[&[u8]],
+    in_out: &mut [&mut [u8]],
+) -> Tag {
+    aes_gcm_vectored(key, nonce, aad, in_out, Direction::Sealing)
+}
+
+fn aes_gcm_open_vectored(
+    key: &aead::KeyInner,
+    nonce: Nonce,
+    aad: &[&[u8]],
+    in_out: &mut [&mut [u8]],
+) -> Tag {
+    aes_gcm_vectored(key, nonce, aad, in_out, Direction::Opening)
+}
+
+fn aes_gcm_vectored(
+    key: &aead::KeyInner,
+    nonce: Nonce,
+    aad: &[&[u8]],
+    in_out: &mut [&mut [u8]],
+    direction: Direction,
+) -> Tag {
+    let Key { gcm_key, aes_key } = match key {
+        aead::KeyInner::AesGcm(key) => key,
+        _ => unreachable!(),
+    };
+
+    let mut ctr = Counter::one(nonce);
+    let tag_iv = ctr.increment();
+
+    let mut auth = gcm::Context::new(gcm_key, Aad::from(&[]));
+    let mut ghash = GhashBuffer::new();
+    let mut aad_len = 0;
+    for part in aad {
+        ghash.update(&mut auth, part);
+        aad_len += part.len();
+    }
+    ghash.pad(&mut auth);
+
+    let mut authenticate = |ciphertext: &[u8]| ghash.update(&mut auth, ciphertext);
+    let mut keystream = Keystream::<BLOCK_LEN>::new();
+    for fragment in in_out.iter_mut() {
+        let (partial, whole, remainder) = vectored::split_fragment(fragment, &keystream);
+        vectored::crypt(direction, partial, &mut authenticate, |partial| {
+            keystream.xor(partial)
+        });
+        for chunk in whole.chunks_mut(CHUNK_BLOCKS * BLOCK_LEN) {
+            vectored::crypt(direction, chunk, &mut authenticate, |chunk| {
+                aes_key.ctr32_encrypt_within(chunk, 0.., &mut ctr)
+            });
+        }
+        if !remainder.is_empty() {
+            let block = aes_key.encrypt_iv_xor_block(ctr.increment(), Block::zero());
+            keystream.refill(*block.as_ref());
+            vectored::crypt(direction, remainder, &mut authenticate, |remainder| {
+                keystream.xor(remainder)
+            });
+        }
+    }
+    ghash.pad(&mut auth);
+
+    finish(aes_key, auth, tag_iv, aad_len, vectored::total_len(in_out))
+}
+
+// The AES-GCM tag for an empty plaintext, where the AAD is the concatenation
+// of the elements of `aad`. This is GMAC with the input given in parts.
+pub(super) fn gmac_parts(key: &aead::KeyInner, nonce: Nonce, aad: &[&[u8]]) -> Tag {
+    let Key { gcm_key, aes_key } = match key {
+        aead::KeyInner::AesGcm(key) => key,
+        _ => unreachable!(),
+    };
+
+    let mut ctr = Counter::one(nonce);
+    let tag_iv = ctr.increment();
+
+    let mut auth = gcm::Context::new(gcm_key, Aad::from(&[]));
+    let mut ghash = GhashBuffer::new();
+    let mut aad_len = 0;
+    for part in aad {
+        ghash.update(&mut auth, part);
+        aad_len += part.len();
+    }
+    ghash.pad(&mut auth);
+
+    finish(aes_key, auth, tag_iv, aad_len, 0)
+}
+
+/// The state of a GMAC computation: AES-GCM with an empty plaintext, where
+/// the input is the additional authenticated data.
+pub(super) struct Gmac<'k> {
+    aes_key: &'k aes::Key,
+    auth: gcm::Context,
+    ghash: GhashBuffer,
+    tag_iv: aes::Iv,
+    len: usize,
+}
+
+impl<'k> Gmac<'k> {
+    pub(super) fn new(key: &'k aead::KeyInner, nonce: Nonce) -> Self {
+        let Key { gcm_key, aes_key } = match key {
+            aead::KeyInner::AesGcm(key) => key,
+            _ => unreachable!(),
+        };
+        Self {
+            aes_key,
+            auth: gcm::Context::new(gcm_key, Aad::from(&[])),
+            ghash: GhashBuffer::new(),
+            tag_iv: Counter::one(nonce).increment(),
+            len: 0,
+        }
+    }
+
+    pub(super) fn update(&mut self, input: &[u8]) {
+        self.ghash.update(&mut self.auth, input);
+        self.len += input.len();
+    }
+
+    pub(super) fn finish(mut self) -> Tag {
+        self.ghash.pad(&mut self.auth);
+        finish(self.aes_key, self.auth, self.tag_iv, self.len, 0)
+    }
+}
+
+// Buffers the input to GHASH so that it can be given in pieces that aren't
+// multiples of the block length.
+struct GhashBuffer {
+    pending: [u8; BLOCK_LEN],
+    pending_len: usize,
+}
+
+impl GhashBuffer {
+    fn new() -> Self {
+        Self {
+            pending: [0; BLOCK_LEN],
+            pending_len: 0,
+        }
+    }
+
+    fn update(&mut self, auth: &mut gcm::Context, input: &[u8]) {
+        let mut input = input;
+        if self.pending_len > 0 {
+            let n = core::cmp::min(BLOCK_LEN - self.pending_len, input.len());
+            let (head, rest) = input.split_at(n);
+            self.pending[self.pending_len..][..n].copy_from_slice(head);
+            self.pending_len += n;
+            input = rest;
+            if self.pending_len < BLOCK_LEN {
+                return;
+            }
+            auth.update_block(Block::from(&self.pending));
+            self.pending_len = 0;
+        }
+
+        let whole_len = input.len() - (input.len() % BLOCK_LEN);
+        let (whole, remainder) = input.split_at(whole_len);
+        if !whole.is_empty() {
+            auth.update_blocks(whole);
+        }
+        self.pending[..remainder.len()].copy_from_slice(remainder);
+        self.pending_len = remainder.len();
+    }
+
+    // Pads any pending input with zeros to a whole block.
+    fn pad(&mut self, auth: &mut gcm::Context) {
+        if self.pending_len > 0 {
+            let mut block = Block::zero();
+            block.overwrite_part_at(0, &self.pending[..self.pending_len]);
+            auth.update_block(block);
+            self.pending_len = 0;
+        }
+    }
+}
+
 fn finish(
     aes_key: &aes::Key,
     mut gcm_ctx: gcm::Context,
@@ -0,0 +1,296 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! The [aes128-gcm@openssh.com] and [aes256-gcm@openssh.com] AEAD-ish
+//! constructs, i.e. AES-GCM for the SSH transport as specified in [RFC 5647].
+//!
+//! This should only be used by SSH implementations. Its API follows
+//! `ring::aead::chacha20_poly1305_openssh` as closely as the construct allows,
+//! so that an SSH implementation can switch between the two with few changes
+//! to its packet processing. `OpeningKey::decrypt_packet_length()` and
+//! `OpeningKey::open_in_place()` have the same signatures. The others can't:
+//!
+//! * `SealingKey::new()` and `OpeningKey::new()` take the algorithm, since
+//!   there are two key lengths, and the initial IV and sequence number, since
+//!   the nonce is derived from the IV agreed during key exchange rather than
+//!   from the sequence number alone. They fail if the key material has the
+//!   wrong length.
+//! * `SealingKey::seal_in_place()` takes `&mut self` and returns a `Result`.
+//!   The invocation counter is 64 bits but the sequence number it is derived
+//!   from wraps around after 2**32 packets, so the key counts the packets it
+//!   has sealed and fails once the nonces would repeat. It also fails, instead
+//!   of panicking, if the input is shorter than `PACKET_LENGTH_LEN`.
+//!
+//! Unlike in chacha20-poly1305@openssh.com, the `packet_length` field is not
+//! encrypted; it is authenticated as the additional authenticated data. The
+//! nonce is the 4-byte fixed field of the initial IV followed by the 8-byte
+//! invocation counter, which is incremented for every packet. The term
+//! `plaintext` is used as a shorthand for
+//! `padding_length||payload||random padding`, which are defined in
+//! [RFC 4253].
+//!
+//! [aes128-gcm@openssh.com]:
+//!    http://cvsweb.openbsd.org/cgi-bin/cvsweb/src/usr.bin/ssh/PROTOCOL?annotate=HEAD
+//! [aes256-gcm@openssh.com]:
+//!    http://cvsweb.openbsd.org/cgi-bin/cvsweb/src/usr.bin/ssh/PROTOCOL?annotate=HEAD
+//! [RFC 5647]: https://tools.ietf.org/html/rfc5647
+//! [RFC 4253]: https://tools.ietf.org/html/rfc4253
+
+use super::{
+    aes_gcm, less_safe_key::seal_in_place_separate_tag_, Aad, Algorithm, LessSafeKey, Nonce, Tag,
+    NONCE_LEN,
+};
+use crate::error;
+
+/// A key for sealing packets.
+pub struct SealingKey {
+    key: Key,
+    sealed_packets: u64,
+}
+
+impl SealingKey {
+    /// Constructs a new `SealingKey`.
+    ///
+    /// `algorithm` must be `ring::aead::AES_128_GCM` or
+    /// `ring::aead::AES_256_GCM` and `key_material` must be of the
+    /// corresponding length. `iv` is the initial IV derived during key
+    /// exchange, and `initial_sequence_number` is the sequence number of the
+    /// first packet that will be sealed with the key.
+    pub fn new(
+        algorithm: &'static Algorithm,
+        key_material: &[u8],
+        iv: &[u8; IV_LEN],
+        initial_sequence_number: u32,
+    ) -> Result<Self, error::Unspecified> {
+        Ok(Self {
+            key: Key::new(algorithm, key_material, iv, initial_sequence_number)?,
+            sealed_packets: 0,
+        })
+    }
+
+    /// Seals (encrypts and signs) a packet.
+    ///
+    /// On input, `plaintext_in_ciphertext_out` must contain the unencrypted
+    /// `packet_length||plaintext` where `plaintext` is the
+    /// `padding_length||payload||random padding`. It will be overwritten by
+    /// `packet_length||ciphertext`, where `packet_length` is left
+    /// unencrypted.
+    ///
+    /// The nonce is derived from `sequence_number`, which wraps around after
+    /// 2**32 packets, so this fails without sealing the packet once the key
+    /// has sealed 2**32 packets. The connection must be rekeyed before then.
+    /// It also fails if `plaintext_in_ciphertext_out` is shorter than
+    /// `PACKET_LENGTH_LEN`; such input doesn't count towards the limit.
+    pub fn seal_in_place(
+        &mut self,
+        sequence_number: u32,
+        plaintext_in_ciphertext_out: &mut [u8],
+        tag_out: &mut [u8; TAG_LEN],
+    ) -> Result<(), error::Unspecified> {
+        if plaintext_in_ciphertext_out.len() < PACKET_LENGTH_LEN {
+            return Err(error::Unspecified);
+        }
+        if self.sealed_packets >= MAX_SEALED_PACKETS {
+            return Err(error::Unspecified);
+        }
+        self.sealed_packets += 1;
+
+        let (packet_length, plaintext_in_ciphertext_out) =
+            plaintext_in_ciphertext_out.split_at_mut(PACKET_LENGTH_LEN);
+
+        // SSH packets are much shorter than the maximum input length of
+        // AES-GCM, so this can't fail.
+        let Tag(tag) = seal_in_place_separate_tag_(
+            &self.key.aead_key,
+            self.key.nonce(sequence_number),
+            Aad::from(&*packet_length),
+            plaintext_in_ciphertext_out,
+        )
+        .unwrap();
+        *tag_out = tag;
+        Ok(())
+    }
+}
+
+/// A key for opening packets.
+pub struct OpeningKey {
+    key: Key,
+}
+
+impl OpeningKey {
+    /// Constructs a new `OpeningKey`.
+    ///
+    /// See `SealingKey::new()` for the requirements on the arguments; here
+    /// `initial_sequence_number` is the sequence number of the first packet
+    /// that will be opened with the key.
+    pub fn new(
+        algorithm: &'static Algorithm,
+        key_material: &[u8],
+        iv: &[u8; IV_LEN],
+        initial_sequence_number: u32,
+    ) -> Result<Self, error::Unspecified> {
+        Ok(Self {
+            key: Key::new(algorithm, key_material, iv, initial_sequence_number)?,
+        })
+    }
+
+    /// Returns the unauthenticated packet length.
+    ///
+    /// The packet length isn't encrypted in this construct so it is returned
+    /// as-is; this exists for compatibility with
+    /// `chacha20_poly1305_openssh::OpeningKey::decrypt_packet_length()`.
+    /// Importantly, the result won't be authenticated until `open_in_place`
+    /// is called.
+    pub fn decrypt_packet_length(
+        &self,
+        _sequence_number: u32,
+        encrypted_packet_length: [u8; PACKET_LENGTH_LEN],
+    ) -> [u8; PACKET_LENGTH_LEN] {
+        encrypted_packet_length
+    }
+
+    /// Opens (authenticates and decrypts) a packet.
+    ///
+    /// `ciphertext_in_plaintext_out` must be of the form
+    /// `packet_length||ciphertext` where `ciphertext` is the encrypted
+    /// `plaintext`. When the function succeeds the ciphertext is replaced by
+    /// the plaintext and the result is `Ok(plaintext)`, where `plaintext` is
+    /// `&ciphertext_in_plaintext_out[PACKET_LENGTH_LEN..]`; otherwise the
+    /// contents of `ciphertext_in_plaintext_out` are unspecified and must not
+    /// be used.
+    pub fn open_in_place<'a>(
+        &self,
+        sequence_number: u32,
+        ciphertext_in_plaintext_out: &'a mut [u8],
+        tag: &[u8; TAG_LEN],
+    ) -> Result<&'a [u8], error::Unspecified> {
+        if ciphertext_in_plaintext_out.len() < PACKET_LENGTH_LEN {
+            return Err(error::Unspecified);
+        }
+        let (packet_length, ciphertext_in_plaintext_out) =
+            ciphertext_in_plaintext_out.split_at_mut(PACKET_LENGTH_LEN);
+
+        let plaintext = self.key.aead_key.open_in_place_separate_tag(
+            self.key.nonce(sequence_number),
+            Aad::from(&*packet_length),
+            Tag::from(*tag),
+            ciphertext_in_plaintext_out,
+            0..,
+        )?;
+        Ok(plaintext)
+    }
+}
+
+struct Key {
+    aead_key: LessSafeKey,
+    fixed: [u8; FIXED_LEN],
+    initial_invocation_counter: u64,
+    initial_sequence_number: u32,
+}
+
+impl Key {
+    fn new(
+        algorithm: &'static Algorithm,
+        key_material: &[u8],
+        iv: &[u8; IV_LEN],
+        initial_sequence_number: u32,
+    ) -> Result<Self, error::Unspecified> {
+        if algorithm != &aes_gcm::AES_128_GCM && algorithm != &aes_gcm::AES_256_GCM {
+            return Err(error::Unspecified);
+        }
+        let aead_key = LessSafeKey::new_(algorithm, key_material)?;
+
+        let (fixed, invocation_counter) = iv.split_at(FIXED_LEN);
+        Ok(Self {
+            aead_key,
+            fixed: fixed.try_into()?,
+            initial_invocation_counter: u64::from_be_bytes(invocation_counter.try_into()?),
+            initial_sequence_number,
+        })
+    }
+
+    fn nonce(&self, sequence_number: u32) -> Nonce {
+        // The invocation counter is incremented once per packet, as is the
+        // sequence number. Because the key must be replaced before the
+        // sequence number wraps around, this is a one-to-one mapping.
+        let packets = sequence_number.wrapping_sub(self.initial_sequence_number);
+        let invocation_counter = self
+            .initial_invocation_counter
+            .wrapping_add(u64::from(packets));
+
+        let mut nonce = [0u8; NONCE_LEN];
+        let (fixed, counter) = nonce.split_at_mut(FIXED_LEN);
+        fixed.copy_from_slice(&self.fixed);
+        counter.copy_from_slice(&invocation_counter.to_be_bytes());
+        Nonce::assume_unique_for_key(nonce)
+    }
+}
+
+/// The length of the initial IV.
+pub const IV_LEN: usize = NONCE_LEN;
+
+// The length of the fixed field of the IV; the rest is the invocation counter.
+const FIXED_LEN: usize = 4;
+
+// The number of distinct nonces that `Key::nonce()` can produce.
+const MAX_SEALED_PACKETS: u64 = 1 << 32;
+
+/// The length in bytes of the `packet_length` field in a SSH packet.
+pub const PACKET_LENGTH_LEN: usize = 4; // 32 bits
+
+/// The length in bytes of an authentication tag.
+pub const TAG_LEN: usize = super::TAG_LEN;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sealing_key_packet_limit() {
+        let mut key =
+            SealingKey::new(&aes_gcm::AES_128_GCM, &[0; 16], &[0; IV_LEN], 0xffff_fffe).unwrap();
+        key.sealed_packets = MAX_SEALED_PACKETS - 2;
+
+        let mut tag = [0u8; TAG_LEN];
+        let mut in_out = [0u8; PACKET_LENGTH_LEN + 16];
+        assert!(key
+            .seal_in_place(0xffff_fffe, &mut in_out, &mut tag)
+            .is_ok());
+        assert!(key
+            .seal_in_place(0xffff_ffff, &mut in_out, &mut tag)
+            .is_ok());
+
+        // The next sequence number, 0, would reuse the first nonce.
+        let mut in_out = [1u8; PACKET_LENGTH_LEN + 16];
+        assert!(key.seal_in_place(0, &mut in_out, &mut tag).is_err());
+        assert_eq!(in_out, [1u8; PACKET_LENGTH_LEN + 16]);
+    }
+
+    #[test]
+    fn test_sealing_key_short_packet() {
+        let mut key = SealingKey::new(&aes_gcm::AES_128_GCM, &[0; 16], &[0; IV_LEN], 0).unwrap();
+
+        let mut tag = [0u8; TAG_LEN];
+        for len in 0..PACKET_LENGTH_LEN {
+            let mut in_out = [0u8; PACKET_LENGTH_LEN];
+            assert!(key.seal_in_place(0, &mut in_out[..len], &mut tag).is_err());
+        }
+
+        // Rejected packets don't use up a nonce.
+        assert_eq!(key.sealed_packets, 0);
+        let mut in_out = [0u8; PACKET_LENGTH_LEN];
+        assert!(key.seal_in_place(0, &mut in_out, &mut tag).is_ok());
+        assert_eq!(key.sealed_packets, 1);
+    }
+}
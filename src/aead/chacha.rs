@@ -174,6 +174,17 @@ impl Counter {
         iv
     }
 
+    /// Returns the counter for the next `blocks` blocks and advances past
+    /// them.
+    ///
+    /// This is "less safe" because the caller must ensure the returned
+    /// counter is used for at most `blocks` blocks.
+    pub(super) fn increment_by_less_safe(&mut self, blocks: u32) -> Self {
+        let current = Self(self.0);
+        self.0[0] += blocks;
+        current
+    }
+
     /// This is "less safe" because it hands off management of the counter to
     /// the caller.
     #[cfg(any(
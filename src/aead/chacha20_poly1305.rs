@@ -14,7 +14,9 @@
 
 use super::{
     chacha::{self, Counter, Iv},
-    poly1305, Aad, Nonce, Tag,
+    poly1305,
+    vectored::{self, Keystream},
+    Aad, Direction, Nonce, Tag,
 };
 use crate::{aead, cpu, endian::*, error, polyfill};
 use core::ops::RangeFrom;
@@ -29,6 +31,10 @@ pub static CHACHA20_POLY1305: aead::Algorithm = aead::Algorithm {
     init: chacha20_poly1305_init,
     seal: chacha20_poly1305_seal,
     open: chacha20_poly1305_open,
+    seal_vectored: chacha20_poly1305_seal_vectored,
+    open_vectored: chacha20_poly1305_open_vectored,
+    seal_to: chacha20_poly1305_seal_to,
+    open_to: chacha20_poly1305_open_to,
     id: aead::AlgorithmID::CHACHA20_POLY1305,
     max_input_len: super::max_input_len(64, 1),
 };
@@ -57,68 +63,23 @@ fn chacha20_poly1305_seal(
     };
 
     #[cfg(any(target_arch = "aarch64", target_arch = "x86_64"))]
-    {
-        if cpu::intel::SSE41.available(chacha20_key.cpu_features())
-            || cpu::arm::NEON.available(chacha20_key.cpu_features())
-        {
-            // XXX: BoringSSL uses `alignas(16)` on `key` instead of on the
-            // structure, but Rust can't do that yet; see
-            // https://github.com/rust-lang/rust/issues/73557.
-            //
-            // Keep in sync with the anonymous struct of BoringSSL's
-            // `chacha20_poly1305_seal_data`.
-            #[repr(align(16), C)]
-            #[derive(Clone, Copy)]
-            struct seal_data_in {
-                key: [u32; chacha::KEY_LEN / 4],
-                counter: u32,
-                nonce: [u8; super::NONCE_LEN],
-                extra_ciphertext: *const u8,
-                extra_ciphertext_len: usize,
-            }
-
-            let mut data = InOut {
-                input: seal_data_in {
-                    key: *chacha20_key.words_less_safe(),
-                    counter: 0,
-                    nonce: *nonce.as_ref(),
-                    extra_ciphertext: core::ptr::null(),
-                    extra_ciphertext_len: 0,
-                },
-            };
-
-            // Encrypts `plaintext_len` bytes from `plaintext` and writes them to `out_ciphertext`.
-            prefixed_extern! {
-                fn chacha20_poly1305_seal(
-                    out_ciphertext: *mut u8,
-                    plaintext: *const u8,
-                    plaintext_len: usize,
-                    ad: *const u8,
-                    ad_len: usize,
-                    data: &mut InOut<seal_data_in>,
-                );
-            }
-
-            let out = unsafe {
-                chacha20_poly1305_seal(
-                    in_out.as_mut_ptr(),
-                    in_out.as_ptr(),
-                    in_out.len(),
-                    aad.as_ref().as_ptr(),
-                    aad.as_ref().len(),
-                    &mut data,
-                );
-                &data.out
-            };
-
-            return Tag(out.tag);
-        }
+    if has_integrated(chacha20_key) {
+        return unsafe {
+            seal_integrated(
+                chacha20_key,
+                nonce,
+                aad.as_ref(),
+                in_out.as_ptr(),
+                in_out.as_mut_ptr(),
+                in_out.len(),
+            )
+        };
     }
 
     let mut counter = Counter::zero(nonce);
     let mut auth = {
         let key = derive_poly1305_key(chacha20_key, counter.increment());
-        poly1305::Context::from_key(key)
+        poly1305::Context::new(key)
     };
 
     poly1305_update_padded_16(&mut auth, aad.as_ref());
@@ -140,64 +101,23 @@ fn chacha20_poly1305_open(
     };
 
     #[cfg(any(target_arch = "aarch64", target_arch = "x86_64"))]
-    {
-        if cpu::intel::SSE41.available(chacha20_key.cpu_features())
-            || cpu::arm::NEON.available(chacha20_key.cpu_features())
-        {
-            // XXX: BoringSSL uses `alignas(16)` on `key` instead of on the
-            // structure, but Rust can't do that yet; see
-            // https://github.com/rust-lang/rust/issues/73557.
-            //
-            // Keep in sync with the anonymous struct of BoringSSL's
-            // `chacha20_poly1305_open_data`.
-            #[derive(Copy, Clone)]
-            #[repr(align(16), C)]
-            struct open_data_in {
-                key: [u32; chacha::KEY_LEN / 4],
-                counter: u32,
-                nonce: [u8; super::NONCE_LEN],
-            }
-
-            let mut data = InOut {
-                input: open_data_in {
-                    key: *chacha20_key.words_less_safe(),
-                    counter: 0,
-                    nonce: *nonce.as_ref(),
-                },
-            };
-
-            // Decrypts `plaintext_len` bytes from `ciphertext` and writes them to `out_plaintext`.
-            prefixed_extern! {
-                fn chacha20_poly1305_open(
-                    out_plaintext: *mut u8,
-                    ciphertext: *const u8,
-                    plaintext_len: usize,
-                    ad: *const u8,
-                    ad_len: usize,
-                    data: &mut InOut<open_data_in>,
-                );
-            }
-
-            let out = unsafe {
-                chacha20_poly1305_open(
-                    in_out.as_mut_ptr(),
-                    in_out.as_ptr().add(src.start),
-                    in_out.len() - src.start,
-                    aad.as_ref().as_ptr(),
-                    aad.as_ref().len(),
-                    &mut data,
-                );
-                &data.out
-            };
-
-            return Tag(out.tag);
-        }
+    if has_integrated(chacha20_key) {
+        return unsafe {
+            open_integrated(
+                chacha20_key,
+                nonce,
+                aad.as_ref(),
+                in_out.as_ptr().add(src.start),
+                in_out.as_mut_ptr(),
+                in_out.len() - src.start,
+            )
+        };
     }
 
     let mut counter = Counter::zero(nonce);
     let mut auth = {
         let key = derive_poly1305_key(chacha20_key, counter.increment());
-        poly1305::Context::from_key(key)
+        poly1305::Context::new(key)
     };
 
     poly1305_update_padded_16(&mut auth, aad.as_ref());
@@ -206,6 +126,282 @@ fn chacha20_poly1305_open(
     finish(auth, aad.as_ref().len(), in_out[src].len())
 }
 
+fn chacha20_poly1305_seal_to(
+    key: &aead::KeyInner,
+    nonce: Nonce,
+    aad: Aad<&[u8]>,
+    input: &[u8],
+    output: &mut [u8],
+) -> Tag {
+    chacha20_poly1305_to(key, nonce, aad, input, output, Direction::Sealing)
+}
+
+fn chacha20_poly1305_open_to(
+    key: &aead::KeyInner,
+    nonce: Nonce,
+    aad: Aad<&[u8]>,
+    input: &[u8],
+    output: &mut [u8],
+) -> Tag {
+    chacha20_poly1305_to(key, nonce, aad, input, output, Direction::Opening)
+}
+
+// Encrypts or decrypts `input` into `output`, which must have the same
+// length.
+fn chacha20_poly1305_to(
+    key: &aead::KeyInner,
+    nonce: Nonce,
+    aad: Aad<&[u8]>,
+    input: &[u8],
+    output: &mut [u8],
+    direction: Direction,
+) -> Tag {
+    let chacha20_key = match key {
+        aead::KeyInner::ChaCha20Poly1305(key) => key,
+        _ => unreachable!(),
+    };
+    assert_eq!(input.len(), output.len());
+
+    // `input` and `output` can't overlap since `output` is borrowed mutably.
+    #[cfg(any(target_arch = "aarch64", target_arch = "x86_64"))]
+    if has_integrated(chacha20_key) {
+        let f = match direction {
+            Direction::Sealing => seal_integrated,
+            Direction::Opening => open_integrated,
+        };
+        return unsafe {
+            f(
+                chacha20_key,
+                nonce,
+                aad.as_ref(),
+                input.as_ptr(),
+                output.as_mut_ptr(),
+                input.len(),
+            )
+        };
+    }
+
+    let mut counter = Counter::zero(nonce);
+    let mut auth = {
+        let key = derive_poly1305_key(chacha20_key, counter.increment());
+        poly1305::Context::new(key)
+    };
+    poly1305_update_padded_16(&mut auth, aad.as_ref());
+
+    // Each chunk is copied to the output and then encrypted in place there,
+    // while it is still in the cache.
+    for (input_chunk, output_chunk) in input
+        .chunks(CHUNK_BLOCKS * CHACHA20_BLOCK_LEN)
+        .zip(output.chunks_mut(CHUNK_BLOCKS * CHACHA20_BLOCK_LEN))
+    {
+        if direction == Direction::Opening {
+            auth.update(input_chunk);
+        }
+        output_chunk.copy_from_slice(input_chunk);
+        let blocks = (output_chunk.len() + CHACHA20_BLOCK_LEN - 1) / CHACHA20_BLOCK_LEN;
+        let chunk_counter = counter.increment_by_less_safe(u32::try_from(blocks).unwrap());
+        chacha20_key.encrypt_in_place(chunk_counter, output_chunk);
+        if direction == Direction::Sealing {
+            auth.update(output_chunk);
+        }
+    }
+    poly1305_pad_16(&mut auth, input.len());
+
+    finish(auth, aad.as_ref().len(), input.len())
+}
+
+const CHUNK_BLOCKS: usize = 3 * 1024 / CHACHA20_BLOCK_LEN;
+
+#[cfg(any(target_arch = "aarch64", target_arch = "x86_64"))]
+fn has_integrated(key: &chacha::Key) -> bool {
+    cpu::intel::SSE41.available(key.cpu_features()) || cpu::arm::NEON.available(key.cpu_features())
+}
+
+/// Seals the `len` bytes at `input`, writing the ciphertext to `output`.
+///
+/// # Safety
+///
+/// `input` and `output` must each be valid for `len` bytes, and must either
+/// be equal or not overlap.
+#[cfg(any(target_arch = "aarch64", target_arch = "x86_64"))]
+unsafe fn seal_integrated(
+    key: &chacha::Key,
+    nonce: Nonce,
+    aad: &[u8],
+    input: *const u8,
+    output: *mut u8,
+    len: usize,
+) -> Tag {
+    // XXX: BoringSSL uses `alignas(16)` on `key` instead of on the
+    // structure, but Rust can't do that yet; see
+    // https://github.com/rust-lang/rust/issues/73557.
+    //
+    // Keep in sync with the anonymous struct of BoringSSL's
+    // `chacha20_poly1305_seal_data`.
+    #[repr(align(16), C)]
+    #[derive(Clone, Copy)]
+    struct seal_data_in {
+        key: [u32; chacha::KEY_LEN / 4],
+        counter: u32,
+        nonce: [u8; super::NONCE_LEN],
+        extra_ciphertext: *const u8,
+        extra_ciphertext_len: usize,
+    }
+
+    let mut data = InOut {
+        input: seal_data_in {
+            key: *key.words_less_safe(),
+            counter: 0,
+            nonce: *nonce.as_ref(),
+            extra_ciphertext: core::ptr::null(),
+            extra_ciphertext_len: 0,
+        },
+    };
+
+    // Encrypts `plaintext_len` bytes from `plaintext` and writes them to `out_ciphertext`.
+    prefixed_extern! {
+        fn chacha20_poly1305_seal(
+            out_ciphertext: *mut u8,
+            plaintext: *const u8,
+            plaintext_len: usize,
+            ad: *const u8,
+            ad_len: usize,
+            data: &mut InOut<seal_data_in>,
+        );
+    }
+
+    chacha20_poly1305_seal(output, input, len, aad.as_ptr(), aad.len(), &mut data);
+    Tag(data.out.tag)
+}
+
+/// Opens the `len` bytes at `input`, writing the plaintext to `output`.
+///
+/// # Safety
+///
+/// `input` and `output` must each be valid for `len` bytes. If they overlap
+/// then `output` must not be after `input`.
+#[cfg(any(target_arch = "aarch64", target_arch = "x86_64"))]
+unsafe fn open_integrated(
+    key: &chacha::Key,
+    nonce: Nonce,
+    aad: &[u8],
+    input: *const u8,
+    output: *mut u8,
+    len: usize,
+) -> Tag {
+    // XXX: BoringSSL uses `alignas(16)` on `key` instead of on the
+    // structure, but Rust can't do that yet; see
+    // https://github.com/rust-lang/rust/issues/73557.
+    //
+    // Keep in sync with the anonymous struct of BoringSSL's
+    // `chacha20_poly1305_open_data`.
+    #[derive(Copy, Clone)]
+    #[repr(align(16), C)]
+    struct open_data_in {
+        key: [u32; chacha::KEY_LEN / 4],
+        counter: u32,
+        nonce: [u8; super::NONCE_LEN],
+    }
+
+    let mut data = InOut {
+        input: open_data_in {
+            key: *key.words_less_safe(),
+            counter: 0,
+            nonce: *nonce.as_ref(),
+        },
+    };
+
+    // Decrypts `plaintext_len` bytes from `ciphertext` and writes them to `out_plaintext`.
+    prefixed_extern! {
+        fn chacha20_poly1305_open(
+            out_plaintext: *mut u8,
+            ciphertext: *const u8,
+            plaintext_len: usize,
+            ad: *const u8,
+            ad_len: usize,
+            data: &mut InOut<open_data_in>,
+        );
+    }
+
+    chacha20_poly1305_open(output, input, len, aad.as_ptr(), aad.len(), &mut data);
+    Tag(data.out.tag)
+}
+
+fn chacha20_poly1305_seal_vectored(
+    key: &aead::KeyInner,
+    nonce: Nonce,
+    aad: &[&[u8]],
+    in_out: &mut [&mut [u8]],
+) -> Tag {
+    chacha20_poly1305_vectored(key, nonce, aad, in_out, Direction::Sealing)
+}
+
+fn chacha20_poly1305_open_vectored(
+    key: &aead::KeyInner,
+    nonce: Nonce,
+    aad: &[&[u8]],
+    in_out: &mut [&mut [u8]],
+) -> Tag {
+    chacha20_poly1305_vectored(key, nonce, aad, in_out, Direction::Opening)
+}
+
+fn chacha20_poly1305_vectored(
+    key: &aead::KeyInner,
+    nonce: Nonce,
+    aad: &[&[u8]],
+    in_out: &mut [&mut [u8]],
+    direction: Direction,
+) -> Tag {
+    let chacha20_key = match key {
+        aead::KeyInner::ChaCha20Poly1305(key) => key,
+        _ => unreachable!(),
+    };
+
+    let mut counter = Counter::zero(nonce);
+    let mut auth = {
+        let key = derive_poly1305_key(chacha20_key, counter.increment());
+        poly1305::Context::new(key)
+    };
+
+    let mut aad_len = 0;
+    for part in aad {
+        auth.update(part);
+        aad_len += part.len();
+    }
+    poly1305_pad_16(&mut auth, aad_len);
+
+    let mut authenticate = |ciphertext: &[u8]| auth.update(ciphertext);
+    let mut keystream = Keystream::<CHACHA20_BLOCK_LEN>::new();
+    for fragment in in_out.iter_mut() {
+        let (partial, whole, remainder) = vectored::split_fragment(fragment, &keystream);
+        vectored::crypt(direction, partial, &mut authenticate, |partial| {
+            keystream.xor(partial)
+        });
+        if !whole.is_empty() {
+            // `check_per_nonce_max_bytes` ensures the number of blocks fits.
+            let blocks = u32::try_from(whole.len() / CHACHA20_BLOCK_LEN).unwrap();
+            let whole_counter = counter.increment_by_less_safe(blocks);
+            vectored::crypt(direction, whole, &mut authenticate, |whole| {
+                chacha20_key.encrypt_in_place(whole_counter, whole)
+            });
+        }
+        if !remainder.is_empty() {
+            let mut block = [0u8; CHACHA20_BLOCK_LEN];
+            chacha20_key.encrypt_in_place(counter.increment_by_less_safe(1), &mut block);
+            keystream.refill(block);
+            vectored::crypt(direction, remainder, &mut authenticate, |remainder| {
+                keystream.xor(remainder)
+            });
+        }
+    }
+
+    let in_out_len = vectored::total_len(in_out);
+    poly1305_pad_16(&mut auth, in_out_len);
+    finish(auth, aad_len, in_out_len)
+}
+
+const CHACHA20_BLOCK_LEN: usize = 64;
+
 fn finish(mut auth: poly1305::Context, aad_len: usize, in_out_len: usize) -> Tag {
     auth.update(
         [
@@ -246,11 +442,18 @@ struct Out {
 fn poly1305_update_padded_16(ctx: &mut poly1305::Context, input: &[u8]) {
     if !input.is_empty() {
         ctx.update(input);
-        let remainder_len = input.len() % poly1305::BLOCK_LEN;
-        if remainder_len != 0 {
-            const ZEROES: [u8; poly1305::BLOCK_LEN] = [0; poly1305::BLOCK_LEN];
-            ctx.update(&ZEROES[..(poly1305::BLOCK_LEN - remainder_len)])
-        }
+        poly1305_pad_16(ctx, input.len());
+    }
+}
+
+// Pads the input to Poly1305 with zeros to a multiple of 16 bytes, given
+// that `len` bytes have been input so far.
+#[inline]
+fn poly1305_pad_16(ctx: &mut poly1305::Context, len: usize) {
+    let remainder_len = len % poly1305::BLOCK_LEN;
+    if remainder_len != 0 {
+        const ZEROES: [u8; poly1305::BLOCK_LEN] = [0; poly1305::BLOCK_LEN];
+        ctx.update(&ZEROES[..(poly1305::BLOCK_LEN - remainder_len)])
     }
 }
 
@@ -258,7 +461,7 @@ fn poly1305_update_padded_16(ctx: &mut poly1305::Context, input: &[u8]) {
 pub(super) fn derive_poly1305_key(chacha_key: &chacha::Key, iv: Iv) -> poly1305::Key {
     let mut key_bytes = [0u8; poly1305::KEY_LEN];
     chacha_key.encrypt_iv_xor_in_place(iv, &mut key_bytes);
-    poly1305::Key::new(key_bytes, chacha_key.cpu_features())
+    poly1305::Key::new_(key_bytes, chacha_key.cpu_features())
 }
 
 #[cfg(test)]
@@ -0,0 +1,158 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! GMAC, as specified in [NIST SP 800-38D] and used by IEEE 802.1AE (MACsec)
+//! for authentication-only framing.
+//!
+//! GMAC is AES-GCM with an empty plaintext, where the input is authenticated
+//! as additional authenticated data. Like AES-GCM, it requires a nonce that is
+//! unique for every use of the key.
+//!
+//! [NIST SP 800-38D]:
+//!     http://nvlpubs.nist.gov/nistpubs/Legacy/SP/nistspecialpublication800-38d.pdf
+
+use super::{aes_gcm, KeyInner, Nonce, Tag};
+use crate::{constant_time, cpu, error};
+
+/// A GMAC algorithm.
+pub struct Algorithm {
+    aead: &'static super::Algorithm,
+}
+
+impl Algorithm {
+    /// The length of the key.
+    #[inline]
+    pub fn key_len(&self) -> usize {
+        self.aead.key_len()
+    }
+}
+
+impl core::fmt::Debug for Algorithm {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_tuple("Algorithm").field(self.aead).finish()
+    }
+}
+
+impl PartialEq for Algorithm {
+    fn eq(&self, other: &Self) -> bool {
+        self.aead == other.aead
+    }
+}
+
+impl Eq for Algorithm {}
+
+/// GMAC using AES-128.
+pub static AES_128: Algorithm = Algorithm {
+    aead: &aes_gcm::AES_128_GCM,
+};
+
+/// GMAC using AES-256.
+pub static AES_256: Algorithm = Algorithm {
+    aead: &aes_gcm::AES_256_GCM,
+};
+
+/// A GMAC key.
+#[derive(Clone)]
+pub struct Key {
+    inner: KeyInner,
+    algorithm: &'static Algorithm,
+}
+
+impl Key {
+    /// Constructs a GMAC key from the given raw key bytes.
+    ///
+    /// Fails if `key_bytes` isn't `algorithm.key_len()` bytes long.
+    pub fn new(
+        algorithm: &'static Algorithm,
+        key_bytes: &[u8],
+    ) -> Result<Self, error::Unspecified> {
+        Ok(Self {
+            inner: (algorithm.aead.init)(key_bytes, cpu::features())?,
+            algorithm,
+        })
+    }
+
+    /// The key's algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static Algorithm {
+        self.algorithm
+    }
+}
+
+impl core::fmt::Debug for Key {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("Key")
+            .field("algorithm", &self.algorithm)
+            .finish()
+    }
+}
+
+/// A context for incremental GMAC computation.
+pub struct Context<'k> {
+    inner: aes_gcm::Gmac<'k>,
+}
+
+impl<'k> Context<'k> {
+    /// Constructs a new context for authenticating input with `key` and
+    /// `nonce`.
+    ///
+    /// `nonce` must be unique for every use of the key.
+    pub fn new(key: &'k Key, nonce: Nonce) -> Self {
+        Self {
+            inner: aes_gcm::Gmac::new(&key.inner, nonce),
+        }
+    }
+
+    /// Updates the context with `data`. It is OK to call `update()` any
+    /// number of times, with input of any length.
+    #[inline]
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data)
+    }
+
+    /// Finalizes the computation and returns the tag.
+    #[inline]
+    pub fn finish(self) -> Tag {
+        self.inner.finish()
+    }
+
+    /// Finalizes the computation and verifies, in constant time, that the
+    /// tag is equal to `tag`.
+    pub fn verify(self, tag: &[u8]) -> Result<(), error::Unspecified> {
+        constant_time::verify_slices_are_equal(self.finish().as_ref(), tag)
+    }
+}
+
+impl core::fmt::Debug for Context<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("Context").finish()
+    }
+}
+
+/// Calculates the GMAC tag of `data` using `key` and `nonce`.
+///
+/// `nonce` must be unique for every use of the key.
+pub fn sign(key: &Key, nonce: Nonce, data: &[u8]) -> Tag {
+    let mut ctx = Context::new(key, nonce);
+    ctx.update(data);
+    ctx.finish()
+}
+
+/// Verifies, in constant time, that `tag` is the GMAC tag of `data` using
+/// `key` and `nonce`.
+pub fn verify(key: &Key, nonce: Nonce, data: &[u8], tag: &[u8]) -> Result<(), error::Unspecified> {
+    let mut ctx = Context::new(key, nonce);
+    ctx.update(data);
+    ctx.verify(tag)
+}
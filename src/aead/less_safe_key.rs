@@ -12,7 +12,7 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
-use super::{Aad, Algorithm, KeyInner, Nonce, Tag, UnboundKey, TAG_LEN};
+use super::{vectored, Aad, Algorithm, KeyInner, Nonce, Tag, UnboundKey, TAG_LEN};
 use crate::{constant_time, cpu, error, polyfill};
 use core::ops::RangeFrom;
 
@@ -143,6 +143,151 @@ impl LessSafeKey {
         seal_in_place_separate_tag_(self, nonce, Aad::from(aad.as_ref()), in_out)
     }
 
+    /// Like [`Self::seal_in_place_append_tag()`], except the plaintext is
+    /// read from `plaintext` and the ciphertext and tag are written to
+    /// `ciphertext_and_tag_out`, which must be exactly `TAG_LEN` bytes longer
+    /// than `plaintext`.
+    ///
+    /// `nonce` must be unique for every use of the key to seal data.
+    #[inline]
+    pub fn seal_to<A>(
+        &self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        plaintext: &[u8],
+        ciphertext_and_tag_out: &mut [u8],
+    ) -> Result<(), error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        let ciphertext_len = ciphertext_and_tag_out
+            .len()
+            .checked_sub(TAG_LEN)
+            .ok_or(error::Unspecified)?;
+        if plaintext.len() != ciphertext_len {
+            return Err(error::Unspecified);
+        }
+        let (ciphertext_out, tag_out) = ciphertext_and_tag_out.split_at_mut(ciphertext_len);
+        check_per_nonce_max_bytes(self.algorithm, plaintext.len())?;
+        let Tag(tag) = (self.algorithm.seal_to)(
+            &self.inner,
+            nonce,
+            Aad::from(aad.as_ref()),
+            plaintext,
+            ciphertext_out,
+        );
+        tag_out.copy_from_slice(&tag);
+        Ok(())
+    }
+
+    /// Like [`Self::open_in_place()`], except the ciphertext and tag are read
+    /// from `ciphertext_and_tag` and the plaintext is written to
+    /// `plaintext_out`, which must be exactly `TAG_LEN` bytes shorter than
+    /// `ciphertext_and_tag`.
+    ///
+    /// On failure, `plaintext_out` is zeroed.
+    ///
+    /// `nonce` must be unique for every use of the key to open data.
+    #[inline]
+    pub fn open_to<A>(
+        &self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        ciphertext_and_tag: &[u8],
+        plaintext_out: &mut [u8],
+    ) -> Result<(), error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        let ciphertext_len = ciphertext_and_tag
+            .len()
+            .checked_sub(TAG_LEN)
+            .ok_or(error::Unspecified)?;
+        if plaintext_out.len() != ciphertext_len {
+            return Err(error::Unspecified);
+        }
+        let (ciphertext, received_tag) = ciphertext_and_tag.split_at(ciphertext_len);
+        check_per_nonce_max_bytes(self.algorithm, ciphertext_len)?;
+
+        let Tag(calculated_tag) = (self.algorithm.open_to)(
+            &self.inner,
+            nonce,
+            Aad::from(aad.as_ref()),
+            ciphertext,
+            plaintext_out,
+        );
+
+        if constant_time::verify_slices_are_equal(&calculated_tag, received_tag).is_err() {
+            // Zero out the plaintext, as `open_within_` does.
+            for b in plaintext_out {
+                *b = 0;
+            }
+            return Err(error::Unspecified);
+        }
+
+        Ok(())
+    }
+
+    /// Like [`Self::seal_in_place_separate_tag()`], except the additional
+    /// authenticated data and the plaintext are each given as a sequence of
+    /// fragments, which are treated as though they were concatenated.
+    ///
+    /// The fragments are encrypted in place without being copied, so a record
+    /// that spans several buffers can be sealed directly. Fragments may have
+    /// any length, including zero.
+    ///
+    /// `nonce` must be unique for every use of the key to seal data.
+    #[inline]
+    pub fn seal_in_place_separate_tag_vectored(
+        &self,
+        nonce: Nonce,
+        aad: &[&[u8]],
+        in_out: &mut [&mut [u8]],
+    ) -> Result<Tag, error::Unspecified> {
+        check_per_nonce_max_bytes(self.algorithm, vectored::total_len(in_out))?;
+        Ok((self.algorithm.seal_vectored)(
+            &self.inner,
+            nonce,
+            aad,
+            in_out,
+        ))
+    }
+
+    /// Like [`Self::open_in_place_separate_tag()`], except the additional
+    /// authenticated data and the ciphertext are each given as a sequence of
+    /// fragments, which are treated as though they were concatenated.
+    ///
+    /// On success, the fragments contain the plaintext. On failure, every
+    /// fragment is zeroed.
+    ///
+    /// `nonce` must be unique for every use of the key to open data.
+    #[inline]
+    pub fn open_in_place_separate_tag_vectored(
+        &self,
+        nonce: Nonce,
+        aad: &[&[u8]],
+        received_tag: Tag,
+        in_out: &mut [&mut [u8]],
+    ) -> Result<(), error::Unspecified> {
+        check_per_nonce_max_bytes(self.algorithm, vectored::total_len(in_out))?;
+
+        let Tag(calculated_tag) = (self.algorithm.open_vectored)(&self.inner, nonce, aad, in_out);
+
+        if constant_time::verify_slices_are_equal(calculated_tag.as_ref(), received_tag.as_ref())
+            .is_err()
+        {
+            // Zero out the plaintext, as `open_within_` does.
+            for fragment in in_out.iter_mut() {
+                for b in fragment.iter_mut() {
+                    *b = 0;
+                }
+            }
+            return Err(error::Unspecified);
+        }
+
+        Ok(())
+    }
+
     /// The key's AEAD algorithm.
     #[inline]
     pub fn algorithm(&self) -> &'static Algorithm {
@@ -0,0 +1,356 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! NaCl/libsodium-compatible `crypto_secretbox`, `crypto_box`, and
+//! `crypto_box_seal`.
+//!
+//! `crypto_secretbox` is XSalsa20-Poly1305 as specified in
+//! [Cryptography in NaCl]. `crypto_box` is `crypto_secretbox` keyed with the
+//! HSalsa20 hash of an X25519 shared secret. `crypto_box_seal`, a.k.a. the
+//! "sealed box", encrypts a message anonymously to a public key using an
+//! ephemeral X25519 key pair; it is specified in the [libsodium
+//! documentation].
+//!
+//! These constructs are provided for interoperability with existing NaCl and
+//! libsodium users. New protocols should use the AEADs in `ring::aead` and
+//! `ring::agreement` instead.
+//!
+//! The ciphertexts produced are byte-for-byte identical to libsodium's. The
+//! "combined" format, `tag || ciphertext`, is produced by `Key::seal_in_place`
+//! and consumed by `Key::open_in_place`; the "detached" format is handled by
+//! the `_separate_tag` variants. A sealed box is
+//! `ephemeral_public_key || tag || ciphertext`.
+//!
+//! [Cryptography in NaCl]: https://cr.yp.to/highspeed/naclcrypto-20090310.pdf
+//! [libsodium documentation]: https://doc.libsodium.org/public-key_cryptography/sealed_boxes
+
+use super::{poly1305, salsa20, Tag};
+use crate::{agreement, blake2b::blake2b, cpu, debug, ec, error, rand};
+
+/// The length of a `crypto_secretbox` key.
+pub const KEY_LEN: usize = salsa20::KEY_LEN;
+
+/// The length of a nonce.
+pub const NONCE_LEN: usize = salsa20::XSALSA20_NONCE_LEN;
+
+/// The length of a tag.
+pub const TAG_LEN: usize = super::TAG_LEN;
+
+/// The length of an X25519 public key.
+pub const PUBLIC_KEY_LEN: usize = 32;
+
+/// The length of an X25519 private key.
+pub const PRIVATE_KEY_LEN: usize = 32;
+
+/// The number of bytes a sealed box is longer than the message.
+pub const SEALED_BOX_OVERHEAD_LEN: usize = PUBLIC_KEY_LEN + TAG_LEN;
+
+/// A nonce for a single sealing or opening operation.
+///
+/// The user must ensure, for a particular key, that each nonce is unique. The
+/// nonces are long enough that they can be chosen randomly.
+pub struct Nonce([u8; NONCE_LEN]);
+
+impl Nonce {
+    /// Constructs a `Nonce` with the given value, assuming that the value is
+    /// unique for the lifetime of the key it is being used with.
+    #[inline]
+    pub fn assume_unique_for_key(value: [u8; NONCE_LEN]) -> Self {
+        Self(value)
+    }
+}
+
+impl AsRef<[u8; NONCE_LEN]> for Nonce {
+    fn as_ref(&self) -> &[u8; NONCE_LEN] {
+        &self.0
+    }
+}
+
+/// A `crypto_secretbox` key, or a precomputed `crypto_box` key.
+pub struct Key {
+    key: salsa20::Key,
+    cpu_features: cpu::Features,
+}
+
+impl Key {
+    /// Constructs a `crypto_secretbox` key from the given raw key bytes.
+    pub fn new(key_bytes: &[u8; KEY_LEN]) -> Self {
+        Self {
+            key: salsa20::Key::new(*key_bytes),
+            cpu_features: cpu::features(),
+        }
+    }
+
+    /// Computes the key that `crypto_box` uses to seal messages from the
+    /// owner of `my_private_key` to the owner of `peer_public_key`, and to
+    /// open messages in the other direction, like `crypto_box_beforenm`.
+    ///
+    /// Fails if the X25519 shared secret is zero, i.e. if `peer_public_key`
+    /// is a point of small order.
+    pub fn precompute(
+        my_private_key: &PrivateKey,
+        peer_public_key: &PublicKey,
+    ) -> Result<Self, error::Unspecified> {
+        let mut shared_secret = [0u8; KEY_LEN];
+        (agreement::X25519.ecdh)(
+            &mut shared_secret,
+            &my_private_key.seed,
+            untrusted::Input::from(peer_public_key.as_ref()),
+        )?;
+        let shared_secret = salsa20::Key::new(shared_secret);
+        let key = shared_secret.hsalsa20(&[0; salsa20::HSALSA20_INPUT_LEN]);
+        Ok(Self {
+            key: salsa20::Key::new(key),
+            cpu_features: my_private_key.seed.cpu_features,
+        })
+    }
+
+    /// Encrypts and signs (“seals”) data in place, producing the combined
+    /// format.
+    ///
+    /// On input, `in_out[TAG_LEN..]` is the plaintext and the contents of
+    /// `in_out[..TAG_LEN]` are ignored. On output, `in_out` is the tag
+    /// followed by the ciphertext, like `crypto_secretbox_easy` and
+    /// `crypto_box_easy_afternm`.
+    ///
+    /// Fails if `in_out` is shorter than `TAG_LEN`.
+    pub fn seal_in_place(&self, nonce: Nonce, in_out: &mut [u8]) -> Result<(), error::Unspecified> {
+        if in_out.len() < TAG_LEN {
+            return Err(error::Unspecified);
+        }
+        let (tag_out, in_out) = in_out.split_at_mut(TAG_LEN);
+        let Tag(tag) = self.seal_in_place_separate_tag(nonce, in_out);
+        tag_out.copy_from_slice(&tag);
+        Ok(())
+    }
+
+    /// Authenticates and decrypts (“opens”) data in the combined format in
+    /// place, like `crypto_secretbox_open_easy` and
+    /// `crypto_box_open_easy_afternm`.
+    ///
+    /// `in_out` is the tag followed by the ciphertext. When `open_in_place()`
+    /// returns `Ok(plaintext)`, the plaintext is `in_out[TAG_LEN..]`. When it
+    /// returns `Err(..)`, `in_out` is unmodified.
+    pub fn open_in_place<'in_out>(
+        &self,
+        nonce: Nonce,
+        in_out: &'in_out mut [u8],
+    ) -> Result<&'in_out mut [u8], error::Unspecified> {
+        if in_out.len() < TAG_LEN {
+            return Err(error::Unspecified);
+        }
+        let (received_tag, in_out) = in_out.split_at_mut(TAG_LEN);
+        let received_tag = Tag::try_from(&received_tag[..])?;
+        self.open_in_place_separate_tag(nonce, received_tag, in_out)?;
+        Ok(in_out)
+    }
+
+    /// Encrypts and signs (“seals”) data in place, producing the detached
+    /// format, like `crypto_secretbox_detached` and
+    /// `crypto_box_detached_afternm`.
+    ///
+    /// `in_out` is replaced by the ciphertext, which is the same length as
+    /// the plaintext, and the tag is returned.
+    pub fn seal_in_place_separate_tag(&self, nonce: Nonce, in_out: &mut [u8]) -> Tag {
+        let cipher = Cipher::new(&self.key, &nonce);
+        cipher.xor_in_place(in_out);
+        poly1305::sign(cipher.poly1305_key(self.cpu_features), in_out)
+    }
+
+    /// Authenticates and decrypts (“opens”) data in the detached format in
+    /// place, like `crypto_secretbox_open_detached` and
+    /// `crypto_box_open_detached_afternm`.
+    ///
+    /// The tag is verified before anything is decrypted, so when the result
+    /// is `Err(..)`, `in_out` is unmodified.
+    pub fn open_in_place_separate_tag(
+        &self,
+        nonce: Nonce,
+        received_tag: Tag,
+        in_out: &mut [u8],
+    ) -> Result<(), error::Unspecified> {
+        let cipher = Cipher::new(&self.key, &nonce);
+        poly1305::verify(
+            cipher.poly1305_key(self.cpu_features),
+            in_out,
+            received_tag.as_ref(),
+        )?;
+        cipher.xor_in_place(in_out);
+        Ok(())
+    }
+}
+
+impl core::fmt::Debug for Key {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("Key").finish()
+    }
+}
+
+/// An X25519 private key for `crypto_box`.
+pub struct PrivateKey {
+    seed: ec::Seed,
+}
+
+impl PrivateKey {
+    /// Constructs a private key from the given raw key bytes, like the
+    /// secret keys produced by `crypto_box_keypair`.
+    pub fn from_bytes(bytes: &[u8; PRIVATE_KEY_LEN]) -> Result<Self, error::Unspecified> {
+        let seed = ec::Seed::from_bytes(
+            agreement::X25519.curve,
+            untrusted::Input::from(bytes),
+            cpu::features(),
+        )?;
+        Ok(Self { seed })
+    }
+
+    /// Generates a new private key.
+    pub fn generate(rng: &dyn rand::SecureRandom) -> Result<Self, error::Unspecified> {
+        let seed = ec::Seed::generate(agreement::X25519.curve, rng, cpu::features())?;
+        Ok(Self { seed })
+    }
+
+    /// Computes the public key from the private key.
+    pub fn compute_public_key(&self) -> Result<PublicKey, error::Unspecified> {
+        let public_key = self.seed.compute_public_key()?;
+        let bytes = public_key.as_ref().try_into()?;
+        Ok(PublicKey::new(bytes))
+    }
+}
+
+impl core::fmt::Debug for PrivateKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("PrivateKey").finish()
+    }
+}
+
+/// An X25519 public key for `crypto_box`.
+#[derive(Clone, Copy)]
+pub struct PublicKey([u8; PUBLIC_KEY_LEN]);
+
+impl PublicKey {
+    /// Constructs a public key from the given raw key bytes.
+    #[inline]
+    pub fn new(bytes: [u8; PUBLIC_KEY_LEN]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl AsRef<[u8]> for PublicKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl core::fmt::Debug for PublicKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_tuple("PublicKey")
+            .field(&debug::HexStr(self.as_ref()))
+            .finish()
+    }
+}
+
+/// Encrypts data in place anonymously to `recipient_public_key`, like
+/// `crypto_box_seal`.
+///
+/// On input, `in_out[SEALED_BOX_OVERHEAD_LEN..]` is the plaintext and the
+/// contents of `in_out[..SEALED_BOX_OVERHEAD_LEN]` are ignored. On output,
+/// `in_out` is the sealed box: a freshly-generated ephemeral public key, the
+/// tag, and the ciphertext.
+///
+/// Fails if `in_out` is shorter than `SEALED_BOX_OVERHEAD_LEN`.
+pub fn seal_anonymous_in_place(
+    recipient_public_key: &PublicKey,
+    in_out: &mut [u8],
+    rng: &dyn rand::SecureRandom,
+) -> Result<(), error::Unspecified> {
+    if in_out.len() < SEALED_BOX_OVERHEAD_LEN {
+        return Err(error::Unspecified);
+    }
+    let ephemeral_private_key = PrivateKey::generate(rng)?;
+    let ephemeral_public_key = ephemeral_private_key.compute_public_key()?;
+    let key = Key::precompute(&ephemeral_private_key, recipient_public_key)?;
+    let nonce = sealed_box_nonce(&ephemeral_public_key, recipient_public_key);
+
+    let (ephemeral_public_key_out, in_out) = in_out.split_at_mut(PUBLIC_KEY_LEN);
+    ephemeral_public_key_out.copy_from_slice(ephemeral_public_key.as_ref());
+    key.seal_in_place(nonce, in_out)
+}
+
+/// Opens a sealed box in place, like `crypto_box_seal_open`.
+///
+/// `in_out` is the sealed box. When `open_anonymous_in_place()` returns
+/// `Ok(plaintext)`, the plaintext is `in_out[SEALED_BOX_OVERHEAD_LEN..]`.
+/// When it returns `Err(..)`, `in_out` is unmodified.
+pub fn open_anonymous_in_place<'in_out>(
+    recipient_private_key: &PrivateKey,
+    in_out: &'in_out mut [u8],
+) -> Result<&'in_out mut [u8], error::Unspecified> {
+    if in_out.len() < SEALED_BOX_OVERHEAD_LEN {
+        return Err(error::Unspecified);
+    }
+    let (ephemeral_public_key, in_out) = in_out.split_at_mut(PUBLIC_KEY_LEN);
+    let ephemeral_public_key = PublicKey::new((&ephemeral_public_key[..]).try_into()?);
+    let recipient_public_key = recipient_private_key.compute_public_key()?;
+    let key = Key::precompute(recipient_private_key, &ephemeral_public_key)?;
+    let nonce = sealed_box_nonce(&ephemeral_public_key, &recipient_public_key);
+    key.open_in_place(nonce, in_out)
+}
+
+// The XSalsa20 keystream for one message. The first 32 bytes of the
+// keystream are the Poly1305 key; the message is XORed with the rest.
+struct Cipher {
+    key: salsa20::Key,
+    nonce: [u8; salsa20::NONCE_LEN],
+    block_0: [u8; salsa20::BLOCK_LEN],
+}
+
+impl Cipher {
+    fn new(key: &salsa20::Key, nonce: &Nonce) -> Self {
+        let (key, nonce) = key.xsalsa20(nonce.as_ref());
+        let block_0 = key.block(&nonce, 0);
+        Self {
+            key,
+            nonce,
+            block_0,
+        }
+    }
+
+    fn poly1305_key(&self, cpu_features: cpu::Features) -> poly1305::Key {
+        let (key, _) = self.block_0.split_at(poly1305::KEY_LEN);
+        poly1305::Key::new_(key.try_into().unwrap(), cpu_features)
+    }
+
+    fn xor_in_place(&self, in_out: &mut [u8]) {
+        let (_, keystream) = self.block_0.split_at(poly1305::KEY_LEN);
+        let (first, rest) = in_out.split_at_mut(core::cmp::min(keystream.len(), in_out.len()));
+        first
+            .iter_mut()
+            .zip(keystream.iter())
+            .for_each(|(b, k)| *b ^= *k);
+        self.key.xor_in_place(&self.nonce, 1, rest);
+    }
+}
+
+// The nonce of a sealed box is the 24-byte BLAKE2b hash of the ephemeral
+// public key followed by the recipient's public key.
+fn sealed_box_nonce(ephemeral_public_key: &PublicKey, recipient_public_key: &PublicKey) -> Nonce {
+    let mut input = [0u8; 2 * PUBLIC_KEY_LEN];
+    let (first, second) = input.split_at_mut(PUBLIC_KEY_LEN);
+    first.copy_from_slice(ephemeral_public_key.as_ref());
+    second.copy_from_slice(recipient_public_key.as_ref());
+
+    let mut nonce = [0u8; NONCE_LEN];
+    blake2b(&input, &mut nonce);
+    Nonce::assume_unique_for_key(nonce)
+}
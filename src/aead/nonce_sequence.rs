@@ -0,0 +1,220 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Implementations of `NonceSequence`.
+//!
+//! Each of these sequences fails once it is exhausted, instead of wrapping
+//! around, and keeps failing thereafter.
+//!
+//! A nonce sequence only guarantees uniqueness within itself. A key must not
+//! be used with two sequences that can produce the same nonce; in particular,
+//! the two directions of a connection must use different keys, or
+//! `PrefixCounter`s with different prefixes.
+
+use super::{Nonce, NonceSequence, NONCE_LEN};
+use crate::{error, tls13};
+
+/// A 96-bit big-endian counter, starting at zero.
+///
+/// Fails after 2<sup>96</sup> nonces.
+pub struct Counter {
+    next: Option<[u8; NONCE_LEN]>,
+}
+
+impl Counter {
+    /// Constructs a new `Counter`.
+    pub fn new() -> Self {
+        Self {
+            next: Some([0; NONCE_LEN]),
+        }
+    }
+}
+
+impl Default for Counter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NonceSequence for Counter {
+    fn advance(&mut self) -> Result<Nonce, error::Unspecified> {
+        let current = self.next.ok_or(error::Unspecified)?;
+
+        let mut next = current;
+        let mut carry = true;
+        for byte in next.iter_mut().rev() {
+            let (sum, overflowed) = byte.overflowing_add(u8::from(carry));
+            *byte = sum;
+            carry = overflowed;
+        }
+        self.next = if carry { None } else { Some(next) };
+
+        Ok(Nonce::assume_unique_for_key(current))
+    }
+}
+
+impl core::fmt::Debug for Counter {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("Counter").finish()
+    }
+}
+
+/// The TLS 1.3 construction ([RFC 8446 Section 5.3]): a 64-bit record
+/// sequence number, starting at zero, left-padded with zeros and XORed with
+/// a static IV.
+///
+/// Fails after 2<sup>64</sup> nonces.
+///
+/// [RFC 8446 Section 5.3]: https://tools.ietf.org/html/rfc8446#section-5.3
+pub struct XorCounter {
+    iv: tls13::Iv,
+    next: Option<u64>,
+}
+
+impl XorCounter {
+    /// Constructs a new `XorCounter` with the given static IV.
+    pub fn new(iv: [u8; NONCE_LEN]) -> Self {
+        Self::from(tls13::Iv::new(iv))
+    }
+}
+
+impl From<tls13::Iv> for XorCounter {
+    fn from(iv: tls13::Iv) -> Self {
+        Self { iv, next: Some(0) }
+    }
+}
+
+impl NonceSequence for XorCounter {
+    fn advance(&mut self) -> Result<Nonce, error::Unspecified> {
+        let sequence_number = self.next.ok_or(error::Unspecified)?;
+        self.next = sequence_number.checked_add(1);
+        Ok(self.iv.nonce_for(sequence_number))
+    }
+}
+
+impl core::fmt::Debug for XorCounter {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("XorCounter").finish()
+    }
+}
+
+/// A fixed 32-bit prefix followed by a 64-bit big-endian counter, starting
+/// at zero, as in [RFC 5116 Section 3.2].
+///
+/// When multiple writers share a key, each must use a different prefix.
+///
+/// Fails after 2<sup>64</sup> nonces.
+///
+/// [RFC 5116 Section 3.2]: https://tools.ietf.org/html/rfc5116#section-3.2
+pub struct PrefixCounter {
+    prefix: [u8; PREFIX_LEN],
+    next: Option<u64>,
+}
+
+/// The length of the prefix of a `PrefixCounter`.
+pub const PREFIX_LEN: usize = NONCE_LEN - 8;
+
+impl PrefixCounter {
+    /// Constructs a new `PrefixCounter` with the given prefix.
+    pub fn new(prefix: [u8; PREFIX_LEN]) -> Self {
+        Self {
+            prefix,
+            next: Some(0),
+        }
+    }
+}
+
+impl NonceSequence for PrefixCounter {
+    fn advance(&mut self) -> Result<Nonce, error::Unspecified> {
+        let counter = self.next.ok_or(error::Unspecified)?;
+        self.next = counter.checked_add(1);
+
+        let mut nonce = [0u8; NONCE_LEN];
+        let (prefix, counter_bytes) = nonce.split_at_mut(PREFIX_LEN);
+        prefix.copy_from_slice(&self.prefix);
+        counter_bytes.copy_from_slice(&counter.to_be_bytes());
+        Ok(Nonce::assume_unique_for_key(nonce))
+    }
+}
+
+impl core::fmt::Debug for PrefixCounter {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("PrefixCounter").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn advance(sequence: &mut impl NonceSequence) -> Option<[u8; NONCE_LEN]> {
+        sequence.advance().ok().map(|nonce| *nonce.as_ref())
+    }
+
+    #[test]
+    fn test_counter_overflow() {
+        let mut last = [0xff; NONCE_LEN];
+        last[NONCE_LEN - 1] = 0xfe;
+        let mut counter = Counter { next: Some(last) };
+        assert_eq!(advance(&mut counter), Some(last));
+        assert_eq!(advance(&mut counter), Some([0xff; NONCE_LEN]));
+        assert_eq!(advance(&mut counter), None);
+        assert_eq!(advance(&mut counter), None);
+    }
+
+    #[test]
+    fn test_counter_carry() {
+        let mut start = [0; NONCE_LEN];
+        start[3] = 0x01;
+        start[4..].copy_from_slice(&[0xff; 8]);
+        let mut counter = Counter { next: Some(start) };
+        assert_eq!(advance(&mut counter), Some(start));
+        let mut expected = [0; NONCE_LEN];
+        expected[3] = 0x02;
+        assert_eq!(advance(&mut counter), Some(expected));
+    }
+
+    #[test]
+    fn test_xor_counter_overflow() {
+        let iv = [0xa5; NONCE_LEN];
+        let mut sequence = XorCounter {
+            iv: tls13::Iv::new(iv),
+            next: Some(u64::MAX - 1),
+        };
+        let mut expected = iv;
+        expected[(NONCE_LEN - 8)..].copy_from_slice(&[0x5a; 8]);
+        expected[NONCE_LEN - 1] = 0x5b;
+        assert_eq!(advance(&mut sequence), Some(expected));
+        expected[NONCE_LEN - 1] = 0x5a;
+        assert_eq!(advance(&mut sequence), Some(expected));
+        assert_eq!(advance(&mut sequence), None);
+        assert_eq!(advance(&mut sequence), None);
+    }
+
+    #[test]
+    fn test_prefix_counter_overflow() {
+        let mut sequence = PrefixCounter {
+            prefix: [1, 2, 3, 4],
+            next: Some(u64::MAX - 1),
+        };
+        let mut expected = [0xff; NONCE_LEN];
+        expected[..PREFIX_LEN].copy_from_slice(&[1, 2, 3, 4]);
+        expected[NONCE_LEN - 1] = 0xfe;
+        assert_eq!(advance(&mut sequence), Some(expected));
+        expected[NONCE_LEN - 1] = 0xff;
+        assert_eq!(advance(&mut sequence), Some(expected));
+        assert_eq!(advance(&mut sequence), None);
+        assert_eq!(advance(&mut sequence), None);
+    }
+}
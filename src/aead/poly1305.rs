@@ -13,23 +13,47 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
+//! The Poly1305 one-time authenticator, as specified in [RFC 8439 Section 2.5].
+//!
+//! A Poly1305 key must be used to authenticate only one message; an attacker
+//! who sees the tags of two messages authenticated with the same key can
+//! forge tags. This is enforced by `Key` being consumed when it is used. The
+//! key is usually derived from a stream cipher and a nonce, as in
+//! ChaCha20-Poly1305 and NaCl's `crypto_secretbox`.
+//!
+//! This is the raw Poly1305 function, with no padding of the input.
+//!
+//! [RFC 8439 Section 2.5]: https://tools.ietf.org/html/rfc8439#section-2.5
+
 // TODO: enforce maximum input length.
 
-use super::{Tag, TAG_LEN};
-use crate::{c, cpu};
+use super::Tag;
+use crate::{c, constant_time, cpu, error};
 
 /// A Poly1305 key.
-pub(super) struct Key {
+pub struct Key {
     key_and_nonce: [u8; KEY_LEN],
     cpu_features: cpu::Features,
 }
 
 pub(super) const BLOCK_LEN: usize = 16;
-pub(super) const KEY_LEN: usize = 2 * BLOCK_LEN;
+
+/// The length of a Poly1305 key.
+pub const KEY_LEN: usize = 2 * BLOCK_LEN;
+
+/// The length of a Poly1305 tag.
+pub const TAG_LEN: usize = super::TAG_LEN;
 
 impl Key {
+    /// Constructs a Poly1305 key from the given raw key bytes, which are the
+    /// 16-byte `r` followed by the 16-byte `s`.
     #[inline]
-    pub(super) fn new(key_and_nonce: [u8; KEY_LEN], cpu_features: cpu::Features) -> Self {
+    pub fn new(key_and_nonce: [u8; KEY_LEN]) -> Self {
+        Self::new_(key_and_nonce, cpu::features())
+    }
+
+    #[inline]
+    pub(super) fn new_(key_and_nonce: [u8; KEY_LEN], cpu_features: cpu::Features) -> Self {
         Self {
             key_and_nonce,
             cpu_features,
@@ -37,6 +61,13 @@ impl Key {
     }
 }
 
+impl core::fmt::Debug for Key {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("Key").finish()
+    }
+}
+
+/// A context for incremental Poly1305 computation.
 pub struct Context {
     state: poly1305_state,
     #[allow(dead_code)]
@@ -78,8 +109,9 @@ macro_rules! dispatch {
 }
 
 impl Context {
+    /// Constructs a new context that consumes `key`.
     #[inline]
-    pub(super) fn from_key(
+    pub fn new(
         Key {
             key_and_nonce,
             cpu_features,
@@ -99,6 +131,8 @@ impl Context {
         ctx
     }
 
+    /// Updates the context with `input`. It is OK to call `update()` any
+    /// number of times, with input of any length.
     #[inline(always)]
     pub fn update(&mut self, input: &[u8]) {
         dispatch!(
@@ -108,7 +142,8 @@ impl Context {
             (&mut self.state, input.as_ptr(), input.len()));
     }
 
-    pub(super) fn finish(mut self) -> Tag {
+    /// Finalizes the computation and returns the tag.
+    pub fn finish(mut self) -> Tag {
         let mut tag = Tag([0u8; TAG_LEN]);
         dispatch!(
             self.cpu_features =>
@@ -117,18 +152,38 @@ impl Context {
             (&mut self.state, &mut tag.0));
         tag
     }
+
+    /// Finalizes the computation and verifies, in constant time, that the
+    /// tag is equal to `tag`.
+    pub fn verify(self, tag: &[u8]) -> Result<(), error::Unspecified> {
+        constant_time::verify_slices_are_equal(self.finish().as_ref(), tag)
+    }
+}
+
+impl core::fmt::Debug for Context {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("Context").finish()
+    }
 }
 
-/// Implements the original, non-IETF padding semantics.
+/// Calculates the Poly1305 tag of `input` using `key`.
 ///
-/// This is used by chacha20_poly1305_openssh and the standalone
-/// poly1305 test vectors.
-pub(super) fn sign(key: Key, input: &[u8]) -> Tag {
-    let mut ctx = Context::from_key(key);
+/// This implements the original, non-IETF padding semantics. It is used by
+/// chacha20_poly1305_openssh.
+pub fn sign(key: Key, input: &[u8]) -> Tag {
+    let mut ctx = Context::new(key);
     ctx.update(input);
     ctx.finish()
 }
 
+/// Verifies, in constant time, that `tag` is the Poly1305 tag of `input`
+/// using `key`.
+pub fn verify(key: Key, input: &[u8], tag: &[u8]) -> Result<(), error::Unspecified> {
+    let mut ctx = Context::new(key);
+    ctx.update(input);
+    ctx.verify(tag)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -144,7 +199,7 @@ mod tests {
             let key: &[u8; KEY_LEN] = key.as_slice().try_into().unwrap();
             let input = test_case.consume_bytes("Input");
             let expected_mac = test_case.consume_bytes("MAC");
-            let key = Key::new(*key, cpu_features);
+            let key = Key::new_(*key, cpu_features);
             let Tag(actual_mac) = sign(key, &input);
             assert_eq!(expected_mac, actual_mac.as_ref());
 
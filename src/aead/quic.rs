@@ -12,13 +12,22 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
-//! QUIC Header Protection.
+//! QUIC Header Protection and Packet Protection.
 //!
-//! See draft-ietf-quic-tls.
+//! See [RFC 9001] and, for QUIC version 2, [RFC 9369].
+//!
+//! `HeaderProtectionKey` can be used on its own with keys derived elsewhere.
+//! `DirectionalKeys` derives both the header protection key and the packet
+//! protection `PacketKey` for one direction of a connection from a traffic
+//! secret, as described in [RFC 9001 Section 5.1].
+//!
+//! [RFC 9001]: https://tools.ietf.org/html/rfc9001
+//! [RFC 9001 Section 5.1]: https://tools.ietf.org/html/rfc9001#section-5.1
+//! [RFC 9369]: https://tools.ietf.org/html/rfc9369
 
 use crate::{
-    aead::{aes, chacha},
-    cpu, error, hkdf,
+    aead::{self, aes, aes_gcm, chacha, Aad, LessSafeKey, Nonce, Tag},
+    constant_time, cpu, error, hkdf, tls13,
 };
 
 /// A key for generating QUIC Header Protection masks.
@@ -186,3 +195,266 @@ fn chacha20_new_mask(key: &KeyInner, sample: Sample) -> [u8; 5] {
 
     chacha20_key.new_mask(sample)
 }
+
+/// A QUIC version, which determines the labels used to derive keys and the
+/// key used for Retry packet integrity.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Version {
+    /// QUIC version 1 ([RFC 9000]).
+    ///
+    /// [RFC 9000]: https://tools.ietf.org/html/rfc9000
+    V1,
+
+    /// QUIC version 2 ([RFC 9369]).
+    ///
+    /// [RFC 9369]: https://tools.ietf.org/html/rfc9369
+    V2,
+}
+
+impl Version {
+    /// Computes the Retry Integrity Tag of a Retry packet
+    /// ([RFC 9001 Section 5.8]).
+    ///
+    /// `original_destination_connection_id` is the Destination Connection ID
+    /// of the Initial packet the Retry packet responds to, and
+    /// `retry_packet_without_tag` is the Retry packet up to, but not
+    /// including, the tag.
+    ///
+    /// Fails if `original_destination_connection_id` is longer than 255 bytes.
+    ///
+    /// [RFC 9001 Section 5.8]: https://tools.ietf.org/html/rfc9001#section-5.8
+    pub fn retry_integrity_tag(
+        self,
+        original_destination_connection_id: &[u8],
+        retry_packet_without_tag: &[u8],
+    ) -> Result<Tag, error::Unspecified> {
+        let odcid_len = u8::try_from(original_destination_connection_id.len())
+            .map_err(|_| error::Unspecified)?;
+        let (key, nonce) = match self {
+            Self::V1 => (&RETRY_KEY_V1, RETRY_NONCE_V1),
+            Self::V2 => (&RETRY_KEY_V2, RETRY_NONCE_V2),
+        };
+        let key = (aes_gcm::AES_128_GCM.init)(key, cpu::features())?;
+
+        // The tag is computed over the Retry Pseudo-Packet with an empty
+        // plaintext.
+        Ok(aes_gcm::gmac_parts(
+            &key,
+            Nonce::assume_unique_for_key(nonce),
+            &[
+                &[odcid_len],
+                original_destination_connection_id,
+                retry_packet_without_tag,
+            ],
+        ))
+    }
+
+    /// Verifies the Retry Integrity Tag at the end of `retry_packet`.
+    ///
+    /// See `retry_integrity_tag()`.
+    pub fn verify_retry_integrity_tag(
+        self,
+        original_destination_connection_id: &[u8],
+        retry_packet: &[u8],
+    ) -> Result<(), error::Unspecified> {
+        let tag_offset = retry_packet
+            .len()
+            .checked_sub(aead::TAG_LEN)
+            .ok_or(error::Unspecified)?;
+        let (retry_packet_without_tag, received_tag) = retry_packet.split_at(tag_offset);
+        let calculated_tag =
+            self.retry_integrity_tag(original_destination_connection_id, retry_packet_without_tag)?;
+        constant_time::verify_slices_are_equal(calculated_tag.as_ref(), received_tag)
+    }
+
+    fn labels(self) -> &'static Labels {
+        match self {
+            Self::V1 => &Labels {
+                key: b"quic key",
+                iv: b"quic iv",
+                hp: b"quic hp",
+                ku: b"quic ku",
+            },
+            Self::V2 => &Labels {
+                key: b"quicv2 key",
+                iv: b"quicv2 iv",
+                hp: b"quicv2 hp",
+                ku: b"quicv2 ku",
+            },
+        }
+    }
+}
+
+struct Labels {
+    key: &'static [u8],
+    iv: &'static [u8],
+    hp: &'static [u8],
+    ku: &'static [u8],
+}
+
+const RETRY_KEY_V1: [u8; 16] = [
+    0xbe, 0x0c, 0x69, 0x0b, 0x9f, 0x66, 0x57, 0x5a, 0x1d, 0x76, 0x6b, 0x54, 0xe3, 0x68, 0xc8, 0x4e,
+];
+const RETRY_NONCE_V1: [u8; aead::NONCE_LEN] = [
+    0x46, 0x15, 0x99, 0xd3, 0x5d, 0x63, 0x2b, 0xf2, 0x23, 0x98, 0x25, 0xbb,
+];
+
+const RETRY_KEY_V2: [u8; 16] = [
+    0x8f, 0xb4, 0xb0, 0x1b, 0x56, 0xac, 0x48, 0xe2, 0x60, 0xfb, 0xcb, 0xce, 0xad, 0x7c, 0xcc, 0x92,
+];
+const RETRY_NONCE_V2: [u8; aead::NONCE_LEN] = [
+    0xd8, 0x69, 0x69, 0xbc, 0x2d, 0x7c, 0x6d, 0x99, 0x90, 0xef, 0xb0, 0x4a,
+];
+
+/// The header protection key and packet protection key for one direction of
+/// a QUIC connection at one encryption level.
+pub struct DirectionalKeys {
+    /// The header protection key. It isn't changed by key updates.
+    pub header: HeaderProtectionKey,
+
+    /// The packet protection key.
+    pub packet: PacketKey,
+}
+
+impl DirectionalKeys {
+    /// Derives the keys for `algorithm` from `secret`, which is a TLS 1.3
+    /// traffic secret or a QUIC Initial secret ([RFC 9001 Section 5.1]).
+    ///
+    /// Fails if `algorithm` isn't one of the AEAD algorithms that QUIC uses.
+    ///
+    /// [RFC 9001 Section 5.1]: https://tools.ietf.org/html/rfc9001#section-5.1
+    pub fn new(
+        version: Version,
+        algorithm: &'static aead::Algorithm,
+        secret: &hkdf::Prk,
+    ) -> Result<Self, error::Unspecified> {
+        let header_algorithm = header_protection_algorithm(algorithm)?;
+        Ok(Self {
+            header: tls13::expand_label(secret, version.labels().hp, &[], header_algorithm)
+                .unwrap(),
+            packet: PacketKey::new(version, algorithm, secret)?,
+        })
+    }
+}
+
+// The header protection algorithm that QUIC uses with `algorithm`, if QUIC
+// uses `algorithm` at all.
+fn header_protection_algorithm(
+    algorithm: &aead::Algorithm,
+) -> Result<&'static Algorithm, error::Unspecified> {
+    match algorithm.id {
+        aead::AlgorithmID::AES_128_GCM => Ok(&AES_128),
+        aead::AlgorithmID::AES_256_GCM => Ok(&AES_256),
+        aead::AlgorithmID::CHACHA20_POLY1305 => Ok(&CHACHA20),
+    }
+}
+
+/// A QUIC packet protection key ([RFC 9001 Section 5.3]).
+///
+/// The nonce for each packet is constructed from the packet number, so a
+/// `PacketKey` must never be used to seal two packets with the same packet
+/// number.
+///
+/// [RFC 9001 Section 5.3]: https://tools.ietf.org/html/rfc9001#section-5.3
+pub struct PacketKey {
+    key: LessSafeKey,
+    iv: tls13::Iv,
+    version: Version,
+    secret: hkdf::Prk,
+}
+
+impl PacketKey {
+    /// Derives the packet protection key and IV for `algorithm` from
+    /// `secret`.
+    ///
+    /// See `DirectionalKeys::new()`.
+    pub fn new(
+        version: Version,
+        algorithm: &'static aead::Algorithm,
+        secret: &hkdf::Prk,
+    ) -> Result<Self, error::Unspecified> {
+        let _ = header_protection_algorithm(algorithm)?;
+        Ok(Self::new_(version, algorithm, secret))
+    }
+
+    fn new_(version: Version, algorithm: &'static aead::Algorithm, secret: &hkdf::Prk) -> Self {
+        let labels = version.labels();
+        let key: aead::UnboundKey =
+            tls13::expand_label(secret, labels.key, &[], algorithm).unwrap();
+        Self {
+            key: LessSafeKey::new(key),
+            iv: tls13::Iv::derive(secret, labels.iv),
+            version,
+            secret: secret.clone(),
+        }
+    }
+
+    /// Derives the key for the next key phase, for a key update
+    /// ([RFC 9001 Section 6]).
+    ///
+    /// The header protection key isn't updated.
+    ///
+    /// [RFC 9001 Section 6]: https://tools.ietf.org/html/rfc9001#section-6
+    pub fn next(&self) -> Self {
+        let algorithm = self.secret.algorithm();
+        let secret: hkdf::Prk =
+            tls13::expand_label(&self.secret, self.version.labels().ku, &[], algorithm).unwrap();
+        Self::new_(self.version, self.key.algorithm(), &secret)
+    }
+
+    /// Seals (encrypts and signs) the payload of the packet with the given
+    /// packet number, returning the tag.
+    ///
+    /// `header` is the packet's header, up to and including the packet number
+    /// field, before header protection is applied.
+    pub fn seal_in_place(
+        &self,
+        packet_number: u64,
+        header: &[u8],
+        payload: &mut [u8],
+    ) -> Result<Tag, error::Unspecified> {
+        self.key.seal_in_place_separate_tag(
+            self.iv.nonce_for(packet_number),
+            Aad::from(header),
+            payload,
+        )
+    }
+
+    /// Opens (authenticates and decrypts) the payload of the packet with the
+    /// given packet number.
+    ///
+    /// `header` is the packet's header, up to and including the packet number
+    /// field, after header protection is removed. `payload_and_tag` is the
+    /// rest of the packet. On success, returns the plaintext payload, which
+    /// is at the start of `payload_and_tag`.
+    pub fn open_in_place<'in_out>(
+        &self,
+        packet_number: u64,
+        header: &[u8],
+        payload_and_tag: &'in_out mut [u8],
+    ) -> Result<&'in_out mut [u8], error::Unspecified> {
+        self.key.open_in_place(
+            self.iv.nonce_for(packet_number),
+            Aad::from(header),
+            payload_and_tag,
+        )
+    }
+
+    /// The key's AEAD algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> &'static aead::Algorithm {
+        self.key.algorithm()
+    }
+
+    /// The QUIC version the key was derived for.
+    #[inline]
+    pub fn version(&self) -> Version {
+        self.version
+    }
+}
+
+impl core::fmt::Debug for PacketKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        self.key.fmt_debug("PacketKey", f)
+    }
+}
@@ -0,0 +1,161 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! The Salsa20 stream cipher, HSalsa20, and XSalsa20, as specified in
+//! [Salsa20 specification] and [Extending the Salsa20 nonce].
+//!
+//! This is a portable implementation used for NaCl compatibility. The Salsa20
+//! core is also used, with fewer rounds, by scrypt.
+//!
+//! [Salsa20 specification]: https://cr.yp.to/snuffle/spec.pdf
+//! [Extending the Salsa20 nonce]: https://cr.yp.to/snuffle/xsalsa-20081128.pdf
+
+use crate::polyfill::ChunksFixed;
+
+pub(super) const KEY_LEN: usize = 32;
+
+/// The length of an HSalsa20 input.
+pub(super) const HSALSA20_INPUT_LEN: usize = 16;
+
+/// The length of a Salsa20 nonce.
+pub(super) const NONCE_LEN: usize = 8;
+
+/// The length of an XSalsa20 nonce.
+pub(super) const XSALSA20_NONCE_LEN: usize = HSALSA20_INPUT_LEN + NONCE_LEN;
+
+pub(super) const BLOCK_LEN: usize = 64;
+
+/// The number of 32-bit words in a Salsa20 block.
+pub(crate) const WORDS: usize = BLOCK_LEN / 4;
+
+// "expand 32-byte k".
+const SIGMA: [u32; 4] = [0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574];
+
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub(super) struct Key([u32; KEY_LEN / 4]);
+
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+impl Key {
+    pub(super) fn new(bytes: [u8; KEY_LEN]) -> Self {
+        let words: &[[u8; 4]; KEY_LEN / 4] = bytes.chunks_fixed();
+        let mut key = [0u32; KEY_LEN / 4];
+        key.iter_mut()
+            .zip(words.iter())
+            .for_each(|(k, w)| *k = u32::from_le_bytes(*w));
+        Self(key)
+    }
+
+    /// Derives the Salsa20 key for XSalsa20 with the given nonce, returning
+    /// the key and the remaining part of the nonce.
+    pub(super) fn xsalsa20(&self, nonce: &[u8; XSALSA20_NONCE_LEN]) -> (Self, [u8; NONCE_LEN]) {
+        let (hsalsa20_input, salsa20_nonce) = nonce.split_at(HSALSA20_INPUT_LEN);
+        let subkey = self.hsalsa20(hsalsa20_input.try_into().unwrap());
+        (Self::new(subkey), salsa20_nonce.try_into().unwrap())
+    }
+
+    /// HSalsa20: the Salsa20 core without the final addition of the input,
+    /// keeping only the words that don't depend on the key directly.
+    pub(super) fn hsalsa20(&self, input: &[u8; HSALSA20_INPUT_LEN]) -> [u8; KEY_LEN] {
+        let input: &[[u8; 4]; HSALSA20_INPUT_LEN / 4] = input.chunks_fixed();
+        let mut x = self.initial_state([
+            u32::from_le_bytes(input[0]),
+            u32::from_le_bytes(input[1]),
+            u32::from_le_bytes(input[2]),
+            u32::from_le_bytes(input[3]),
+        ]);
+        rounds(&mut x, 20);
+
+        let mut out = [0u8; KEY_LEN];
+        [0, 5, 10, 15, 6, 7, 8, 9]
+            .iter()
+            .zip(out.chunks_exact_mut(4))
+            .for_each(|(&i, out)| out.copy_from_slice(&x[i].to_le_bytes()));
+        out
+    }
+
+    /// Returns the Salsa20 keystream block with the given block counter.
+    pub(super) fn block(&self, nonce: &[u8; NONCE_LEN], counter: u64) -> [u8; BLOCK_LEN] {
+        let nonce: &[[u8; 4]; NONCE_LEN / 4] = nonce.chunks_fixed();
+        let mut x = self.initial_state([
+            u32::from_le_bytes(nonce[0]),
+            u32::from_le_bytes(nonce[1]),
+            counter as u32,
+            (counter >> 32) as u32,
+        ]);
+        core(&mut x, 20);
+
+        let mut out = [0u8; BLOCK_LEN];
+        x.iter()
+            .zip(out.chunks_exact_mut(4))
+            .for_each(|(x, out)| out.copy_from_slice(&x.to_le_bytes()));
+        out
+    }
+
+    /// XORs `in_out` with the Salsa20 keystream starting at block `counter`.
+    pub(super) fn xor_in_place(&self, nonce: &[u8; NONCE_LEN], counter: u64, in_out: &mut [u8]) {
+        let mut counter = counter;
+        for chunk in in_out.chunks_mut(BLOCK_LEN) {
+            let keystream = self.block(nonce, counter);
+            chunk
+                .iter_mut()
+                .zip(keystream.iter())
+                .for_each(|(b, k)| *b ^= *k);
+            counter = counter.wrapping_add(1);
+        }
+    }
+
+    fn initial_state(&self, input: [u32; 4]) -> [u32; WORDS] {
+        let k = &self.0;
+        [
+            SIGMA[0], k[0], k[1], k[2], k[3], SIGMA[1], input[0], input[1], input[2], input[3],
+            SIGMA[2], k[4], k[5], k[6], k[7], SIGMA[3],
+        ]
+    }
+}
+
+/// The Salsa20 core with `num_rounds` rounds: Salsa20 uses 20 rounds and
+/// scrypt's Salsa20/8 uses 8. `b` is replaced with the core's output.
+pub(crate) fn core(b: &mut [u32; WORDS], num_rounds: usize) {
+    let mut x = *b;
+    rounds(&mut x, num_rounds);
+    b.iter_mut()
+        .zip(x.iter())
+        .for_each(|(b, x)| *b = b.wrapping_add(*x));
+}
+
+// `num_rounds` rounds of the Salsa20 core, without the final addition.
+fn rounds(x: &mut [u32; WORDS], num_rounds: usize) {
+    #[inline(always)]
+    fn quarter_round(x: &mut [u32; WORDS], a: usize, b: usize, c: usize, d: usize) {
+        x[b] ^= x[a].wrapping_add(x[d]).rotate_left(7);
+        x[c] ^= x[b].wrapping_add(x[a]).rotate_left(9);
+        x[d] ^= x[c].wrapping_add(x[b]).rotate_left(13);
+        x[a] ^= x[d].wrapping_add(x[c]).rotate_left(18);
+    }
+
+    debug_assert_eq!(num_rounds % 2, 0);
+    for _ in 0..(num_rounds / 2) {
+        // Columns.
+        quarter_round(x, 0, 4, 8, 12);
+        quarter_round(x, 5, 9, 13, 1);
+        quarter_round(x, 10, 14, 2, 6);
+        quarter_round(x, 15, 3, 7, 11);
+
+        // Rows.
+        quarter_round(x, 0, 1, 2, 3);
+        quarter_round(x, 5, 6, 7, 4);
+        quarter_round(x, 10, 11, 8, 9);
+        quarter_round(x, 15, 12, 13, 14);
+    }
+}
@@ -22,7 +22,7 @@
 //! [`crypto.cipher.AEAD`]: https://golang.org/pkg/crypto/cipher/#AEAD
 
 use super::{Algorithm, LessSafeKey, MAX_KEY_LEN};
-use crate::{error, hkdf};
+use crate::{error, hkdf, kbkdf, sskdf};
 
 /// An AEAD key without a designated role or nonce sequence.
 pub struct UnboundKey {
@@ -49,6 +49,18 @@ impl UnboundKey {
         self.inner.algorithm()
     }
 
+    fn construct<F>(algorithm: &'static Algorithm, fill: F) -> Self
+    where
+        F: FnOnce(&mut [u8]) -> Result<(), error::Unspecified>,
+    {
+        let mut key_bytes = [0; MAX_KEY_LEN];
+        let key_bytes = &mut key_bytes[..algorithm.key_len];
+        fill(key_bytes).unwrap();
+        Self {
+            inner: LessSafeKey::new_(algorithm, key_bytes).unwrap(),
+        }
+    }
+
     #[inline]
     pub(super) fn into_inner(self) -> LessSafeKey {
         self.inner
@@ -63,12 +75,21 @@ impl core::fmt::Debug for UnboundKey {
 
 impl From<hkdf::Okm<'_, &'static Algorithm>> for UnboundKey {
     fn from(okm: hkdf::Okm<&'static Algorithm>) -> Self {
-        let mut key_bytes = [0; MAX_KEY_LEN];
-        let key_bytes = &mut key_bytes[..okm.len().key_len];
         let algorithm = *okm.len();
-        okm.fill(key_bytes).unwrap();
-        Self {
-            inner: LessSafeKey::new_(algorithm, key_bytes).unwrap(),
-        }
+        Self::construct(algorithm, |key_bytes| okm.fill(key_bytes))
+    }
+}
+
+impl From<kbkdf::Okm<'_, &'static Algorithm>> for UnboundKey {
+    fn from(okm: kbkdf::Okm<&'static Algorithm>) -> Self {
+        let algorithm = *okm.len();
+        Self::construct(algorithm, |key_bytes| okm.fill(key_bytes))
+    }
+}
+
+impl From<sskdf::Okm<'_, &'static Algorithm>> for UnboundKey {
+    fn from(okm: sskdf::Okm<&'static Algorithm>) -> Self {
+        let algorithm = *okm.len();
+        Self::construct(algorithm, |key_bytes| okm.fill(key_bytes))
     }
 }
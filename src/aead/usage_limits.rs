@@ -0,0 +1,424 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use super::{
+    vectored, Aad, Algorithm, AlgorithmID, LessSafeKey, Nonce, NonceSequence, OpeningKey,
+    SealingKey, Tag,
+};
+use crate::{error, polyfill};
+use core::ops::RangeFrom;
+
+/// Limits on the use of a single AEAD key.
+///
+/// The security of an AEAD degrades as more data is sealed with a key
+/// (the confidentiality limit) and as more forgery attempts are made against
+/// it (the integrity limit). See [RFC 9001 Section 6.6].
+///
+/// `quic()` gives the limits RFC 9001 defines for QUIC. The appropriate
+/// limits for other protocols depend on their record sizes and security
+/// targets, so they must be given to `new()`.
+///
+/// [RFC 9001 Section 6.6]: https://tools.ietf.org/html/rfc9001#section-6.6
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct UsageLimits {
+    max_sealed_records: u64,
+    max_sealed_bytes: u64,
+    max_failed_opens: u64,
+}
+
+impl UsageLimits {
+    /// Constructs limits allowing at most `max_sealed_records` seal
+    /// operations sealing at most `max_sealed_bytes` bytes of plaintext in
+    /// total, and at most `max_failed_opens` failed open operations.
+    pub const fn new(
+        max_sealed_records: u64,
+        max_sealed_bytes: u64,
+        max_failed_opens: u64,
+    ) -> Self {
+        Self {
+            max_sealed_records,
+            max_sealed_bytes,
+            max_failed_opens,
+        }
+    }
+
+    /// The limits for QUIC packet protection keys of the given algorithm from
+    /// [RFC 9001 Section 6.6].
+    ///
+    /// Fails if RFC 9001 doesn't define limits for `algorithm`.
+    ///
+    /// [RFC 9001 Section 6.6]: https://tools.ietf.org/html/rfc9001#section-6.6
+    pub fn quic(algorithm: &'static Algorithm) -> Result<Self, error::Unspecified> {
+        match algorithm.id {
+            AlgorithmID::AES_128_GCM | AlgorithmID::AES_256_GCM => {
+                Ok(Self::new(1 << 23, u64::MAX, 1 << 52))
+            }
+            // The confidentiality limit is larger than the number of possible
+            // packets.
+            AlgorithmID::CHACHA20_POLY1305 => Ok(Self::new(u64::MAX, u64::MAX, 1 << 36)),
+        }
+    }
+
+    /// The maximum number of seal operations.
+    #[inline]
+    pub fn max_sealed_records(&self) -> u64 {
+        self.max_sealed_records
+    }
+
+    /// The maximum total length of the plaintext of all seal operations.
+    #[inline]
+    pub fn max_sealed_bytes(&self) -> u64 {
+        self.max_sealed_bytes
+    }
+
+    /// The maximum number of failed open operations.
+    #[inline]
+    pub fn max_failed_opens(&self) -> u64 {
+        self.max_failed_opens
+    }
+}
+
+/// An AEAD key that counts its uses and fails once its `UsageLimits` are
+/// reached.
+///
+/// `K` is a `SealingKey`, an `OpeningKey`, or a `LessSafeKey`. A seal
+/// operation fails, without sealing anything, if it would exceed the limit
+/// on records or bytes sealed. Every failed open operation is counted, and
+/// once the limit on failed opens is reached every subsequent open operation
+/// fails. Once `limit_reached()` returns `true`, the key should be replaced,
+/// e.g. by a TLS or QUIC key update.
+///
+/// Every sealing and opening operation of the wrapped key has a counterpart
+/// here. Operations done through the `LessSafeKey` returned by `key()` aren't
+/// counted.
+pub struct UsageLimited<K> {
+    key: K,
+    limits: UsageLimits,
+    sealed_records: u64,
+    sealed_bytes: u64,
+    failed_opens: u64,
+}
+
+impl<K> UsageLimited<K> {
+    /// Wraps `key`, which hasn't been used yet, so that its use is limited by
+    /// `limits`.
+    pub fn new(key: K, limits: UsageLimits) -> Self {
+        Self {
+            key,
+            limits,
+            sealed_records: 0,
+            sealed_bytes: 0,
+            failed_opens: 0,
+        }
+    }
+
+    /// The wrapped key.
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// The limits.
+    #[inline]
+    pub fn limits(&self) -> &UsageLimits {
+        &self.limits
+    }
+
+    /// The number of successful seal operations so far.
+    #[inline]
+    pub fn sealed_records(&self) -> u64 {
+        self.sealed_records
+    }
+
+    /// The total length of the plaintext of the successful seal operations so
+    /// far.
+    #[inline]
+    pub fn sealed_bytes(&self) -> u64 {
+        self.sealed_bytes
+    }
+
+    /// The number of failed open operations so far.
+    #[inline]
+    pub fn failed_opens(&self) -> u64 {
+        self.failed_opens
+    }
+
+    /// Returns `true` if any of the limits has been reached, i.e. if the key
+    /// should be replaced now.
+    pub fn limit_reached(&self) -> bool {
+        self.sealed_records >= self.limits.max_sealed_records
+            || self.sealed_bytes >= self.limits.max_sealed_bytes
+            || self.failed_opens >= self.limits.max_failed_opens
+    }
+
+    fn seal<T>(
+        &mut self,
+        plaintext_len: usize,
+        f: impl FnOnce(&mut K) -> Result<T, error::Unspecified>,
+    ) -> Result<T, error::Unspecified> {
+        let sealed_records = self
+            .sealed_records
+            .checked_add(1)
+            .filter(|&records| records <= self.limits.max_sealed_records)
+            .ok_or(error::Unspecified)?;
+        let sealed_bytes = self
+            .sealed_bytes
+            .checked_add(polyfill::u64_from_usize(plaintext_len))
+            .filter(|&bytes| bytes <= self.limits.max_sealed_bytes)
+            .ok_or(error::Unspecified)?;
+
+        let result = f(&mut self.key)?;
+        self.sealed_records = sealed_records;
+        self.sealed_bytes = sealed_bytes;
+        Ok(result)
+    }
+
+    fn open<T>(
+        &mut self,
+        f: impl FnOnce(&mut K) -> Result<T, error::Unspecified>,
+    ) -> Result<T, error::Unspecified> {
+        if self.failed_opens >= self.limits.max_failed_opens {
+            return Err(error::Unspecified);
+        }
+        let result = f(&mut self.key);
+        if result.is_err() {
+            self.failed_opens += 1;
+        }
+        result
+    }
+}
+
+impl<K: core::fmt::Debug> core::fmt::Debug for UsageLimited<K> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("UsageLimited")
+            .field("key", &self.key)
+            .field("limits", &self.limits)
+            .field("sealed_records", &self.sealed_records)
+            .field("sealed_bytes", &self.sealed_bytes)
+            .field("failed_opens", &self.failed_opens)
+            .finish()
+    }
+}
+
+impl<N: NonceSequence> UsageLimited<SealingKey<N>> {
+    /// Like `SealingKey::seal_in_place_append_tag()`, except it fails if the
+    /// limits would be exceeded.
+    #[inline]
+    pub fn seal_in_place_append_tag<A, InOut>(
+        &mut self,
+        aad: Aad<A>,
+        in_out: &mut InOut,
+    ) -> Result<(), error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+        InOut: AsMut<[u8]> + for<'in_out> Extend<&'in_out u8>,
+    {
+        let len = in_out.as_mut().len();
+        self.seal(len, |key| key.seal_in_place_append_tag(aad, in_out))
+    }
+
+    /// Like `SealingKey::seal_in_place_separate_tag()`, except it fails if
+    /// the limits would be exceeded.
+    #[inline]
+    pub fn seal_in_place_separate_tag<A>(
+        &mut self,
+        aad: Aad<A>,
+        in_out: &mut [u8],
+    ) -> Result<Tag, error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        self.seal(in_out.len(), |key| {
+            key.seal_in_place_separate_tag(aad, in_out)
+        })
+    }
+}
+
+impl<N: NonceSequence> UsageLimited<OpeningKey<N>> {
+    /// Like `OpeningKey::open_in_place()`, except that failures are counted
+    /// and it fails once the limit on failed opens has been reached.
+    #[inline]
+    pub fn open_in_place<'in_out, A>(
+        &mut self,
+        aad: Aad<A>,
+        in_out: &'in_out mut [u8],
+    ) -> Result<&'in_out mut [u8], error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        self.open(|key| key.open_in_place(aad, in_out))
+    }
+
+    /// Like `OpeningKey::open_within()`, except that failures are counted
+    /// and it fails once the limit on failed opens has been reached.
+    #[inline]
+    pub fn open_within<'in_out, A>(
+        &mut self,
+        aad: Aad<A>,
+        in_out: &'in_out mut [u8],
+        ciphertext_and_tag: RangeFrom<usize>,
+    ) -> Result<&'in_out mut [u8], error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        self.open(|key| key.open_within(aad, in_out, ciphertext_and_tag))
+    }
+}
+
+impl UsageLimited<LessSafeKey> {
+    /// Like `LessSafeKey::seal_in_place_append_tag()`, except it fails if the
+    /// limits would be exceeded.
+    #[inline]
+    pub fn seal_in_place_append_tag<A, InOut>(
+        &mut self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        in_out: &mut InOut,
+    ) -> Result<(), error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+        InOut: AsMut<[u8]> + for<'in_out> Extend<&'in_out u8>,
+    {
+        let len = in_out.as_mut().len();
+        self.seal(len, |key| key.seal_in_place_append_tag(nonce, aad, in_out))
+    }
+
+    /// Like `LessSafeKey::seal_in_place_separate_tag()`, except it fails if
+    /// the limits would be exceeded.
+    #[inline]
+    pub fn seal_in_place_separate_tag<A>(
+        &mut self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        in_out: &mut [u8],
+    ) -> Result<Tag, error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        self.seal(in_out.len(), |key| {
+            key.seal_in_place_separate_tag(nonce, aad, in_out)
+        })
+    }
+
+    /// Like `LessSafeKey::open_in_place()`, except that failures are counted
+    /// and it fails once the limit on failed opens has been reached.
+    #[inline]
+    pub fn open_in_place<'in_out, A>(
+        &mut self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        in_out: &'in_out mut [u8],
+    ) -> Result<&'in_out mut [u8], error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        self.open(|key| key.open_in_place(nonce, aad, in_out))
+    }
+
+    /// Like `LessSafeKey::open_within()`, except that failures are counted
+    /// and it fails once the limit on failed opens has been reached.
+    #[inline]
+    pub fn open_within<'in_out, A>(
+        &mut self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        in_out: &'in_out mut [u8],
+        ciphertext_and_tag: RangeFrom<usize>,
+    ) -> Result<&'in_out mut [u8], error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        self.open(|key| key.open_within(nonce, aad, in_out, ciphertext_and_tag))
+    }
+
+    /// Like `LessSafeKey::seal_to()`, except it fails if the limits would be
+    /// exceeded.
+    #[inline]
+    pub fn seal_to<A>(
+        &mut self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        plaintext: &[u8],
+        ciphertext_and_tag_out: &mut [u8],
+    ) -> Result<(), error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        self.seal(plaintext.len(), |key| {
+            key.seal_to(nonce, aad, plaintext, ciphertext_and_tag_out)
+        })
+    }
+
+    /// Like `LessSafeKey::seal_in_place_separate_tag_vectored()`, except it
+    /// fails if the limits would be exceeded.
+    #[inline]
+    pub fn seal_in_place_separate_tag_vectored(
+        &mut self,
+        nonce: Nonce,
+        aad: &[&[u8]],
+        in_out: &mut [&mut [u8]],
+    ) -> Result<Tag, error::Unspecified> {
+        self.seal(vectored::total_len(in_out), |key| {
+            key.seal_in_place_separate_tag_vectored(nonce, aad, in_out)
+        })
+    }
+
+    /// Like `LessSafeKey::open_in_place_separate_tag()`, except that failures
+    /// are counted and it fails once the limit on failed opens has been
+    /// reached.
+    #[inline]
+    pub fn open_in_place_separate_tag<'in_out, A>(
+        &mut self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        tag: Tag,
+        in_out: &'in_out mut [u8],
+        ciphertext: RangeFrom<usize>,
+    ) -> Result<&'in_out mut [u8], error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        self.open(|key| key.open_in_place_separate_tag(nonce, aad, tag, in_out, ciphertext))
+    }
+
+    /// Like `LessSafeKey::open_to()`, except that failures are counted and it
+    /// fails once the limit on failed opens has been reached.
+    #[inline]
+    pub fn open_to<A>(
+        &mut self,
+        nonce: Nonce,
+        aad: Aad<A>,
+        ciphertext_and_tag: &[u8],
+        plaintext_out: &mut [u8],
+    ) -> Result<(), error::Unspecified>
+    where
+        A: AsRef<[u8]>,
+    {
+        self.open(|key| key.open_to(nonce, aad, ciphertext_and_tag, plaintext_out))
+    }
+
+    /// Like `LessSafeKey::open_in_place_separate_tag_vectored()`, except that
+    /// failures are counted and it fails once the limit on failed opens has
+    /// been reached.
+    #[inline]
+    pub fn open_in_place_separate_tag_vectored(
+        &mut self,
+        nonce: Nonce,
+        aad: &[&[u8]],
+        received_tag: Tag,
+        in_out: &mut [&mut [u8]],
+    ) -> Result<(), error::Unspecified> {
+        self.open(|key| key.open_in_place_separate_tag_vectored(nonce, aad, received_tag, in_out))
+    }
+}
@@ -0,0 +1,98 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Support for sealing and opening input that is split into multiple
+//! fragments ("scatter/gather" I/O).
+//!
+//! Whole blocks within a fragment are processed directly. When a block is
+//! split across fragments, one block of keystream is generated and its
+//! unused bytes are kept for the following fragments.
+
+use super::Direction;
+
+/// Encrypts or decrypts `in_out` using `f`, passing the ciphertext to
+/// `authenticate`: the output when sealing, and the input when opening.
+pub(super) fn crypt(
+    direction: Direction,
+    in_out: &mut [u8],
+    authenticate: &mut impl FnMut(&[u8]),
+    f: impl FnOnce(&mut [u8]),
+) {
+    if direction == Direction::Opening {
+        authenticate(in_out);
+    }
+    f(in_out);
+    if direction == Direction::Sealing {
+        authenticate(in_out);
+    }
+}
+
+/// The unused part of the last block of keystream.
+pub(super) struct Keystream<const BLOCK_LEN: usize> {
+    block: [u8; BLOCK_LEN],
+    used: usize,
+}
+
+impl<const BLOCK_LEN: usize> Keystream<BLOCK_LEN> {
+    pub fn new() -> Self {
+        Self {
+            block: [0; BLOCK_LEN],
+            used: BLOCK_LEN,
+        }
+    }
+
+    /// Returns the number of unused keystream bytes remaining.
+    pub fn remaining(&self) -> usize {
+        BLOCK_LEN - self.used
+    }
+
+    /// Replaces the (exhausted) keystream with a new block of keystream.
+    pub fn refill(&mut self, block: [u8; BLOCK_LEN]) {
+        debug_assert_eq!(self.remaining(), 0);
+        self.block = block;
+        self.used = 0;
+    }
+
+    /// XORs the unused keystream into the start of `in_out`. `in_out` must
+    /// not be longer than `self.remaining()`.
+    pub fn xor(&mut self, in_out: &mut [u8]) {
+        let keystream = &self.block[self.used..][..in_out.len()];
+        in_out
+            .iter_mut()
+            .zip(keystream.iter())
+            .for_each(|(b, k)| *b ^= *k);
+        self.used += in_out.len();
+    }
+}
+
+/// Splits off the start of `fragment` that completes a block begun in a
+/// previous fragment, the whole blocks after it, and the remainder.
+pub(super) fn split_fragment<'a, const BLOCK_LEN: usize>(
+    fragment: &'a mut [u8],
+    keystream: &Keystream<BLOCK_LEN>,
+) -> (&'a mut [u8], &'a mut [u8], &'a mut [u8]) {
+    let partial_len = core::cmp::min(keystream.remaining(), fragment.len());
+    let (partial, rest) = fragment.split_at_mut(partial_len);
+    let whole_len = rest.len() - (rest.len() % BLOCK_LEN);
+    let (whole, remainder) = rest.split_at_mut(whole_len);
+    (partial, whole, remainder)
+}
+
+/// The total length of `fragments`.
+pub(super) fn total_len<T: AsRef<[u8]>>(fragments: &[T]) -> usize {
+    fragments
+        .iter()
+        .map(|fragment| fragment.as_ref().len())
+        .sum()
+}
@@ -62,6 +62,9 @@
 
 use crate::{cpu, debug, ec, error, rand};
 
+#[cfg(feature = "alloc")]
+use crate::spki;
+
 pub use crate::ec::{
     curve25519::x25519::X25519,
     suite_b::ecdh::{ECDH_P256, ECDH_P384},
@@ -175,6 +178,22 @@ impl PublicKey {
     pub fn algorithm(&self) -> &'static Algorithm {
         self.algorithm
     }
+
+    /// The public key as a DER-encoded SubjectPublicKeyInfo.
+    ///
+    /// X25519 keys are identified by `id-X25519` ([RFC 8410 Section 4]), and
+    /// P-256 and P-384 keys by `id-ecPublicKey` and the curve's name
+    /// ([RFC 5480 Section 2]), as for ECDSA keys.
+    ///
+    /// [RFC 8410 Section 4]: https://tools.ietf.org/html/rfc8410#section-4
+    /// [RFC 5480 Section 2]: https://tools.ietf.org/html/rfc5480#section-2
+    #[cfg(feature = "alloc")]
+    pub fn to_spki(&self) -> spki::Document {
+        spki::wrap_key(
+            spki::KeyType::for_curve(self.algorithm.curve),
+            self.as_ref(),
+        )
+    }
 }
 
 /// An unparsed, possibly malformed, public key for key agreement.
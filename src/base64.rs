@@ -0,0 +1,246 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Base64 encoding and decoding using the standard alphabet from
+//! [RFC 4648 Section 4], with or without padding, and using the URL-safe
+//! alphabet from [RFC 4648 Section 5] without padding.
+//!
+//! Decoding is strict: only canonical encodings are accepted, so every
+//! sequence of bytes has exactly one valid encoding.
+//!
+//! [RFC 4648 Section 4]: https://tools.ietf.org/html/rfc4648#section-4
+//! [RFC 4648 Section 5]: https://tools.ietf.org/html/rfc4648#section-5
+
+use crate::error;
+use alloc::{string::String, vec::Vec};
+
+#[derive(Clone, Copy)]
+enum Alphabet {
+    Standard,
+    UrlSafe,
+}
+
+/// Appends the unpadded base64 encoding of `input` to `out`.
+pub(crate) fn encode_unpadded(input: &[u8], out: &mut String) {
+    encode_unpadded_(input, Alphabet::Standard, out)
+}
+
+/// Decodes the unpadded base64 encoding `input`.
+pub(crate) fn decode_unpadded(input: &[u8]) -> Result<Vec<u8>, error::Unspecified> {
+    decode_unpadded_(input, Alphabet::Standard)
+}
+
+/// Appends the unpadded base64url encoding of `input` to `out`.
+#[cfg_attr(not(feature = "jose"), allow(dead_code))]
+pub(crate) fn encode_url_unpadded(input: &[u8], out: &mut String) {
+    encode_unpadded_(input, Alphabet::UrlSafe, out)
+}
+
+/// Decodes the unpadded base64url encoding `input`.
+#[cfg_attr(not(feature = "jose"), allow(dead_code))]
+pub(crate) fn decode_url_unpadded(input: &[u8]) -> Result<Vec<u8>, error::Unspecified> {
+    decode_unpadded_(input, Alphabet::UrlSafe)
+}
+
+fn encode_unpadded_(input: &[u8], alphabet: Alphabet, out: &mut String) {
+    for chunk in input.chunks(3) {
+        let b0 = chunk[0];
+        let b1 = chunk.get(1).copied().unwrap_or(0);
+        let b2 = chunk.get(2).copied().unwrap_or(0);
+        let sextets = [
+            b0 >> 2,
+            ((b0 & 0x03) << 4) | (b1 >> 4),
+            ((b1 & 0x0f) << 2) | (b2 >> 6),
+            b2 & 0x3f,
+        ];
+        sextets[..(chunk.len() + 1)]
+            .iter()
+            .for_each(|&sextet| out.push(char::from(encode_sextet(sextet, alphabet))));
+    }
+}
+
+fn decode_unpadded_(input: &[u8], alphabet: Alphabet) -> Result<Vec<u8>, error::Unspecified> {
+    let mut out = Vec::with_capacity((input.len() / 4) * 3 + 2);
+    for chunk in input.chunks(4) {
+        if chunk.len() == 1 {
+            return Err(error::Unspecified);
+        }
+        let mut sextets = [0u8; 4];
+        for (sextet, &c) in sextets.iter_mut().zip(chunk) {
+            *sextet = decode_sextet(c, alphabet)?;
+        }
+        let bytes = [
+            (sextets[0] << 2) | (sextets[1] >> 4),
+            (sextets[1] << 4) | (sextets[2] >> 2),
+            (sextets[2] << 6) | sextets[3],
+        ];
+        let len = chunk.len() - 1;
+
+        // Reject non-canonical encodings where the unused low bits of the
+        // last character are not zero.
+        let unused_bits = match len {
+            1 => sextets[1] & 0x0f,
+            2 => sextets[2] & 0x03,
+            _ => 0,
+        };
+        if unused_bits != 0 {
+            return Err(error::Unspecified);
+        }
+
+        out.extend_from_slice(&bytes[..len]);
+    }
+    Ok(out)
+}
+
+pub(crate) fn encode_padded(input: &[u8], out: &mut String) {
+    encode_unpadded(input, out);
+    let padding_len = (3 - (input.len() % 3)) % 3;
+    (0..padding_len).for_each(|_| out.push('='));
+}
+
+pub(crate) fn decode_padded(input: &[u8]) -> Result<Vec<u8>, error::Unspecified> {
+    if input.len() % 4 != 0 {
+        return Err(error::Unspecified);
+    }
+    // Any other '=' is rejected by `decode_unpadded`.
+    let unpadded = match input {
+        [unpadded @ .., b'=', b'='] | [unpadded @ .., b'='] => unpadded,
+        _ => input,
+    };
+    decode_unpadded(unpadded)
+}
+
+fn encode_sextet(sextet: u8, alphabet: Alphabet) -> u8 {
+    match (sextet, alphabet) {
+        (0..=25, _) => b'A' + sextet,
+        (26..=51, _) => b'a' + (sextet - 26),
+        (52..=61, _) => b'0' + (sextet - 52),
+        (62, Alphabet::Standard) => b'+',
+        (63, Alphabet::Standard) => b'/',
+        (62, Alphabet::UrlSafe) => b'-',
+        (63, Alphabet::UrlSafe) => b'_',
+        _ => unreachable!(),
+    }
+}
+
+fn decode_sextet(c: u8, alphabet: Alphabet) -> Result<u8, error::Unspecified> {
+    match (c, alphabet) {
+        (b'A'..=b'Z', _) => Ok(c - b'A'),
+        (b'a'..=b'z', _) => Ok(c - b'a' + 26),
+        (b'0'..=b'9', _) => Ok(c - b'0' + 52),
+        (b'+', Alphabet::Standard) => Ok(62),
+        (b'/', Alphabet::Standard) => Ok(63),
+        (b'-', Alphabet::UrlSafe) => Ok(62),
+        (b'_', Alphabet::UrlSafe) => Ok(63),
+        _ => Err(error::Unspecified),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 4648 Section 10, with the padding removed.
+    static TEST_VECTORS: &[(&[u8], &str)] = &[
+        (b"", ""),
+        (b"f", "Zg"),
+        (b"fo", "Zm8"),
+        (b"foo", "Zm9v"),
+        (b"foob", "Zm9vYg"),
+        (b"fooba", "Zm9vYmE"),
+        (b"foobar", "Zm9vYmFy"),
+    ];
+
+    #[test]
+    fn test_round_trip() {
+        for &(decoded, encoded) in TEST_VECTORS {
+            let mut actual = String::new();
+            encode_unpadded(decoded, &mut actual);
+            assert_eq!(actual, encoded);
+            assert_eq!(decode_unpadded(encoded.as_bytes()).unwrap(), decoded);
+        }
+
+        let all_bytes = (0..=255).collect::<Vec<u8>>();
+        let mut encoded = String::new();
+        encode_unpadded(&all_bytes, &mut encoded);
+        assert_eq!(decode_unpadded(encoded.as_bytes()).unwrap(), all_bytes);
+    }
+
+    // RFC 4648 Section 10.
+    static PADDED_TEST_VECTORS: &[(&[u8], &str)] = &[
+        (b"", ""),
+        (b"f", "Zg=="),
+        (b"fo", "Zm8="),
+        (b"foo", "Zm9v"),
+        (b"foob", "Zm9vYg=="),
+        (b"fooba", "Zm9vYmE="),
+        (b"foobar", "Zm9vYmFy"),
+    ];
+
+    #[test]
+    fn test_round_trip_padded() {
+        for &(decoded, encoded) in PADDED_TEST_VECTORS {
+            let mut actual = String::new();
+            encode_padded(decoded, &mut actual);
+            assert_eq!(actual, encoded);
+            assert_eq!(decode_padded(encoded.as_bytes()).unwrap(), decoded);
+        }
+    }
+
+    #[test]
+    fn test_decode_padded_invalid() {
+        for &invalid in &[
+            "Zg",       // Missing padding.
+            "Zg=",      // Incomplete padding.
+            "Zg===",    // Too much padding.
+            "Z===",     // Impossible length.
+            "Zm9v====", // A block of only padding.
+            "Zh==",     // Non-zero unused bits.
+            "Zg==Zm9v", // Padding in the middle.
+            "Zm=v",     // Padding in the middle of a block.
+        ] {
+            assert!(decode_padded(invalid.as_bytes()).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_url_safe() {
+        let input = [0xfb, 0xff, 0xbf];
+        let mut standard = String::new();
+        encode_unpadded(&input, &mut standard);
+        assert_eq!(standard, "+/+/");
+        let mut url_safe = String::new();
+        encode_url_unpadded(&input, &mut url_safe);
+        assert_eq!(url_safe, "-_-_");
+        assert_eq!(decode_url_unpadded(b"-_-_").unwrap(), input);
+
+        // The alphabets aren't mixed.
+        assert!(decode_url_unpadded(b"+/+/").is_err());
+        assert!(decode_unpadded(b"-_-_").is_err());
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        for &invalid in &[
+            "Z",       // Impossible length.
+            "Zh",      // Non-zero unused bits.
+            "Zm9=",    // Padding.
+            "Zm9vYmF", // Non-zero unused bits.
+            "Zm 9v",   // Whitespace.
+            "Zm-v",    // URL-safe alphabet.
+        ] {
+            assert!(decode_unpadded(invalid.as_bytes()).is_err(), "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,164 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! BLAKE2b, as specified in [RFC 7693].
+//!
+//! This is a portable implementation used to derive the nonces of NaCl
+//! sealed boxes.
+//!
+//! [RFC 7693]: https://www.rfc-editor.org/rfc/rfc7693
+
+/// The maximum output length of BLAKE2b.
+pub(crate) const MAX_OUTPUT_LEN: usize = 64;
+
+/// Unkeyed BLAKE2b with an output of `out.len()` bytes, which must be at most
+/// `MAX_OUTPUT_LEN`.
+pub(crate) fn blake2b(input: &[u8], out: &mut [u8]) {
+    const BLOCK_LEN: usize = 128;
+    const IV: [u64; 8] = [
+        0x6a09_e667_f3bc_c908,
+        0xbb67_ae85_84ca_a73b,
+        0x3c6e_f372_fe94_f82b,
+        0xa54f_f53a_5f1d_36f1,
+        0x510e_527f_ade6_82d1,
+        0x9b05_688c_2b3e_6c1f,
+        0x1f83_d9ab_fb41_bd6b,
+        0x5be0_cd19_137e_2179,
+    ];
+    const SIGMA: [[usize; 16]; 10] = [
+        [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+        [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+        [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+        [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+        [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+        [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+        [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+        [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+        [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+        [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+    ];
+
+    #[inline(always)]
+    #[allow(clippy::many_single_char_names)]
+    fn g(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize, x: u64, y: u64) {
+        v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+        v[d] = (v[d] ^ v[a]).rotate_right(32);
+        v[c] = v[c].wrapping_add(v[d]);
+        v[b] = (v[b] ^ v[c]).rotate_right(24);
+        v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+        v[d] = (v[d] ^ v[a]).rotate_right(16);
+        v[c] = v[c].wrapping_add(v[d]);
+        v[b] = (v[b] ^ v[c]).rotate_right(63);
+    }
+
+    fn compress(h: &mut [u64; 8], block: &[u8; BLOCK_LEN], t: u128, last: bool) {
+        let mut m = [0u64; 16];
+        m.iter_mut()
+            .zip(block.chunks_exact(8))
+            .for_each(|(m, b)| *m = u64::from_le_bytes(b.try_into().unwrap()));
+
+        let mut v = [0u64; 16];
+        v[..8].copy_from_slice(h);
+        v[8..].copy_from_slice(&IV);
+        v[12] ^= t as u64;
+        v[13] ^= (t >> 64) as u64;
+        if last {
+            v[14] = !v[14];
+        }
+
+        for round in 0..12 {
+            let s = &SIGMA[round % 10];
+            g(&mut v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+            g(&mut v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+            g(&mut v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+            g(&mut v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+            g(&mut v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+            g(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+            g(&mut v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+            g(&mut v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+        }
+
+        h.iter_mut()
+            .zip(v[..8].iter().zip(v[8..].iter()))
+            .for_each(|(h, (v_lo, v_hi))| *h ^= v_lo ^ v_hi);
+    }
+
+    debug_assert!(out.len() <= MAX_OUTPUT_LEN);
+    let mut h = IV;
+    h[0] ^= 0x0101_0000 ^ (out.len() as u64);
+
+    // The last block is always processed separately, even if it is full.
+    let last_len = match input.len() % BLOCK_LEN {
+        0 if !input.is_empty() => BLOCK_LEN,
+        n => n,
+    };
+    let (blocks, last) = input.split_at(input.len() - last_len);
+    let mut t: u128 = 0;
+    for block in blocks.chunks_exact(BLOCK_LEN) {
+        t += BLOCK_LEN as u128;
+        compress(&mut h, block.try_into().unwrap(), t, false);
+    }
+    let mut last_block = [0u8; BLOCK_LEN];
+    last_block[..last.len()].copy_from_slice(last);
+    t += last.len() as u128;
+    compress(&mut h, &last_block, t, true);
+
+    out.iter_mut()
+        .zip(h.iter().flat_map(|h| h.to_le_bytes()))
+        .for_each(|(out, h)| *out = h);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::from_hex;
+
+    // RFC 7693 Appendix A.
+    #[test]
+    fn test_blake2b_512_abc() {
+        let expected = from_hex(
+            "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+             7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923",
+        )
+        .unwrap();
+        let mut actual = [0u8; MAX_OUTPUT_LEN];
+        blake2b(b"abc", &mut actual);
+        assert_eq!(&actual[..], &expected[..]);
+    }
+
+    // Empty input, inputs at and around the block boundary, and a truncated
+    // output of the length used for NaCl sealed boxes.
+    #[test]
+    fn test_blake2b_lengths() {
+        const TESTS: &[(usize, usize, &str)] = &[
+            (
+                0,
+                64,
+                "786a02f742015903c6c6fd852552d272912f4740e15847618a86e217f71f5419\
+                 d25e1031afee585313896444934eb04b903a685b1448b755d56f701afe9be2ce",
+            ),
+            (128, 24, "2f5f6c3d92c87ec6916cd0034fea92dfd83b28dc91485d41"),
+            (129, 24, "921c466b582e135fab21c8e052fe3715d9113bcd864276ba"),
+            (255, 24, "70659e8f816c47e625d11b16b2480a2ef53c091dee2aaf9c"),
+        ];
+        let mut input = [0u8; 255];
+        input.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+        for &(input_len, output_len, expected) in TESTS {
+            let expected = from_hex(expected).unwrap();
+            let mut actual = [0u8; MAX_OUTPUT_LEN];
+            blake2b(&input[..input_len], &mut actual[..output_len]);
+            assert_eq!(&actual[..output_len], &expected[..]);
+        }
+    }
+}
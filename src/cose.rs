@@ -0,0 +1,48 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! CBOR Object Signing and Encryption (COSE).
+//!
+//! COSE_Keys ([RFC 9052 Section 7]) are supported for RSA ([RFC 8230]),
+//! P-256 and P-384 ([RFC 9053 Section 7.1]), and Ed25519 and X25519
+//! ([RFC 9053 Section 7.2]) keys.
+//!
+//! COSE_Sign1 messages ([RFC 9052 Section 4.2]) can be signed and verified
+//! with `ES256`, `ES384`, `EdDSA`, and `PS256`, and COSE_Encrypt0 messages
+//! ([RFC 9052 Section 5.2]) can be encrypted and decrypted with an
+//! `aead::LessSafeKey` for AES-128-GCM, AES-256-GCM, or ChaCha20-Poly1305.
+//! Only the `alg`, `kid`, and `IV` header parameters are supported.
+//!
+//! [RFC 9052 Section 7]: https://tools.ietf.org/html/rfc9052#section-7
+//! [RFC 8230]: https://tools.ietf.org/html/rfc8230
+//! [RFC 9053 Section 7.1]: https://tools.ietf.org/html/rfc9053#section-7.1
+//! [RFC 9053 Section 7.2]: https://tools.ietf.org/html/rfc9053#section-7.2
+//! [RFC 9052 Section 4.2]: https://tools.ietf.org/html/rfc9052#section-4.2
+//! [RFC 9052 Section 5.2]: https://tools.ietf.org/html/rfc9052#section-5.2
+
+mod cbor;
+mod encrypt0;
+mod header;
+mod key;
+mod sign1;
+
+pub use self::{
+    encrypt0::{encrypt0, Encrypt0},
+    key::Key,
+    sign1::{sign1, Sign1, SignatureAlgorithm, EDDSA, ES256, ES384, PS256},
+};
+pub use crate::{
+    key_components::{Curve, KeyComponents},
+    signer::SigningKey,
+};
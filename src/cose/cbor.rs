@@ -0,0 +1,400 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! A minimal CBOR ([RFC 8949]) parser and serializer.
+//!
+//! Parsing is strict: only definite-length items whose arguments are encoded
+//! in the fewest possible bytes are accepted, text strings must be UTF-8,
+//! maps must not contain duplicate keys, and the only simple values accepted
+//! are `false`, `true`, and `null`; floating-point numbers aren't supported.
+//! Serialization uses the core deterministic encoding of
+//! [RFC 8949 Section 4.2.1], so map keys are sorted by their encodings.
+//!
+//! [RFC 8949]: https://tools.ietf.org/html/rfc8949
+//! [RFC 8949 Section 4.2.1]: https://tools.ietf.org/html/rfc8949#section-4.2.1
+
+use crate::error;
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+/// The maximum nesting depth of arrays, maps, and tags.
+const MAX_DEPTH: usize = 32;
+
+const MAJOR_UNSIGNED: u8 = 0;
+const MAJOR_NEGATIVE: u8 = 1;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_MAP: u8 = 5;
+const MAJOR_TAG: u8 = 6;
+const MAJOR_SIMPLE: u8 = 7;
+
+const SIMPLE_FALSE: u64 = 20;
+const SIMPLE_TRUE: u64 = 21;
+const SIMPLE_NULL: u64 = 22;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) enum Value {
+    Unsigned(u64),
+
+    /// The negative integer `-1 - n`.
+    Negative(u64),
+
+    Bytes(Vec<u8>),
+    Text(String),
+    Array(Vec<Value>),
+    Map(Vec<(Value, Value)>),
+    Tag(u64, Box<Value>),
+    Bool(bool),
+    Null,
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        if value < 0 {
+            Self::Negative(!value as u64)
+        } else {
+            Self::Unsigned(value as u64)
+        }
+    }
+}
+
+impl Value {
+    /// Parses `input`, which must contain exactly one CBOR data item.
+    pub(super) fn parse(input: &[u8]) -> Result<Self, error::Unspecified> {
+        untrusted::Input::from(input).read_all(error::Unspecified, |input| parse_item(input, 0))
+    }
+
+    /// Appends the deterministic encoding of the value to `out`.
+    pub(super) fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::Unsigned(n) => write_head(MAJOR_UNSIGNED, *n, out),
+            Self::Negative(n) => write_head(MAJOR_NEGATIVE, *n, out),
+            Self::Bytes(bytes) => {
+                write_head(MAJOR_BYTES, bytes.len() as u64, out);
+                out.extend_from_slice(bytes);
+            }
+            Self::Text(text) => {
+                write_head(MAJOR_TEXT, text.len() as u64, out);
+                out.extend_from_slice(text.as_bytes());
+            }
+            Self::Array(elements) => {
+                write_head(MAJOR_ARRAY, elements.len() as u64, out);
+                elements.iter().for_each(|element| element.write(out));
+            }
+            Self::Map(entries) => {
+                let mut encoded: Vec<(Vec<u8>, &Self)> = entries
+                    .iter()
+                    .map(|(key, value)| {
+                        let mut encoded_key = Vec::new();
+                        key.write(&mut encoded_key);
+                        (encoded_key, value)
+                    })
+                    .collect();
+                encoded.sort_by(|(a, _), (b, _)| a.cmp(b));
+                write_head(MAJOR_MAP, encoded.len() as u64, out);
+                for (key, value) in encoded {
+                    out.extend_from_slice(&key);
+                    value.write(out);
+                }
+            }
+            Self::Tag(tag, value) => {
+                write_head(MAJOR_TAG, *tag, out);
+                value.write(out);
+            }
+            Self::Bool(false) => write_head(MAJOR_SIMPLE, SIMPLE_FALSE, out),
+            Self::Bool(true) => write_head(MAJOR_SIMPLE, SIMPLE_TRUE, out),
+            Self::Null => write_head(MAJOR_SIMPLE, SIMPLE_NULL, out),
+        }
+    }
+
+    /// The value, if it is an integer that fits in an `i64`.
+    pub(super) fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Self::Unsigned(n) => i64::try_from(n).ok(),
+            Self::Negative(n) => i64::try_from(n).ok().map(|n| -1 - n),
+            _ => None,
+        }
+    }
+
+    /// The value, if it is a byte string.
+    pub(super) fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// The entries of the value, if it is a map.
+    pub(super) fn as_map(&self) -> Option<&[(Self, Self)]> {
+        match self {
+            Self::Map(entries) => Some(entries),
+            _ => None,
+        }
+    }
+}
+
+fn write_head(major_type: u8, argument: u64, out: &mut Vec<u8>) {
+    let major_type = major_type << 5;
+    if argument < 24 {
+        out.push(major_type | argument as u8);
+    } else if let Ok(argument) = u8::try_from(argument) {
+        out.extend_from_slice(&[major_type | 24, argument]);
+    } else if let Ok(argument) = u16::try_from(argument) {
+        out.push(major_type | 25);
+        out.extend_from_slice(&argument.to_be_bytes());
+    } else if let Ok(argument) = u32::try_from(argument) {
+        out.push(major_type | 26);
+        out.extend_from_slice(&argument.to_be_bytes());
+    } else {
+        out.push(major_type | 27);
+        out.extend_from_slice(&argument.to_be_bytes());
+    }
+}
+
+// Reads the initial byte and argument of a data item, returning the major
+// type and the argument.
+fn read_head(input: &mut untrusted::Reader) -> Result<(u8, u64), error::Unspecified> {
+    let initial = input.read_byte()?;
+    let major_type = initial >> 5;
+    let additional_info = initial & 0x1f;
+    let (len, min) = match additional_info {
+        0..=23 => return Ok((major_type, u64::from(additional_info))),
+        24 => (1, 24),
+        25 => (2, 0x100),
+        26 => (4, 0x1_0000),
+        27 => (8, 0x1_0000_0000),
+        // Reserved values and indefinite lengths.
+        _ => return Err(error::Unspecified),
+    };
+    let mut argument = 0u64;
+    for _ in 0..len {
+        argument = (argument << 8) | u64::from(input.read_byte()?);
+    }
+    // Only the shortest encoding of the argument is accepted.
+    if argument < min {
+        return Err(error::Unspecified);
+    }
+    Ok((major_type, argument))
+}
+
+fn read_len(argument: u64) -> Result<usize, error::Unspecified> {
+    usize::try_from(argument).map_err(|_| error::Unspecified)
+}
+
+fn parse_item(input: &mut untrusted::Reader, depth: usize) -> Result<Value, error::Unspecified> {
+    if depth > MAX_DEPTH {
+        return Err(error::Unspecified);
+    }
+    let (major_type, argument) = read_head(input)?;
+    Ok(match major_type {
+        MAJOR_UNSIGNED => Value::Unsigned(argument),
+        MAJOR_NEGATIVE => Value::Negative(argument),
+        MAJOR_BYTES => {
+            let bytes = input.read_bytes(read_len(argument)?)?;
+            Value::Bytes(bytes.as_slice_less_safe().to_vec())
+        }
+        MAJOR_TEXT => {
+            let bytes = input.read_bytes(read_len(argument)?)?;
+            let text =
+                core::str::from_utf8(bytes.as_slice_less_safe()).map_err(|_| error::Unspecified)?;
+            Value::Text(String::from(text))
+        }
+        MAJOR_ARRAY => {
+            let len = read_len(argument)?;
+            let mut elements = Vec::new();
+            for _ in 0..len {
+                elements.push(parse_item(input, depth + 1)?);
+            }
+            Value::Array(elements)
+        }
+        MAJOR_MAP => {
+            let len = read_len(argument)?;
+            let mut entries: Vec<(Value, Value)> = Vec::new();
+            let mut encoded_keys = Vec::new();
+            for _ in 0..len {
+                let (encoded_key, key) =
+                    input.read_partial(|input| parse_item(input, depth + 1))?;
+                let value = parse_item(input, depth + 1)?;
+                encoded_keys.push(encoded_key.as_slice_less_safe());
+                entries.push((key, value));
+            }
+
+            // Only one encoding of each key is accepted, so keys are equal
+            // exactly when their encodings are. Sorting the encodings instead
+            // of comparing each pair of keys keeps maps with many entries from
+            // taking quadratic time.
+            encoded_keys.sort_unstable();
+            if encoded_keys.windows(2).any(|pair| pair[0] == pair[1]) {
+                return Err(error::Unspecified);
+            }
+
+            Value::Map(entries)
+        }
+        MAJOR_TAG => Value::Tag(argument, Box::new(parse_item(input, depth + 1)?)),
+        MAJOR_SIMPLE => match argument {
+            SIMPLE_FALSE => Value::Bool(false),
+            SIMPLE_TRUE => Value::Bool(true),
+            SIMPLE_NULL => Value::Null,
+            _ => return Err(error::Unspecified),
+        },
+        _ => unreachable!(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test;
+    use alloc::{string::ToString, vec};
+
+    #[test]
+    fn test_round_trip() {
+        // Examples from RFC 8949 Appendix A.
+        let cases: &[(&str, Value)] = &[
+            ("00", Value::Unsigned(0)),
+            ("17", Value::Unsigned(23)),
+            ("1818", Value::Unsigned(24)),
+            ("1903e8", Value::Unsigned(1000)),
+            ("1a000f4240", Value::Unsigned(1000000)),
+            ("1b000000e8d4a51000", Value::Unsigned(1000000000000)),
+            ("1bffffffffffffffff", Value::Unsigned(u64::MAX)),
+            ("20", Value::from(-1)),
+            ("3863", Value::from(-100)),
+            ("3bffffffffffffffff", Value::Negative(u64::MAX)),
+            ("40", Value::Bytes(vec![])),
+            ("4401020304", Value::Bytes(vec![1, 2, 3, 4])),
+            ("60", Value::Text("".to_string())),
+            ("62c3bc", Value::Text("\u{fc}".to_string())),
+            ("f4", Value::Bool(false)),
+            ("f5", Value::Bool(true)),
+            ("f6", Value::Null),
+            (
+                "c11a514b67b0",
+                Value::Tag(1, Box::new(Value::Unsigned(1363896240))),
+            ),
+            (
+                "8301820203820405",
+                Value::Array(vec![
+                    Value::Unsigned(1),
+                    Value::Array(vec![Value::Unsigned(2), Value::Unsigned(3)]),
+                    Value::Array(vec![Value::Unsigned(4), Value::Unsigned(5)]),
+                ]),
+            ),
+            (
+                "a201020304",
+                Value::Map(vec![
+                    (Value::Unsigned(1), Value::Unsigned(2)),
+                    (Value::Unsigned(3), Value::Unsigned(4)),
+                ]),
+            ),
+        ];
+        for (encoded, value) in cases {
+            let encoded = test::from_hex(encoded).unwrap();
+            assert_eq!(Value::parse(&encoded).as_ref(), Ok(value));
+            let mut out = Vec::new();
+            value.write(&mut out);
+            assert_eq!(out, encoded);
+        }
+    }
+
+    #[test]
+    fn test_as_i64() {
+        assert_eq!(Value::from(i64::MIN).as_i64(), Some(i64::MIN));
+        assert_eq!(Value::from(i64::MAX).as_i64(), Some(i64::MAX));
+        assert_eq!(Value::Unsigned(u64::MAX).as_i64(), None);
+        assert_eq!(Value::Negative(u64::MAX).as_i64(), None);
+    }
+
+    #[test]
+    fn test_write_sorts_map_keys() {
+        let value = Value::Map(vec![
+            (Value::from(-1), Value::Null),
+            (Value::Text("a".to_string()), Value::Null),
+            (Value::Unsigned(24), Value::Null),
+            (Value::Unsigned(1), Value::Null),
+        ]);
+        let mut out = Vec::new();
+        value.write(&mut out);
+        assert_eq!(out, test::from_hex("a401f61818f620f66161f6").unwrap());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        const INVALID: &[&str] = &[
+            "",
+            "0000",
+            // Non-shortest arguments.
+            "1817",
+            "190017",
+            "1a0000ffff",
+            "1b00000000ffffffff",
+            // Reserved additional information values.
+            "1c",
+            // Indefinite lengths.
+            "5f4101ff",
+            "9fff",
+            "bfff",
+            // Truncated.
+            "1901",
+            "4201",
+            "8201",
+            "a101",
+            // Invalid UTF-8.
+            "61ff",
+            // Duplicate map keys.
+            "a20102010a",
+            "a3010203040105",
+            // Undefined, other simple values, and floats.
+            "f7",
+            "f0",
+            "f93c00",
+            "fa47c35000",
+        ];
+        for input in INVALID {
+            let input = test::from_hex(input).unwrap();
+            assert_eq!(Value::parse(&input), Err(error::Unspecified), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_many_entries() {
+        let encode = |keys: &[u64]| {
+            let mut encoded = Vec::new();
+            write_head(MAJOR_MAP, keys.len() as u64, &mut encoded);
+            for &key in keys {
+                Value::Unsigned(key).write(&mut encoded);
+                Value::Null.write(&mut encoded);
+            }
+            encoded
+        };
+
+        let mut keys = (0..10_000).collect::<Vec<u64>>();
+        let value = Value::parse(&encode(&keys)).unwrap();
+        assert_eq!(value.as_map().unwrap().len(), 10_000);
+
+        keys.push(0);
+        assert_eq!(Value::parse(&encode(&keys)), Err(error::Unspecified));
+    }
+
+    #[test]
+    fn test_parse_depth() {
+        let nested = |depth| {
+            let mut encoded = vec![0x81; depth];
+            encoded.push(0);
+            encoded
+        };
+        assert!(Value::parse(&nested(MAX_DEPTH)).is_ok());
+        assert!(Value::parse(&nested(MAX_DEPTH + 1)).is_err());
+    }
+}
@@ -0,0 +1,148 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use super::{
+    cbor::Value,
+    header::{parse_message, write_message, Headers},
+};
+use crate::{aead, error};
+use alloc::{vec, vec::Vec};
+
+const TAG_ENCRYPT0: u64 = 16;
+
+// Content encryption algorithm identifiers (RFC 9053 Sections 4.1 and 4.3).
+const ALG_A128GCM: i64 = 1;
+const ALG_A256GCM: i64 = 3;
+const ALG_CHACHA20_POLY1305: i64 = 24;
+
+// The identifier of `algorithm`, if COSE defines one that is supported.
+fn algorithm_id(algorithm: &aead::Algorithm) -> Option<i64> {
+    if *algorithm == aead::AES_128_GCM {
+        Some(ALG_A128GCM)
+    } else if *algorithm == aead::AES_256_GCM {
+        Some(ALG_A256GCM)
+    } else if *algorithm == aead::CHACHA20_POLY1305 {
+        Some(ALG_CHACHA20_POLY1305)
+    } else {
+        None
+    }
+}
+
+/// Encrypts `plaintext` and returns the tagged COSE_Encrypt0 message
+/// ([RFC 9052 Section 5.2]).
+///
+/// `key` must be an `AES_128_GCM`, `AES_256_GCM`, or `CHACHA20_POLY1305` key;
+/// its algorithm is the protected `alg` header parameter. `nonce` is sent as
+/// the unprotected `IV` header parameter and `key_id`, if any, as the
+/// unprotected `kid`. `external_aad` is authenticated but not included in
+/// the message.
+///
+/// [RFC 9052 Section 5.2]: https://tools.ietf.org/html/rfc9052#section-5.2
+pub fn encrypt0(
+    key: &aead::LessSafeKey,
+    nonce: aead::Nonce,
+    key_id: Option<&[u8]>,
+    plaintext: &[u8],
+    external_aad: &[u8],
+) -> Result<Vec<u8>, error::Unspecified> {
+    let algorithm = algorithm_id(key.algorithm()).ok_or(error::Unspecified)?;
+    let (protected, unprotected) = Headers::write(algorithm, key_id, Some(nonce.as_ref()));
+    let protected_bytes = protected.as_bytes().ok_or(error::Unspecified)?;
+    let aad = enc_structure(protected_bytes, external_aad);
+
+    let mut ciphertext = plaintext.to_vec();
+    key.seal_in_place_append_tag(nonce, aead::Aad::from(&aad), &mut ciphertext)?;
+
+    Ok(write_message(
+        TAG_ENCRYPT0,
+        vec![protected, unprotected, Value::Bytes(ciphertext)],
+    ))
+}
+
+/// A COSE_Encrypt0 message ([RFC 9052 Section 5.2]) that hasn't been
+/// decrypted yet.
+///
+/// [RFC 9052 Section 5.2]: https://tools.ietf.org/html/rfc9052#section-5.2
+#[derive(Debug)]
+pub struct Encrypt0 {
+    headers: Headers,
+    ciphertext: Vec<u8>,
+}
+
+impl Encrypt0 {
+    /// Parses a COSE_Encrypt0 message, which may be tagged or untagged.
+    ///
+    /// `alg` must be a protected header parameter and `IV` must be present.
+    /// Messages with a detached ciphertext, a `Partial IV`, or a `crit`
+    /// header parameter are rejected.
+    pub fn parse(message: &[u8]) -> Result<Self, error::Unspecified> {
+        let mut elements = parse_message(message, TAG_ENCRYPT0, 3)?.into_iter();
+        let (protected, unprotected, ciphertext) =
+            match (elements.next(), elements.next(), elements.next()) {
+                (Some(protected), Some(unprotected), Some(Value::Bytes(ciphertext))) => {
+                    (protected, unprotected, ciphertext)
+                }
+                _ => return Err(error::Unspecified),
+            };
+        let headers = Headers::parse(&protected, &unprotected)?;
+        if headers.iv.is_none() {
+            return Err(error::Unspecified);
+        }
+        Ok(Self {
+            headers,
+            ciphertext,
+        })
+    }
+
+    /// The key ID (`kid`), which isn't authenticated. It may be used to
+    /// choose the key to decrypt the message with.
+    pub fn key_id_unverified(&self) -> Option<&[u8]> {
+        self.headers.key_id.as_deref()
+    }
+
+    /// Decrypts the message with `key` and returns the plaintext.
+    ///
+    /// Fails unless the `alg` header parameter identifies `key`'s algorithm.
+    pub fn decrypt(
+        &self,
+        key: &aead::LessSafeKey,
+        external_aad: &[u8],
+    ) -> Result<Vec<u8>, error::Unspecified> {
+        if algorithm_id(key.algorithm()) != Some(self.headers.algorithm) {
+            return Err(error::Unspecified);
+        }
+        let iv = self.headers.iv.as_deref().ok_or(error::Unspecified)?;
+        let nonce = aead::Nonce::try_assume_unique_for_key(iv)?;
+        let aad = enc_structure(&self.headers.protected, external_aad);
+
+        let mut in_out = self.ciphertext.clone();
+        let plaintext_len = key
+            .open_in_place(nonce, aead::Aad::from(&aad), &mut in_out)?
+            .len();
+        in_out.truncate(plaintext_len);
+        Ok(in_out)
+    }
+}
+
+// The Enc_structure for COSE_Encrypt0 (RFC 9052 Section 5.3).
+fn enc_structure(protected: &[u8], external_aad: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    Value::Array(vec![
+        Value::Text("Encrypt0".into()),
+        Value::Bytes(protected.to_vec()),
+        Value::Bytes(external_aad.to_vec()),
+    ])
+    .write(&mut out);
+    out
+}
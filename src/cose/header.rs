@@ -0,0 +1,153 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use super::cbor::Value;
+use crate::error;
+use alloc::{boxed::Box, vec, vec::Vec};
+
+// Header parameter labels (RFC 9052 Section 3.1).
+const LABEL_ALG: i64 = 1;
+const LABEL_CRIT: i64 = 2;
+const LABEL_KID: i64 = 4;
+const LABEL_IV: i64 = 5;
+const LABEL_PARTIAL_IV: i64 = 6;
+
+/// The header parameters of a COSE message that are supported.
+#[derive(Debug)]
+pub(super) struct Headers {
+    /// The encoded protected header map, exactly as it appeared in the
+    /// message, since it is authenticated as-is.
+    pub(super) protected: Vec<u8>,
+
+    pub(super) algorithm: i64,
+    pub(super) key_id: Option<Vec<u8>>,
+    pub(super) iv: Option<Vec<u8>>,
+}
+
+impl Headers {
+    /// Parses the protected and unprotected header buckets.
+    ///
+    /// `alg` must be protected. A label may not appear in both buckets.
+    /// Messages with `crit` or `Partial IV` are rejected since neither is
+    /// supported.
+    pub(super) fn parse(
+        protected: &Value,
+        unprotected: &Value,
+    ) -> Result<Self, error::Unspecified> {
+        let protected = protected.as_bytes().ok_or(error::Unspecified)?;
+        let protected_value;
+        let protected_params = if protected.is_empty() {
+            &[][..]
+        } else {
+            protected_value = Value::parse(protected)?;
+            protected_value.as_map().ok_or(error::Unspecified)?
+        };
+        let unprotected_params = unprotected.as_map().ok_or(error::Unspecified)?;
+
+        // Labels are unique within each bucket, so a repeated encoding is a
+        // label that appears in both. As in `cbor`, sorting the encodings
+        // avoids comparing every pair of labels.
+        let mut encoded_labels: Vec<Vec<u8>> = protected_params
+            .iter()
+            .chain(unprotected_params)
+            .map(|(label, _)| {
+                let mut encoded = Vec::new();
+                label.write(&mut encoded);
+                encoded
+            })
+            .collect();
+        encoded_labels.sort_unstable();
+        if encoded_labels.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(error::Unspecified);
+        }
+        let param = |label| {
+            protected_params
+                .iter()
+                .chain(unprotected_params)
+                .find(|(l, _)| l.as_i64() == Some(label))
+                .map(|(_, value)| value)
+        };
+        let bytes_param = |label| {
+            param(label)
+                .map(|value| {
+                    value
+                        .as_bytes()
+                        .map(<[u8]>::to_vec)
+                        .ok_or(error::Unspecified)
+                })
+                .transpose()
+        };
+
+        if param(LABEL_CRIT).is_some() || param(LABEL_PARTIAL_IV).is_some() {
+            return Err(error::Unspecified);
+        }
+        let algorithm = protected_params
+            .iter()
+            .find(|(l, _)| l.as_i64() == Some(LABEL_ALG))
+            .and_then(|(_, value)| value.as_i64())
+            .ok_or(error::Unspecified)?;
+
+        Ok(Self {
+            protected: protected.to_vec(),
+            algorithm,
+            key_id: bytes_param(LABEL_KID)?,
+            iv: bytes_param(LABEL_IV)?,
+        })
+    }
+
+    /// Encodes the header buckets, with `alg` protected and everything else
+    /// unprotected.
+    pub(super) fn write(
+        algorithm: i64,
+        key_id: Option<&[u8]>,
+        iv: Option<&[u8]>,
+    ) -> (Value, Value) {
+        let mut protected = Vec::new();
+        Value::Map(vec![(Value::from(LABEL_ALG), Value::from(algorithm))]).write(&mut protected);
+
+        let unprotected = [(LABEL_KID, key_id), (LABEL_IV, iv)]
+            .iter()
+            .filter_map(|&(label, value)| {
+                value.map(|value| (Value::from(label), Value::Bytes(value.to_vec())))
+            })
+            .collect();
+
+        (Value::Bytes(protected), Value::Map(unprotected))
+    }
+}
+
+/// Parses a COSE message that is an array of `len` elements, optionally
+/// tagged with `tag`.
+pub(super) fn parse_message(
+    input: &[u8],
+    tag: u64,
+    len: usize,
+) -> Result<Vec<Value>, error::Unspecified> {
+    let message = match Value::parse(input)? {
+        Value::Tag(t, message) if t == tag => *message,
+        Value::Tag(..) => return Err(error::Unspecified),
+        message => message,
+    };
+    match message {
+        Value::Array(elements) if elements.len() == len => Ok(elements),
+        _ => Err(error::Unspecified),
+    }
+}
+
+/// Encodes a COSE message tagged with `tag`.
+pub(super) fn write_message(tag: u64, elements: Vec<Value>) -> Vec<u8> {
+    let mut out = Vec::new();
+    Value::Tag(tag, Box::new(Value::Array(elements))).write(&mut out);
+    out
+}
@@ -0,0 +1,298 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use super::cbor::Value;
+use crate::{
+    ec, error,
+    key_components::{Curve, KeyComponents},
+    rsa,
+};
+use alloc::{vec, vec::Vec};
+
+// Common COSE_Key parameters (RFC 9052 Section 7.1).
+const LABEL_KTY: i64 = 1;
+const LABEL_KID: i64 = 2;
+const LABEL_ALG: i64 = 3;
+
+// Key types (RFC 9053 Section 7 and RFC 8230 Section 4).
+const KTY_OKP: i64 = 1;
+const KTY_EC2: i64 = 2;
+const KTY_RSA: i64 = 3;
+
+// EC2 and OKP key parameters (RFC 9053 Sections 7.1 and 7.2).
+const LABEL_CRV: i64 = -1;
+const LABEL_X: i64 = -2;
+const LABEL_Y: i64 = -3;
+const LABEL_D: i64 = -4;
+
+// Elliptic curves (RFC 9053 Section 7.1).
+const CRV_P256: i64 = 1;
+const CRV_P384: i64 = 2;
+const CRV_X25519: i64 = 4;
+const CRV_ED25519: i64 = 6;
+
+// RSA key parameters (RFC 8230 Section 4).
+const LABEL_RSA_N: i64 = -1;
+const LABEL_RSA_E: i64 = -2;
+const LABEL_RSA_D: i64 = -3;
+const LABEL_RSA_P: i64 = -4;
+const LABEL_RSA_Q: i64 = -5;
+const LABEL_RSA_DP: i64 = -6;
+const LABEL_RSA_DQ: i64 = -7;
+const LABEL_RSA_QINV: i64 = -8;
+const LABEL_RSA_OTHER: i64 = -9;
+
+/// A COSE_Key ([RFC 9052 Section 7]).
+///
+/// Only the parameters that determine the key material, `kid`, and an
+/// integer `alg` are supported. Other parameters, like `key_ops`, are ignored
+/// when parsing and are never serialized.
+///
+/// [RFC 9052 Section 7]: https://tools.ietf.org/html/rfc9052#section-7
+#[derive(Clone, Debug)]
+pub struct Key {
+    /// The key material.
+    pub components: KeyComponents,
+
+    /// The key ID (`kid`).
+    pub key_id: Option<Vec<u8>>,
+
+    /// The algorithm the key is intended for use with (`alg`), e.g. -7 for
+    /// ES256.
+    pub algorithm: Option<i64>,
+}
+
+impl Key {
+    /// Parses a COSE_Key from its CBOR encoding.
+    ///
+    /// The key type (`kty`) must be OKP, EC2, or RSA, and for OKP and EC2
+    /// keys the curve (`crv`) must be P-256, P-384, Ed25519, or X25519. EC2
+    /// public keys may use point compression. The public key is validated,
+    /// but whether the private key, if any, is consistent with it isn't
+    /// checked until the components are used to construct a key pair. RSA
+    /// keys with more than two primes aren't supported.
+    pub fn from_cbor(cbor: &[u8]) -> Result<Self, error::KeyRejected> {
+        let value = Value::parse(cbor)
+            .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
+        let params = value
+            .as_map()
+            .ok_or_else(error::KeyRejected::invalid_encoding)?;
+
+        let kty = param(params, LABEL_KTY)
+            .ok_or_else(error::KeyRejected::invalid_encoding)?
+            .as_i64()
+            .ok_or_else(error::KeyRejected::wrong_algorithm)?;
+        let components = match kty {
+            KTY_RSA => {
+                let public_key = rsa::PublicKeyComponents {
+                    n: bytes_param(params, LABEL_RSA_N)?,
+                    e: bytes_param(params, LABEL_RSA_E)?,
+                };
+                if param(params, LABEL_RSA_OTHER).is_some() {
+                    return Err(error::KeyRejected::invalid_component());
+                }
+                match param(params, LABEL_RSA_D) {
+                    None => KeyComponents::RsaPublicKey(public_key),
+                    Some(_) => KeyComponents::RsaKeyPair(rsa::KeyPairComponents {
+                        public_key,
+                        d: bytes_param(params, LABEL_RSA_D)?,
+                        p: bytes_param(params, LABEL_RSA_P)?,
+                        q: bytes_param(params, LABEL_RSA_Q)?,
+                        dP: bytes_param(params, LABEL_RSA_DP)?,
+                        dQ: bytes_param(params, LABEL_RSA_DQ)?,
+                        qInv: bytes_param(params, LABEL_RSA_QINV)?,
+                    }),
+                }
+            }
+            KTY_EC2 | KTY_OKP => {
+                let crv = param(params, LABEL_CRV)
+                    .ok_or_else(error::KeyRejected::invalid_encoding)?
+                    .as_i64();
+                let curve = match (kty, crv) {
+                    (KTY_EC2, Some(CRV_P256)) => Curve::P256,
+                    (KTY_EC2, Some(CRV_P384)) => Curve::P384,
+                    (KTY_OKP, Some(CRV_ED25519)) => Curve::Ed25519,
+                    (KTY_OKP, Some(CRV_X25519)) => Curve::X25519,
+                    _ => return Err(error::KeyRejected::wrong_algorithm()),
+                };
+                let public_key = match curve {
+                    Curve::P256 | Curve::P384 => ec2_public_key(params, curve)?,
+                    Curve::Ed25519 | Curve::X25519 => bytes_param(params, LABEL_X)?,
+                };
+                match param(params, LABEL_D) {
+                    None => KeyComponents::EcPublicKey { curve, public_key },
+                    Some(_) => KeyComponents::EcKeyPair {
+                        curve,
+                        private_key: bytes_param(params, LABEL_D)?,
+                        public_key,
+                    },
+                }
+            }
+            _ => return Err(error::KeyRejected::wrong_algorithm()),
+        };
+        components.check()?;
+
+        let key_id = match param(params, LABEL_KID) {
+            None => None,
+            Some(kid) => Some(
+                kid.as_bytes()
+                    .ok_or_else(error::KeyRejected::invalid_encoding)?
+                    .to_vec(),
+            ),
+        };
+        let algorithm = match param(params, LABEL_ALG) {
+            None => None,
+            Some(alg) => Some(
+                alg.as_i64()
+                    .ok_or_else(error::KeyRejected::invalid_encoding)?,
+            ),
+        };
+
+        Ok(Self {
+            components,
+            key_id,
+            algorithm,
+        })
+    }
+
+    /// Serializes the COSE_Key using the deterministic encoding of
+    /// [RFC 8949 Section 4.2.1].
+    ///
+    /// Private key components, if any, are included. EC2 public keys are
+    /// serialized without point compression.
+    ///
+    /// Fails if `components` isn't valid.
+    ///
+    /// [RFC 8949 Section 4.2.1]: https://tools.ietf.org/html/rfc8949#section-4.2.1
+    pub fn to_cbor(&self) -> Result<Vec<u8>, error::Unspecified> {
+        self.components
+            .check()
+            .map_err(|_: error::KeyRejected| error::Unspecified)?;
+
+        let mut params = match &self.components {
+            KeyComponents::RsaPublicKey(public_key) => vec![
+                int_entry(LABEL_KTY, KTY_RSA),
+                bytes_entry(LABEL_RSA_N, &public_key.n),
+                bytes_entry(LABEL_RSA_E, &public_key.e),
+            ],
+            KeyComponents::RsaKeyPair(key_pair) => vec![
+                int_entry(LABEL_KTY, KTY_RSA),
+                bytes_entry(LABEL_RSA_N, &key_pair.public_key.n),
+                bytes_entry(LABEL_RSA_E, &key_pair.public_key.e),
+                bytes_entry(LABEL_RSA_D, &key_pair.d),
+                bytes_entry(LABEL_RSA_P, &key_pair.p),
+                bytes_entry(LABEL_RSA_Q, &key_pair.q),
+                bytes_entry(LABEL_RSA_DP, &key_pair.dP),
+                bytes_entry(LABEL_RSA_DQ, &key_pair.dQ),
+                bytes_entry(LABEL_RSA_QINV, &key_pair.qInv),
+            ],
+            KeyComponents::EcPublicKey { curve, public_key }
+            | KeyComponents::EcKeyPair {
+                curve, public_key, ..
+            } => {
+                let (kty, crv) = match curve {
+                    Curve::P256 => (KTY_EC2, CRV_P256),
+                    Curve::P384 => (KTY_EC2, CRV_P384),
+                    Curve::Ed25519 => (KTY_OKP, CRV_ED25519),
+                    Curve::X25519 => (KTY_OKP, CRV_X25519),
+                };
+                let mut params = vec![int_entry(LABEL_KTY, kty), int_entry(LABEL_CRV, crv)];
+                match curve {
+                    Curve::P256 | Curve::P384 => {
+                        // `check()` verified that this is an uncompressed point.
+                        let (x, y) = public_key[1..].split_at(curve.coordinate_len());
+                        params.push(bytes_entry(LABEL_X, x));
+                        params.push(bytes_entry(LABEL_Y, y));
+                    }
+                    Curve::Ed25519 | Curve::X25519 => {
+                        params.push(bytes_entry(LABEL_X, public_key));
+                    }
+                }
+                if let KeyComponents::EcKeyPair { private_key, .. } = &self.components {
+                    params.push(bytes_entry(LABEL_D, private_key));
+                }
+                params
+            }
+        };
+        if let Some(key_id) = &self.key_id {
+            params.push(bytes_entry(LABEL_KID, key_id));
+        }
+        if let Some(algorithm) = self.algorithm {
+            params.push(int_entry(LABEL_ALG, algorithm));
+        }
+
+        let mut cbor = Vec::new();
+        Value::Map(params).write(&mut cbor);
+        Ok(cbor)
+    }
+}
+
+fn param(params: &[(Value, Value)], label: i64) -> Option<&Value> {
+    params
+        .iter()
+        .find(|(key, _)| key.as_i64() == Some(label))
+        .map(|(_, value)| value)
+}
+
+fn bytes_param(params: &[(Value, Value)], label: i64) -> Result<Vec<u8>, error::KeyRejected> {
+    param(params, label)
+        .and_then(Value::as_bytes)
+        .map(<[u8]>::to_vec)
+        .ok_or_else(error::KeyRejected::invalid_encoding)
+}
+
+// The public key of an EC2 key, as an uncompressed point. `y` may be the
+// sign bit of a compressed point instead of a coordinate.
+fn ec2_public_key(params: &[(Value, Value)], curve: Curve) -> Result<Vec<u8>, error::KeyRejected> {
+    let len = curve.coordinate_len();
+    let x = bytes_param(params, LABEL_X)?;
+    if x.len() != len {
+        return Err(error::KeyRejected::invalid_encoding());
+    }
+    let y = param(params, LABEL_Y).ok_or_else(error::KeyRejected::invalid_encoding)?;
+
+    let mut public_key = Vec::with_capacity(1 + 2 * len);
+    match y {
+        Value::Bytes(y) if y.len() == len => {
+            public_key.push(4); // Uncompressed encoding.
+            public_key.extend_from_slice(&x);
+            public_key.extend_from_slice(y);
+        }
+        Value::Bool(y_is_odd) => {
+            let mut compressed = Vec::with_capacity(1 + x.len());
+            compressed.push(if *y_is_odd { 3 } else { 2 });
+            compressed.extend_from_slice(&x);
+            public_key.resize(1 + 2 * len, 0);
+            let suite_b_curve = curve
+                .suite_b_curve()
+                .ok_or_else(error::KeyRejected::unexpected_error)?;
+            ec::suite_b::uncompress_public_key(
+                suite_b_curve,
+                untrusted::Input::from(&compressed),
+                &mut public_key,
+            )
+            .map_err(|error::Unspecified| error::KeyRejected::invalid_component())?;
+        }
+        _ => return Err(error::KeyRejected::invalid_encoding()),
+    }
+    Ok(public_key)
+}
+
+fn int_entry(label: i64, value: i64) -> (Value, Value) {
+    (Value::from(label), Value::from(value))
+}
+
+fn bytes_entry(label: i64, value: &[u8]) -> (Value, Value) {
+    (Value::from(label), Value::Bytes(value.to_vec()))
+}
@@ -0,0 +1,197 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use super::{
+    cbor::Value,
+    header::{parse_message, write_message, Headers},
+};
+use crate::{
+    error, rand, signature,
+    signer::{SigningAlgorithm, SigningKey},
+};
+use alloc::{vec, vec::Vec};
+
+const TAG_SIGN1: u64 = 18;
+
+/// A COSE signature algorithm ([RFC 9053 Section 2]).
+///
+/// The algorithm used for verification is always chosen by the caller, never
+/// by the message.
+///
+/// [RFC 9053 Section 2]: https://tools.ietf.org/html/rfc9053#section-2
+pub struct SignatureAlgorithm {
+    id: i64,
+    verification_alg: &'static dyn signature::VerificationAlgorithm,
+    signing_alg: SigningAlgorithm,
+}
+
+derive_debug_via_field!(SignatureAlgorithm, id);
+
+impl SignatureAlgorithm {
+    /// The algorithm's identifier, as used in the `alg` header parameter.
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    /// Looks up an algorithm by its identifier, e.g. -7 for `ES256`.
+    pub fn from_id(id: i64) -> Option<&'static Self> {
+        ALGORITHMS.iter().copied().find(|alg| alg.id == id)
+    }
+}
+
+const ALGORITHMS: &[&SignatureAlgorithm] = &[&ES256, &ES384, &EDDSA, &PS256];
+
+/// ECDSA using P-256 and SHA-256 (-7).
+pub static ES256: SignatureAlgorithm = SignatureAlgorithm {
+    id: -7,
+    verification_alg: &signature::ECDSA_P256_SHA256_FIXED,
+    signing_alg: SigningAlgorithm::Ecdsa(&signature::ECDSA_P256_SHA256_FIXED_SIGNING),
+};
+
+/// ECDSA using P-384 and SHA-384 (-35).
+pub static ES384: SignatureAlgorithm = SignatureAlgorithm {
+    id: -35,
+    verification_alg: &signature::ECDSA_P384_SHA384_FIXED,
+    signing_alg: SigningAlgorithm::Ecdsa(&signature::ECDSA_P384_SHA384_FIXED_SIGNING),
+};
+
+/// EdDSA using Ed25519 (-8).
+pub static EDDSA: SignatureAlgorithm = SignatureAlgorithm {
+    id: -8,
+    verification_alg: &signature::ED25519,
+    signing_alg: SigningAlgorithm::Ed25519,
+};
+
+/// RSASSA-PSS using SHA-256 and MGF1 with SHA-256 (-37), with 2048-8192 bit
+/// keys ([RFC 8230 Section 2]).
+///
+/// [RFC 8230 Section 2]: https://tools.ietf.org/html/rfc8230#section-2
+pub static PS256: SignatureAlgorithm = SignatureAlgorithm {
+    id: -37,
+    verification_alg: &signature::RSA_PSS_2048_8192_SHA256,
+    signing_alg: SigningAlgorithm::Rsa(&signature::RSA_PSS_SHA256),
+};
+
+/// Signs `payload` and returns the tagged COSE_Sign1 message
+/// ([RFC 9052 Section 4.2]).
+///
+/// `alg` is a protected header parameter and `key_id`, if any, is an
+/// unprotected one. `external_aad` is authenticated but not included in the
+/// message. Fails if `key` can't be used with `algorithm`.
+///
+/// [RFC 9052 Section 4.2]: https://tools.ietf.org/html/rfc9052#section-4.2
+pub fn sign1(
+    algorithm: &'static SignatureAlgorithm,
+    key: SigningKey,
+    key_id: Option<&[u8]>,
+    payload: &[u8],
+    external_aad: &[u8],
+    rng: &dyn rand::SecureRandom,
+) -> Result<Vec<u8>, error::Unspecified> {
+    let (protected, unprotected) = Headers::write(algorithm.id, key_id, None);
+    let protected_bytes = protected.as_bytes().ok_or(error::Unspecified)?;
+    let to_be_signed = sig_structure(protected_bytes, external_aad, payload);
+
+    let signature = algorithm.signing_alg.sign(key, &to_be_signed, rng)?;
+
+    Ok(write_message(
+        TAG_SIGN1,
+        vec![
+            protected,
+            unprotected,
+            Value::Bytes(payload.to_vec()),
+            Value::Bytes(signature),
+        ],
+    ))
+}
+
+/// A COSE_Sign1 message ([RFC 9052 Section 4.2]) whose signature hasn't been
+/// verified yet.
+///
+/// [RFC 9052 Section 4.2]: https://tools.ietf.org/html/rfc9052#section-4.2
+#[derive(Debug)]
+pub struct Sign1 {
+    headers: Headers,
+    payload: Vec<u8>,
+    signature: Vec<u8>,
+}
+
+impl Sign1 {
+    /// Parses a COSE_Sign1 message, which may be tagged or untagged.
+    ///
+    /// `alg` must be a protected header parameter. Messages with a detached
+    /// payload or with a `crit` header parameter are rejected.
+    pub fn parse(message: &[u8]) -> Result<Self, error::Unspecified> {
+        let mut elements = parse_message(message, TAG_SIGN1, 4)?.into_iter();
+        let (protected, unprotected, payload, signature) = match (
+            elements.next(),
+            elements.next(),
+            elements.next(),
+            elements.next(),
+        ) {
+            (
+                Some(protected),
+                Some(unprotected),
+                Some(Value::Bytes(payload)),
+                Some(Value::Bytes(signature)),
+            ) => (protected, unprotected, payload, signature),
+            _ => return Err(error::Unspecified),
+        };
+        Ok(Self {
+            headers: Headers::parse(&protected, &unprotected)?,
+            payload,
+            signature,
+        })
+    }
+
+    /// The key ID (`kid`), which isn't authenticated. It may be used to
+    /// choose the key to verify the message with.
+    pub fn key_id_unverified(&self) -> Option<&[u8]> {
+        self.headers.key_id.as_deref()
+    }
+
+    /// Verifies the signature with `public_key`, which is encoded as
+    /// `signature::UnparsedPublicKey` expects for `algorithm`, and returns
+    /// the payload.
+    ///
+    /// Fails unless the `alg` header parameter is exactly `algorithm`'s
+    /// identifier.
+    pub fn verify(
+        &self,
+        algorithm: &'static SignatureAlgorithm,
+        public_key: &[u8],
+        external_aad: &[u8],
+    ) -> Result<&[u8], error::Unspecified> {
+        if self.headers.algorithm != algorithm.id {
+            return Err(error::Unspecified);
+        }
+        let to_be_signed = sig_structure(&self.headers.protected, external_aad, &self.payload);
+        signature::UnparsedPublicKey::new(algorithm.verification_alg, public_key)
+            .verify(&to_be_signed, &self.signature)?;
+        Ok(&self.payload)
+    }
+}
+
+// The Sig_structure for COSE_Sign1 (RFC 9052 Section 4.4).
+fn sig_structure(protected: &[u8], external_aad: &[u8], payload: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    Value::Array(vec![
+        Value::Text("Signature1".into()),
+        Value::Bytes(protected.to_vec()),
+        Value::Bytes(external_aad.to_vec()),
+        Value::Bytes(payload.to_vec()),
+    ])
+    .write(&mut out);
+    out
+}
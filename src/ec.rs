@@ -58,6 +58,12 @@ const SEED_MAX_BYTES: usize = ELEM_MAX_BYTES;
 /// longer.
 pub const PKCS8_DOCUMENT_MAX_LEN: usize = 40 + SCALAR_MAX_BYTES + keys::PUBLIC_KEY_MAX_LEN;
 
+/// The maximum length of a SEC 1 `ECPrivateKey` document generated by *ring*.
+///
+/// `22` is the length of the P-384 encoding excluding the private key and the
+/// public key; the P-256 encoding is shorter.
+pub const SEC1_DOCUMENT_MAX_LEN: usize = 22 + SCALAR_MAX_BYTES + keys::PUBLIC_KEY_MAX_LEN;
+
 pub mod curve25519;
 mod keys;
 pub mod suite_b;
@@ -15,15 +15,19 @@
 //! EdDSA Signatures.
 
 use super::{super::ops::*, eddsa_digest, ED25519_PUBLIC_KEY_LEN};
-use crate::{
-    digest, error,
-    io::der,
-    pkcs8, rand,
-    signature::{self, KeyPair as SigningKeyPair},
-};
+use crate::{digest, error, io::der, pkcs8, rand, signature};
+
+#[cfg(feature = "alloc")]
+use crate::spki;
+
+#[cfg(feature = "alloc")]
+use core::num::NonZeroU32;
 
 /// An Ed25519 key pair, for signing.
 pub struct Ed25519KeyPair {
+    // RFC 8032 Section 5.1.5 calls this *k*.
+    seed: Seed,
+
     // RFC 8032 Section 5.1.6 calls this *s*.
     private_scalar: Scalar,
 
@@ -50,12 +54,7 @@ impl Ed25519KeyPair {
         rng: &dyn rand::SecureRandom,
     ) -> Result<pkcs8::Document, error::Unspecified> {
         let seed: [u8; SEED_LEN] = rand::generate(rng)?.expose();
-        let key_pair = Self::from_seed_(&seed);
-        Ok(pkcs8::wrap_key(
-            &PKCS8_TEMPLATE,
-            &seed[..],
-            key_pair.public_key().as_ref(),
-        ))
+        Ok(Self::from_seed_(&seed).to_pkcs8_less_safe())
     }
 
     /// Constructs an Ed25519 key pair by parsing an unencrypted PKCS#8 v2
@@ -122,6 +121,50 @@ impl Ed25519KeyPair {
         }
     }
 
+    /// Constructs an Ed25519 key pair by decrypting a PKCS#8 v2
+    /// `EncryptedPrivateKeyInfo` with `password` and then parsing it like
+    /// `Ed25519KeyPair::from_pkcs8()`.
+    ///
+    /// See `pkcs8::Document::encrypt()` for the supported encryption
+    /// algorithms. Documents that use more than `max_iterations` PBKDF2
+    /// iterations are rejected with a `TooLarge` error without being
+    /// decrypted.
+    #[cfg(feature = "alloc")]
+    pub fn from_encrypted_pkcs8(
+        encrypted_pkcs8: &[u8],
+        password: &[u8],
+        max_iterations: NonZeroU32,
+    ) -> Result<Self, error::KeyRejected> {
+        let pkcs8 = pkcs8::decrypt(
+            untrusted::Input::from(encrypted_pkcs8),
+            password,
+            max_iterations,
+        )?;
+        Self::from_pkcs8(&pkcs8)
+    }
+
+    /// Constructs an Ed25519 key pair by decrypting a PKCS#8 v1 or v2
+    /// `EncryptedPrivateKeyInfo` with `password` and then parsing it like
+    /// `Ed25519KeyPair::from_pkcs8_maybe_unchecked()`.
+    ///
+    /// `openssl pkcs8 -topk8` encrypts PKCS#8 v1 keys, which require the use
+    /// of this function. Documents that use more than `max_iterations` PBKDF2
+    /// iterations are rejected with a `TooLarge` error without being
+    /// decrypted.
+    #[cfg(feature = "alloc")]
+    pub fn from_encrypted_pkcs8_maybe_unchecked(
+        encrypted_pkcs8: &[u8],
+        password: &[u8],
+        max_iterations: NonZeroU32,
+    ) -> Result<Self, error::KeyRejected> {
+        let pkcs8 = pkcs8::decrypt(
+            untrusted::Input::from(encrypted_pkcs8),
+            password,
+            max_iterations,
+        )?;
+        Self::from_pkcs8_maybe_unchecked(&pkcs8)
+    }
+
     /// Constructs an Ed25519 key pair from the private key seed `seed` and its
     /// public key `public_key`.
     ///
@@ -182,12 +225,32 @@ impl Ed25519KeyPair {
         }
 
         Self {
+            seed: *seed,
             private_scalar,
             private_prefix: private_prefix.try_into().unwrap(),
             public_key: PublicKey(a.into_encoded_point()),
         }
     }
 
+    /// Serializes the key pair, including the private key, as a PKCS#8 v2
+    /// document in the same format that `Ed25519KeyPair::generate_pkcs8()`
+    /// uses.
+    ///
+    /// The public key is always included, even if the key pair was
+    /// constructed from a PKCS#8 v1 document or from the seed alone. The
+    /// result can be parsed with `Ed25519KeyPair::from_pkcs8()`.
+    pub fn to_pkcs8_less_safe(&self) -> pkcs8::Document {
+        pkcs8::wrap_key(&PKCS8_TEMPLATE, &self.seed, self.public_key.as_ref())
+    }
+
+    /// Returns the private key seed.
+    ///
+    /// The result can be passed to `Ed25519KeyPair::from_seed_and_public_key()`
+    /// along with the public key.
+    pub fn seed_less_safe(&self) -> &[u8] {
+        &self.seed
+    }
+
     /// Returns the signature of the message `msg`.
     pub fn sign(&self, msg: &[u8]) -> signature::Signature {
         signature::Signature::new(|signature_bytes| {
@@ -248,6 +311,17 @@ impl AsRef<[u8]> for PublicKey {
     }
 }
 
+impl PublicKey {
+    /// The public key as a DER-encoded SubjectPublicKeyInfo, identified by
+    /// `id-Ed25519` ([RFC 8410 Section 4]).
+    ///
+    /// [RFC 8410 Section 4]: https://tools.ietf.org/html/rfc8410#section-4
+    #[cfg(feature = "alloc")]
+    pub fn to_spki(self) -> spki::Document {
+        spki::wrap_key(spki::KeyType::Ed25519, self.as_ref())
+    }
+}
+
 derive_debug_self_as_ref_hex_bytes!(PublicKey);
 
 fn unwrap_pkcs8(
@@ -275,7 +349,7 @@ const SIGNATURE_LEN: usize = ELEM_LEN + SCALAR_LEN;
 type Seed = [u8; SEED_LEN];
 const SEED_LEN: usize = 32;
 
-static PKCS8_TEMPLATE: pkcs8::Template = pkcs8::Template {
+pub(crate) static PKCS8_TEMPLATE: pkcs8::Template = pkcs8::Template {
     bytes: include_bytes!("ed25519_pkcs8_v2_template.der"),
     alg_id_range: core::ops::Range { start: 7, end: 12 },
     curve_id_index: 0,
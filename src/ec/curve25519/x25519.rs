@@ -163,7 +163,7 @@ const PRIVATE_KEY_LEN: usize = ELEM_AND_SCALAR_LEN;
 
 // An X25519 public key as an encoded Curve25519 point.
 type PublicKey = [u8; PUBLIC_KEY_LEN];
-const PUBLIC_KEY_LEN: usize = ELEM_AND_SCALAR_LEN;
+pub(crate) const PUBLIC_KEY_LEN: usize = ELEM_AND_SCALAR_LEN;
 
 // An X25519 shared secret as an encoded Curve25519 point.
 type SharedSecret = [u8; SHARED_SECRET_LEN];
@@ -87,5 +87,21 @@ impl AsRef<[u8]> for PublicKey {
     }
 }
 
+impl PublicKey {
+    /// Returns the key in SEC 1 compressed form. `self` must be in
+    /// uncompressed form, i.e. a P-256 or P-384 public key.
+    pub fn compressed(&self) -> Self {
+        debug_assert_eq!(self.bytes[0], 4);
+        let elem_len = (self.len - 1) / 2;
+        let mut r = Self {
+            bytes: [0u8; PUBLIC_KEY_MAX_LEN],
+            len: 1 + elem_len,
+        };
+        r.bytes[0] = 2 | (self.bytes[self.len - 1] & 1);
+        r.bytes[1..r.len].copy_from_slice(&self.bytes[1..r.len]);
+        r
+    }
+}
+
 /// The maximum length, in bytes, of an encoded public key.
 pub const PUBLIC_KEY_MAX_LEN: usize = 1 + (2 * ELEM_MAX_BYTES);
@@ -15,7 +15,7 @@
 //! Elliptic curve operations on P-256 & P-384.
 
 use self::ops::*;
-use crate::{arithmetic::montgomery::*, cpu, ec, error, io::der, limb::LimbMask, pkcs8};
+use crate::{arithmetic::montgomery::*, cpu, ec, error, limb::LimbMask, pkcs8, sec1};
 
 // NIST SP 800-56A Step 3: "If q is an odd prime p, verify that
 // yQ**2 = xQ**3 + axQ + b in GF(p), where the arithmetic is performed modulo
@@ -160,53 +160,32 @@ pub(crate) fn key_pair_from_pkcs8(
     cpu_features: cpu::Features,
 ) -> Result<ec::KeyPair, error::KeyRejected> {
     let (ec_private_key, _) = pkcs8::unwrap_key(template, pkcs8::Version::V1Only, input)?;
-    let (private_key, public_key) =
-        ec_private_key.read_all(error::KeyRejected::invalid_encoding(), |input| {
-            // https://tools.ietf.org/html/rfc5915#section-3
-            der::nested(
-                input,
-                der::Tag::Sequence,
-                error::KeyRejected::invalid_encoding(),
-                |input| key_pair_from_pkcs8_(template, input),
-            )
-        })?;
+    let (private_key, public_key) = sec1::unwrap_key(template.curve_oid(), ec_private_key)?;
+    // The RFC says the public key is optional, but we require it to be
+    // present.
+    let public_key = public_key.ok_or_else(error::KeyRejected::invalid_encoding)?;
     key_pair_from_bytes(curve, private_key, public_key, cpu_features)
 }
 
-fn key_pair_from_pkcs8_<'a>(
+/// Parses a SEC 1 `ECPrivateKey`. If the public key is present then it must be
+/// consistent with the private key; otherwise, it is computed from the private
+/// key.
+pub(crate) fn key_pair_from_sec1(
+    curve: &'static ec::Curve,
     template: &pkcs8::Template,
-    input: &mut untrusted::Reader<'a>,
-) -> Result<(untrusted::Input<'a>, untrusted::Input<'a>), error::KeyRejected> {
-    let version = der::small_nonnegative_integer(input)
-        .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
-    if version != 1 {
-        return Err(error::KeyRejected::version_not_supported());
-    }
-
-    let private_key = der::expect_tag_and_get_value(input, der::Tag::OctetString)
-        .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
-
-    // [0] parameters (optional).
-    if input.peek(u8::from(der::Tag::ContextSpecificConstructed0)) {
-        let actual_alg_id =
-            der::expect_tag_and_get_value(input, der::Tag::ContextSpecificConstructed0)
-                .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
-        if actual_alg_id.as_slice_less_safe() != template.curve_oid().as_slice_less_safe() {
-            return Err(error::KeyRejected::wrong_algorithm());
+    input: untrusted::Input,
+    cpu_features: cpu::Features,
+) -> Result<ec::KeyPair, error::KeyRejected> {
+    let (private_key, public_key) = sec1::unwrap_key(template.curve_oid(), input)?;
+    match public_key {
+        Some(public_key) => key_pair_from_bytes(curve, private_key, public_key, cpu_features),
+        None => {
+            let seed = ec::Seed::from_bytes(curve, private_key, cpu_features)
+                .map_err(|error::Unspecified| error::KeyRejected::invalid_component())?;
+            ec::KeyPair::derive(seed)
+                .map_err(|error::Unspecified| error::KeyRejected::unexpected_error())
         }
     }
-
-    // [1] publicKey. The RFC says it is optional, but we require it
-    // to be present.
-    let public_key = der::nested(
-        input,
-        der::Tag::ContextSpecificConstructed1,
-        error::Unspecified,
-        der::bit_string_with_no_unused_bits,
-    )
-    .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
-
-    Ok((private_key, public_key))
 }
 
 pub(crate) fn key_pair_from_bytes(
@@ -227,6 +206,53 @@ pub(crate) fn key_pair_from_bytes(
     Ok(r)
 }
 
+/// Verifies that `public_key` is a valid uncompressed point on `curve`, which
+/// must be P-256 or P-384.
+pub(crate) fn check_public_key(
+    curve: &ec::Curve,
+    public_key: untrusted::Input,
+) -> Result<(), error::Unspecified> {
+    let ops = match curve.id {
+        ec::CurveID::P256 => &p256::PUBLIC_KEY_OPS,
+        ec::CurveID::P384 => &p384::PUBLIC_KEY_OPS,
+        #[cfg(not(target_arch = "wasm32"))]
+        ec::CurveID::Curve25519 => return Err(error::Unspecified),
+    };
+    let _ = public_key::parse_uncompressed_point(ops, public_key)?;
+    Ok(())
+}
+
+/// Validates the compressed point `public_key` on `curve`, which must be
+/// P-256 or P-384, and writes it to `out` in uncompressed form. `out` must be
+/// exactly `curve.public_key_len` bytes long.
+#[cfg(feature = "cose")]
+pub(crate) fn uncompress_public_key(
+    curve: &ec::Curve,
+    public_key: untrusted::Input,
+    out: &mut [u8],
+) -> Result<(), error::Unspecified> {
+    let ops = match curve.id {
+        ec::CurveID::P256 => &p256::PUBLIC_KEY_OPS,
+        ec::CurveID::P384 => &p384::PUBLIC_KEY_OPS,
+        #[cfg(not(target_arch = "wasm32"))]
+        ec::CurveID::Curve25519 => return Err(error::Unspecified),
+    };
+    let (x, y) = public_key::parse_compressed_point(ops, public_key)?;
+
+    let num_limbs = ops.common.num_limbs;
+    let (encoding, xy_out) = out.split_first_mut().ok_or(error::Unspecified)?;
+    *encoding = 4; // Uncompressed encoding.
+    if xy_out.len() != 2 * num_limbs * crate::limb::LIMB_BYTES {
+        return Err(error::Unspecified);
+    }
+    let (x_out, y_out) = xy_out.split_at_mut(xy_out.len() / 2);
+    for (elem, out) in [(x, x_out), (y, y_out)] {
+        let elem = ops.common.elem_unencoded(&elem);
+        crate::limb::big_endian_from_limbs(&elem.limbs[..num_limbs], out);
+    }
+    Ok(())
+}
+
 pub mod curve;
 
 #[cfg(not(target_arch = "wasm32"))]
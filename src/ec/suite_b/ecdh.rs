@@ -27,7 +27,8 @@ macro_rules! ecdh {
         ///
         /// Public keys are encoding in uncompressed form using the
         /// Octet-String-to-Elliptic-Curve-Point algorithm in
-        /// [SEC 1: Elliptic Curve Cryptography, Version 2.0]. Public keys are
+        /// [SEC 1: Elliptic Curve Cryptography, Version 2.0]. Peer public keys
+        /// may also be in compressed form. Public keys are
         /// validated during key agreement according to
         /// [NIST Special Publication 800-56A, revision 2] and Appendix B.3 of
         /// the NSA's [Suite B Implementer's Guide to NIST SP 800-56A].
@@ -95,10 +96,9 @@ fn ecdh(
     // NIST SP 800-56Ar2 5.6.2.2.2.
     // NSA Guide Step 2.
     //
-    // `parse_uncompressed_point` verifies that the point is not at infinity
-    // and that it is on the curve, using the Partial Public-Key Validation
-    // Routine.
-    let peer_public_key = parse_uncompressed_point(public_key_ops, peer_public_key)?;
+    // `parse_point` verifies that the point is not at infinity and that it is
+    // on the curve, using the Partial Public-Key Validation Routine.
+    let peer_public_key = parse_point(public_key_ops, peer_public_key)?;
 
     // NIST SP 800-56Ar2 Step 1.
     // NSA Guide Step 3 (except point at infinity check).
@@ -108,9 +108,9 @@ fn ecdh(
     //
     // It is impossible for the result to be the point at infinity because our
     // private key is in the range [1, n) and the curve has prime order and
-    // `parse_uncompressed_point` verified that the peer public key is on the
-    // curve and not at infinity. However, since the standards require the
-    // check, we do it using `assert!`.
+    // `parse_point` verified that the peer public key is on the curve and not
+    // at infinity. However, since the standards require the check, we do it
+    // using `assert!`.
     //
     // NIST SP 800-56Ar2 defines "Destroy" thusly: "In this Recommendation, to
     // destroy is an action applied to a key or a piece of secret data. After
@@ -24,8 +24,14 @@ use crate::{
     },
     error,
     io::der,
-    limb, pkcs8, rand, sealed, signature,
+    limb, pkcs8, rand, sealed, sec1, signature,
 };
+
+#[cfg(feature = "alloc")]
+use crate::spki;
+
+#[cfg(feature = "alloc")]
+use core::num::NonZeroU32;
 /// An ECDSA signing algorithm.
 pub struct EcdsaSigningAlgorithm {
     curve: &'static ec::Curve,
@@ -60,6 +66,7 @@ impl sealed::Sealed for EcdsaSigningAlgorithm {}
 /// An ECDSA key pair, used for signing.
 pub struct EcdsaKeyPair {
     d: Scalar<R>,
+    seed: ec::Seed,
     nonce_key: NonceRandomKey,
     alg: &'static EcdsaSigningAlgorithm,
     public_key: PublicKey,
@@ -116,6 +123,56 @@ impl EcdsaKeyPair {
         Self::new(alg, key_pair, rng)
     }
 
+    /// Constructs an ECDSA key pair by decrypting a PKCS#8
+    /// `EncryptedPrivateKeyInfo` with `password` and then parsing it like
+    /// `EcdsaKeyPair::from_pkcs8()`.
+    ///
+    /// See `pkcs8::Document::encrypt()` for the supported encryption
+    /// algorithms. Documents that use more than `max_iterations` PBKDF2
+    /// iterations are rejected with a `TooLarge` error without being
+    /// decrypted.
+    #[cfg(feature = "alloc")]
+    pub fn from_encrypted_pkcs8(
+        alg: &'static EcdsaSigningAlgorithm,
+        encrypted_pkcs8: &[u8],
+        password: &[u8],
+        max_iterations: NonZeroU32,
+        rng: &dyn rand::SecureRandom,
+    ) -> Result<Self, error::KeyRejected> {
+        let pkcs8 = pkcs8::decrypt(
+            untrusted::Input::from(encrypted_pkcs8),
+            password,
+            max_iterations,
+        )?;
+        Self::from_pkcs8(alg, &pkcs8, rng)
+    }
+
+    /// Constructs an ECDSA key pair by parsing a SEC 1 `ECPrivateKey`
+    /// ([RFC 5915]), the format OpenSSL labels `EC PRIVATE KEY`.
+    ///
+    /// If the `parameters` field is present then it must name the curve of
+    /// `alg`. If the `publicKey` field is present then `from_sec1()` will
+    /// verify that it is consistent with the private key; otherwise the public
+    /// key is computed from the private key.
+    ///
+    /// `pem::decode_expecting(pem::Label::EcPrivateKey, ...)` can be used to
+    /// decode the PEM form.
+    ///
+    /// [RFC 5915]: https://tools.ietf.org/html/rfc5915
+    pub fn from_sec1(
+        alg: &'static EcdsaSigningAlgorithm,
+        sec1: &[u8],
+        rng: &dyn rand::SecureRandom,
+    ) -> Result<Self, error::KeyRejected> {
+        let key_pair = ec::suite_b::key_pair_from_sec1(
+            alg.curve,
+            alg.pkcs8_template,
+            untrusted::Input::from(sec1),
+            cpu::features(),
+        )?;
+        Self::new(alg, key_pair, rng)
+    }
+
     /// Constructs an ECDSA key pair from the private key and public key bytes
     ///
     /// The private key must encoded as a big-endian fixed-length integer. For
@@ -162,12 +219,60 @@ impl EcdsaKeyPair {
         let nonce_key = NonceRandomKey::new(alg, &seed, rng)?;
         Ok(Self {
             d,
+            seed,
             nonce_key,
             alg,
-            public_key: PublicKey(public_key),
+            public_key: PublicKey {
+                key: public_key,
+                curve: alg.curve,
+            },
         })
     }
 
+    /// Serializes the key pair, including the private key, as a SEC 1
+    /// `ECPrivateKey` ([RFC 5915]) that names the curve and contains the
+    /// public key.
+    ///
+    /// The result can be parsed with `EcdsaKeyPair::from_sec1()`.
+    ///
+    /// [RFC 5915]: https://tools.ietf.org/html/rfc5915
+    pub fn to_sec1_less_safe(&self) -> sec1::Document {
+        sec1::wrap_key(
+            self.alg.pkcs8_template.curve_oid().as_slice_less_safe(),
+            self.seed.bytes_less_safe(),
+            self.public_key.key.as_ref(),
+        )
+    }
+
+    /// Serializes the key pair, including the private key, as a PKCS#8 v1
+    /// document in the same format that `EcdsaKeyPair::generate_pkcs8()`
+    /// uses.
+    ///
+    /// The result can be parsed with `EcdsaKeyPair::from_pkcs8()`.
+    pub fn to_pkcs8_less_safe(&self) -> pkcs8::Document {
+        pkcs8::wrap_key(
+            self.alg.pkcs8_template,
+            self.seed.bytes_less_safe(),
+            self.public_key.key.as_ref(),
+        )
+    }
+
+    /// Returns the private key as a big-endian encoded scalar that is exactly
+    /// as long as the curve's field elements.
+    ///
+    /// The result can be passed to
+    /// `EcdsaKeyPair::from_private_key_and_public_key()` along with the public
+    /// key.
+    pub fn private_key_less_safe(&self) -> &[u8] {
+        self.seed.bytes_less_safe()
+    }
+
+    /// The signing algorithm the key pair was constructed for.
+    #[cfg(any(feature = "cose", feature = "jose"))]
+    pub(crate) fn algorithm(&self) -> &'static EcdsaSigningAlgorithm {
+        self.alg
+    }
+
     /// Deprecated. Returns the signature of the `message` using a random nonce
     /// generated by `rng`.
     pub fn sign(
@@ -371,11 +476,53 @@ impl signature::KeyPair for EcdsaKeyPair {
 }
 
 #[derive(Clone, Copy)]
-pub struct PublicKey(ec::PublicKey);
+pub struct PublicKey {
+    key: ec::PublicKey,
+    #[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+    curve: &'static ec::Curve,
+}
 
 derive_debug_self_as_ref_hex_bytes!(PublicKey);
 
 impl AsRef<[u8]> for PublicKey {
+    fn as_ref(&self) -> &[u8] {
+        self.key.as_ref()
+    }
+}
+
+impl PublicKey {
+    /// The public key as a DER-encoded SubjectPublicKeyInfo, identified by
+    /// `id-ecPublicKey` and the curve's name ([RFC 5480 Section 2]).
+    ///
+    /// [RFC 5480 Section 2]: https://tools.ietf.org/html/rfc5480#section-2
+    #[cfg(feature = "alloc")]
+    pub fn to_spki(self) -> spki::Document {
+        spki::wrap_key(spki::KeyType::for_curve(self.curve), self.as_ref())
+    }
+
+    /// The public key in compressed form using the
+    /// Elliptic-Curve-Point-to-Octet-String algorithm in
+    /// [SEC 1: Elliptic Curve Cryptography, Version 2.0]: the x coordinate,
+    /// prefixed with 0x02 if the y coordinate is even or with 0x03 if it is
+    /// odd. A P-256 key is 33 bytes and a P-384 key is 49 bytes.
+    ///
+    /// The result is accepted by `signature::UnparsedPublicKey` for ECDSA
+    /// verification and by `agreement::UnparsedPublicKey` for ECDH.
+    ///
+    /// [SEC 1: Elliptic Curve Cryptography, Version 2.0]:
+    ///     http://www.secg.org/sec1-v2.pdf
+    pub fn to_compressed(self) -> CompressedPublicKey {
+        CompressedPublicKey(self.key.compressed())
+    }
+}
+
+/// An ECDSA public key in compressed form. See `PublicKey::to_compressed()`.
+#[derive(Clone, Copy)]
+pub struct CompressedPublicKey(ec::PublicKey);
+
+derive_debug_self_as_ref_hex_bytes!(CompressedPublicKey);
+
+impl AsRef<[u8]> for CompressedPublicKey {
     fn as_ref(&self) -> &[u8] {
         self.0.as_ref()
     }
@@ -499,14 +646,14 @@ pub static ECDSA_P384_SHA384_ASN1_SIGNING: EcdsaSigningAlgorithm = EcdsaSigningA
     id: AlgorithmID::ECDSA_P384_SHA384_ASN1_SIGNING,
 };
 
-static EC_PUBLIC_KEY_P256_PKCS8_V1_TEMPLATE: pkcs8::Template = pkcs8::Template {
+pub(crate) static EC_PUBLIC_KEY_P256_PKCS8_V1_TEMPLATE: pkcs8::Template = pkcs8::Template {
     bytes: include_bytes!("ecPublicKey_p256_pkcs8_v1_template.der"),
     alg_id_range: core::ops::Range { start: 8, end: 27 },
     curve_id_index: 9,
     private_key_index: 0x24,
 };
 
-static EC_PUBLIC_KEY_P384_PKCS8_V1_TEMPLATE: pkcs8::Template = pkcs8::Template {
+pub(crate) static EC_PUBLIC_KEY_P384_PKCS8_V1_TEMPLATE: pkcs8::Template = pkcs8::Template {
     bytes: include_bytes!("ecPublicKey_p384_pkcs8_v1_template.der"),
     alg_id_range: core::ops::Range { start: 8, end: 24 },
     curve_id_index: 9,
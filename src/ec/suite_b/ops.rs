@@ -110,6 +110,22 @@ impl CommonOps {
         mul_mont(self.elem_mul_mont, a, b)
     }
 
+    #[inline]
+    pub fn elem_negated<E: Encoding>(&self, a: &Elem<E>) -> Elem<E> {
+        let mut r = Elem::zero();
+        let zero = Elem::<E>::zero();
+        unsafe {
+            LIMBS_sub_mod(
+                r.limbs.as_mut_ptr(),
+                zero.limbs.as_ptr(),
+                a.limbs.as_ptr(),
+                self.q.p.as_ptr(),
+                self.num_limbs,
+            )
+        }
+        r
+    }
+
     #[inline]
     pub fn elem_square(&self, a: &mut Elem<R>) {
         unary_op_assign(self.elem_sqr_mont, a);
@@ -209,6 +225,7 @@ impl PrivateKeyOps {
 /// agreement and ECDSA verification).
 pub struct PublicKeyOps {
     pub common: &'static CommonOps,
+    elem_sqrt_candidate: fn(a: &Elem<R>) -> Elem<R>,
 }
 
 impl PublicKeyOps {
@@ -232,6 +249,16 @@ impl PublicKeyOps {
         }
         Ok(r)
     }
+
+    /// Returns a square root of `a` if `a` is a square (mod q). Otherwise the
+    /// result is not a square root of `a`, so the caller must square the
+    /// result to determine whether it is one.
+    ///
+    /// Both curves have q = 3 (mod 4), so the candidate is a**((q + 1) / 4).
+    #[inline]
+    pub fn elem_sqrt_candidate(&self, a: &Elem<R>) -> Elem<R> {
+        (self.elem_sqrt_candidate)(a)
+    }
 }
 
 // Operations used by both ECDSA signing and ECDSA verification. In general
@@ -428,6 +455,13 @@ prefixed_extern! {
         m: *const Limb,
         num_limbs: c::size_t,
     );
+    fn LIMBS_sub_mod(
+        r: *mut Limb,
+        a: *const Limb,
+        b: *const Limb,
+        m: *const Limb,
+        num_limbs: c::size_t,
+    );
 }
 
 #[cfg(test)]
@@ -139,8 +139,49 @@ fn p256_point_mul_base_impl(g_scalar: &Scalar) -> Point {
 
 pub static PUBLIC_KEY_OPS: PublicKeyOps = PublicKeyOps {
     common: &COMMON_OPS,
+    elem_sqrt_candidate: p256_elem_sqrt_candidate,
 };
 
+fn p256_elem_sqrt_candidate(a: &Elem<R>) -> Elem<R> {
+    // Calculate a**((q + 1) / 4) (mod q).
+    //
+    // The exponent (q + 1) / 4 is:
+    //
+    //    0x3fffffffc0000000400000000000000000000000400000000000000000000000
+
+    #[inline]
+    fn sqr_mul(a: &Elem<R>, squarings: usize, b: &Elem<R>) -> Elem<R> {
+        elem_sqr_mul(&COMMON_OPS, a, squarings, b)
+    }
+
+    #[inline]
+    fn sqr_mul_acc(a: &mut Elem<R>, squarings: usize, b: &Elem<R>) {
+        elem_sqr_mul_acc(&COMMON_OPS, a, squarings, b)
+    }
+
+    let b_1 = &a;
+    let b_11 = sqr_mul(b_1, 1, b_1);
+    let f = sqr_mul(&b_11, 2, &b_11);
+    let ff = sqr_mul(&f, 4, &f);
+    let ffff = sqr_mul(&ff, 8, &ff);
+
+    // ffffffff
+    let mut acc = sqr_mul(&ffff, 16, &ffff);
+
+    // ffffffff00000001
+    sqr_mul_acc(&mut acc, 32, b_1);
+
+    // ffffffff00000001000000000000000000000001
+    sqr_mul_acc(&mut acc, 96, b_1);
+
+    // 3fffffffc0000000400000000000000000000000400000000000000000000000
+    for _ in 0..94 {
+        COMMON_OPS.elem_square(&mut acc);
+    }
+
+    acc
+}
+
 pub static SCALAR_OPS: ScalarOps = ScalarOps {
     common: &COMMON_OPS,
     scalar_inv_to_mont_impl: p256_scalar_inv_to_mont,
@@ -155,8 +155,64 @@ fn p384_point_mul_base_impl(a: &Scalar) -> Point {
 
 pub static PUBLIC_KEY_OPS: PublicKeyOps = PublicKeyOps {
     common: &COMMON_OPS,
+    elem_sqrt_candidate: p384_elem_sqrt_candidate,
 };
 
+fn p384_elem_sqrt_candidate(a: &Elem<R>) -> Elem<R> {
+    // Calculate a**((q + 1) / 4) (mod q).
+    //
+    // The exponent (q + 1) / 4 is:
+    //
+    //    0x3fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff\
+    //      bfffffffc00000000000000040000000
+
+    #[inline]
+    fn sqr_mul(a: &Elem<R>, squarings: usize, b: &Elem<R>) -> Elem<R> {
+        elem_sqr_mul(&COMMON_OPS, a, squarings, b)
+    }
+
+    #[inline]
+    fn sqr_mul_acc(a: &mut Elem<R>, squarings: usize, b: &Elem<R>) {
+        elem_sqr_mul_acc(&COMMON_OPS, a, squarings, b)
+    }
+
+    let b_1 = &a;
+    let b_11 = sqr_mul(b_1, 1, b_1);
+    let b_111 = sqr_mul(&b_11, 1, b_1);
+    let f_11 = sqr_mul(&b_111, 3, &b_111);
+    let fff = sqr_mul(&f_11, 6, &f_11);
+    let fff_111 = sqr_mul(&fff, 3, &b_111);
+    let fffffff_11 = sqr_mul(&fff_111, 15, &fff_111);
+    let ffffffff = sqr_mul(&fffffff_11, 2, &b_11);
+
+    let fffffffffffffff = sqr_mul(&fffffff_11, 30, &fffffff_11);
+
+    let ffffffffffffffffffffffffffffff = sqr_mul(&fffffffffffffff, 60, &fffffffffffffff);
+
+    // ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff
+    let mut acc = sqr_mul(
+        &ffffffffffffffffffffffffffffff,
+        120,
+        &ffffffffffffffffffffffffffffff,
+    );
+
+    // 255 one bits.
+    sqr_mul_acc(&mut acc, 15, &fff_111);
+
+    // A zero bit and then 32 one bits.
+    sqr_mul_acc(&mut acc, 1 + 32, &ffffffff);
+
+    // 63 zero bits and then a one bit.
+    sqr_mul_acc(&mut acc, 64, b_1);
+
+    // 30 zero bits.
+    for _ in 0..30 {
+        COMMON_OPS.elem_square(&mut acc);
+    }
+
+    acc
+}
+
 pub static SCALAR_OPS: ScalarOps = ScalarOps {
     common: &COMMON_OPS,
     scalar_inv_to_mont_impl: p384_scalar_inv_to_mont,
@@ -16,7 +16,19 @@
 //! ECDH agreement).
 
 use super::{ops::*, verify_affine_point_is_on_the_curve};
-use crate::{arithmetic::montgomery::*, error};
+use crate::{arithmetic::montgomery::*, error, limb};
+
+/// Parses a public key encoded in either uncompressed or compressed form. The
+/// key is validated as described for `parse_uncompressed_point`.
+pub fn parse_point(
+    ops: &PublicKeyOps,
+    input: untrusted::Input,
+) -> Result<(Elem<R>, Elem<R>), error::Unspecified> {
+    match input.as_slice_less_safe().first() {
+        Some(2) | Some(3) => parse_compressed_point(ops, input),
+        _ => parse_uncompressed_point(ops, input),
+    }
+}
 
 /// Parses a public key encoded in uncompressed form. The key is validated
 /// using the ECC Partial Public-Key Validation Routine from
@@ -64,6 +76,63 @@ pub fn parse_uncompressed_point(
     Ok((x, y))
 }
 
+/// Parses a public key encoded in compressed form, recovering the y
+/// coordinate using the Octet-String-to-Elliptic-Curve-Point algorithm in
+/// [SEC 1: Elliptic Curve Cryptography, Version 2.0] Section 2.3.4. The key is
+/// then validated the same way as in `parse_uncompressed_point`.
+///
+/// [SEC 1: Elliptic Curve Cryptography, Version 2.0]:
+///     http://www.secg.org/sec1-v2.pdf
+pub fn parse_compressed_point(
+    ops: &PublicKeyOps,
+    input: untrusted::Input,
+) -> Result<(Elem<R>, Elem<R>), error::Unspecified> {
+    let (y_is_odd, x) = input.read_all(error::Unspecified, |input| {
+        // The encoding must be 2 or 3, which are the encodings for
+        // "compressed" with an even or odd y coordinate, respectively.
+        let y_is_odd = match input.read_byte()? {
+            2 => false,
+            3 => true,
+            _ => return Err(error::Unspecified),
+        };
+
+        // NIST SP 800-56A Step 2, for x. The y coordinate is computed below
+        // and is always in range.
+        let x = ops.elem_parse(input)?;
+        Ok((y_is_odd, x))
+    })?;
+
+    // y**2 == (x**2 + a)*x + b  (mod q)
+    let common = ops.common;
+    let mut y_squared = common.elem_squared(&x);
+    common.elem_add(&mut y_squared, &common.a);
+    common.elem_mul(&mut y_squared, &x);
+    common.elem_add(&mut y_squared, &common.b);
+
+    // If `y_squared` isn't a square then there is no point with the given x
+    // coordinate, and the candidate will fail the on-the-curve check below.
+    let y = ops.elem_sqrt_candidate(&y_squared);
+
+    // Choose whichever of y and -y has the requested parity. Neither curve
+    // has a point with y == 0, as they have prime order, so the other root
+    // always has the other parity.
+    let y_unencoded = common.elem_unencoded(&y);
+    let y_is_even = limb::limbs_are_even_constant_time(&y_unencoded.limbs[..common.num_limbs]);
+    let y = if (y_is_even == limb::LimbMask::True) == y_is_odd {
+        common.elem_negated(&y)
+    } else {
+        y
+    };
+
+    // NIST SP 800-56A Step 3.
+    verify_affine_point_is_on_the_curve(common, (&x, &y))?;
+
+    // See the notes at the end of `parse_uncompressed_point` regarding the
+    // order of the point.
+
+    Ok((x, y))
+}
+
 #[cfg(test)]
 mod tests {
     use super::{super::ops, *};
@@ -95,6 +164,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_compressed_point_test() {
+        test::run(
+            test_file!("suite_b_public_key_tests.txt"),
+            |section, test_case| {
+                assert_eq!(section, "");
+
+                let curve_name = test_case.consume_string("Curve");
+                let public_key = test_case.consume_bytes("Q");
+                let is_valid = test_case.consume_string("Result") == "P";
+                if !is_valid {
+                    return Ok(());
+                }
+
+                let curve_ops = public_key_ops_from_curve_name(&curve_name);
+                let num_limbs = curve_ops.common.num_limbs;
+                let (x, y) =
+                    parse_uncompressed_point(curve_ops, untrusted::Input::from(&public_key))
+                        .unwrap();
+
+                // Compress the point.
+                let elem_len = (public_key.len() - 1) / 2;
+                let mut compressed = public_key[..(1 + elem_len)].to_vec();
+                compressed[0] = 2 | (public_key[public_key.len() - 1] & 1);
+
+                let (actual_x, actual_y) =
+                    parse_point(curve_ops, untrusted::Input::from(&compressed)).unwrap();
+                assert_eq!(actual_x.limbs[..num_limbs], x.limbs[..num_limbs]);
+                assert_eq!(actual_y.limbs[..num_limbs], y.limbs[..num_limbs]);
+
+                // With the other parity, the point is the negation.
+                compressed[0] ^= 1;
+                let (actual_x, actual_y) =
+                    parse_point(curve_ops, untrusted::Input::from(&compressed)).unwrap();
+                let neg_y = curve_ops.common.elem_negated(&y);
+                assert_eq!(actual_x.limbs[..num_limbs], x.limbs[..num_limbs]);
+                assert_eq!(actual_y.limbs[..num_limbs], neg_y.limbs[..num_limbs]);
+
+                // `parse_uncompressed_point` doesn't accept compressed points.
+                assert!(
+                    parse_uncompressed_point(curve_ops, untrusted::Input::from(&compressed))
+                        .is_err()
+                );
+
+                Ok(())
+            },
+        );
+    }
+
     fn public_key_ops_from_curve_name(curve_name: &str) -> &'static PublicKeyOps {
         if curve_name == "P-256" {
             &ops::p256::PUBLIC_KEY_OPS
@@ -127,11 +127,19 @@ impl From<core::array::TryFromSliceError> for Unspecified {
 ///  * Wrong algorithm: The key is not valid for the algorithm in which it was
 ///    being used.
 ///
+///  * Decryption failed: An encrypted key couldn't be decrypted, usually
+///    because the password is wrong.
+///
 ///  * Unexpected errors: Report this as a bug.
 #[derive(Copy, Clone, Debug)]
 pub struct KeyRejected(&'static str);
 
 impl KeyRejected {
+    #[cfg(feature = "alloc")]
+    pub(crate) fn decryption_failed() -> Self {
+        Self("DecryptionFailed")
+    }
+
     pub(crate) fn inconsistent_components() -> Self {
         Self("InconsistentComponents")
     }
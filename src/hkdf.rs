@@ -18,7 +18,10 @@
 //!
 //! [RFC 5869]: https://tools.ietf.org/html/rfc5869
 
-use crate::{error, hmac};
+use crate::{digest, error, hmac};
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 
 /// An HKDF algorithm.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -69,15 +72,32 @@ impl Salt {
     ///
     /// [HKDF-Extract]: https://tools.ietf.org/html/rfc5869#section-2.2
     pub fn extract(&self, secret: &[u8]) -> Prk {
+        let prk = self.extract_(secret);
+        Prk {
+            key: hmac::Key::new(self.0.algorithm(), prk.as_ref()),
+            value: None,
+        }
+    }
+
+    /// Like [`Self::extract()`], except that the value of the resulting PRK
+    /// can be retrieved with [`Prk::value_less_safe()`].
+    ///
+    /// Usually one can avoid using this. Only use it when the application
+    /// intentionally wants to leak the PRK secret, since the returned `Prk`
+    /// keeps a copy of its value.
+    pub fn extract_exportable(&self, secret: &[u8]) -> Prk {
+        let prk = self.extract_(secret);
+        Prk::new_exportable(self.0.algorithm(), prk.as_ref())
+    }
+
+    fn extract_(&self, secret: &[u8]) -> hmac::Tag {
         // The spec says that if no salt is provided then a key of
         // `digest_alg.output_len` bytes of zeros is used. But, HMAC keys are
         // already zero-padded to the block length, which is larger than the output
         // length of the extract step (the length of the digest). Consequently the
         // `Key` constructor will automatically do the right thing for a
         // zero-length string.
-        let salt = &self.0;
-        let prk = hmac::sign(salt, secret);
-        Prk(hmac::Key::new(salt.algorithm(), prk.as_ref()))
+        hmac::sign(&self.0, secret)
     }
 
     /// The algorithm used to derive this salt.
@@ -105,8 +125,27 @@ pub trait KeyType {
 }
 
 /// A HKDF PRK (pseudorandom key).
-#[derive(Clone, Debug)]
-pub struct Prk(hmac::Key);
+#[derive(Clone)]
+pub struct Prk {
+    key: hmac::Key,
+
+    // The value of `key`, kept only for `value_less_safe()` and only when
+    // the `Prk` was constructed with `Salt::extract_exportable()` or
+    // `Prk::new_less_safe()`.
+    value: Option<ExportableValue>,
+}
+
+#[derive(Clone)]
+struct ExportableValue {
+    bytes: [u8; digest::MAX_BLOCK_LEN],
+    len: usize,
+}
+
+impl core::fmt::Debug for Prk {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_tuple("Prk").field(&self.key).finish()
+    }
+}
 
 impl Prk {
     /// Construct a new `Prk` directly with the given value.
@@ -114,8 +153,64 @@ impl Prk {
     /// Usually one can avoid using this. It is useful when the application
     /// intentionally wants to leak the PRK secret, e.g. to implement
     /// `SSLKEYLOGFILE` functionality.
+    ///
+    /// The returned `Prk` keeps a copy of its value for
+    /// [`Self::value_less_safe()`].
     pub fn new_less_safe(algorithm: Algorithm, value: &[u8]) -> Self {
-        Self(hmac::Key::new(algorithm.hmac_algorithm(), value))
+        Self::new_exportable(algorithm.hmac_algorithm(), value)
+    }
+
+    fn new_exportable(algorithm: hmac::Algorithm, value: &[u8]) -> Self {
+        // Like `hmac::Key::new()`, use the digest of values longer than the
+        // block length, so that the stored value is equivalent to `value`.
+        let digest_alg = algorithm.digest_algorithm();
+        let value_hash;
+        let value = if value.len() <= digest_alg.block_len() {
+            value
+        } else {
+            value_hash = digest::digest(digest_alg, value);
+            value_hash.as_ref()
+        };
+
+        let mut bytes = [0u8; digest::MAX_BLOCK_LEN];
+        bytes[..value.len()].copy_from_slice(value);
+        Self {
+            key: hmac::Key::new(algorithm, value),
+            value: Some(ExportableValue {
+                bytes,
+                len: value.len(),
+            }),
+        }
+    }
+
+    /// The value of the PRK, if it was constructed with
+    /// `Salt::extract_exportable()` or `Prk::new_less_safe()`.
+    ///
+    /// Usually one can avoid using this. It is useful when the application
+    /// intentionally wants to leak the PRK secret, e.g. to implement
+    /// `SSLKEYLOGFILE` functionality, or to persist the PRK for use by
+    /// another system. `Prk::new_less_safe()` reconstructs an equivalent
+    /// `Prk` from the value.
+    ///
+    /// Other `Prk`s don't keep a copy of their value, so this returns `None`
+    /// for them. To export a PRK that is the output of HKDF-Expand, fill a
+    /// buffer with the `Okm` and construct the `Prk` from it with
+    /// `Prk::new_less_safe()`.
+    ///
+    /// For a `Prk` constructed with `Salt::extract_exportable()` the value is
+    /// the output of HKDF-Extract. For one constructed with `new_less_safe()`
+    /// it is the value given, except that a value longer than the digest
+    /// algorithm's block length is replaced by its digest, which is
+    /// equivalent to it as an HMAC key.
+    #[inline]
+    pub fn value_less_safe(&self) -> Option<&[u8]> {
+        self.value.as_ref().map(|value| &value.bytes[..value.len])
+    }
+
+    /// The algorithm used to derive this PRK.
+    #[inline]
+    pub fn algorithm(&self) -> Algorithm {
+        Algorithm(self.key.algorithm())
     }
 
     /// The [HKDF-Expand] operation.
@@ -130,7 +225,7 @@ impl Prk {
         len: L,
     ) -> Result<Okm<'a, L>, error::Unspecified> {
         let len_cached = len.len();
-        if len_cached > 255 * self.0.algorithm().digest_algorithm().output_len() {
+        if len_cached > 255 * self.key.algorithm().digest_algorithm().output_len() {
             return Err(error::Unspecified);
         }
         Ok(Okm {
@@ -144,12 +239,15 @@ impl Prk {
 
 impl From<Okm<'_, Algorithm>> for Prk {
     fn from(okm: Okm<Algorithm>) -> Self {
-        Self(hmac::Key::from(Okm {
-            prk: okm.prk,
-            info: okm.info,
-            len: okm.len().0,
-            len_cached: okm.len_cached,
-        }))
+        Self {
+            key: hmac::Key::from(Okm {
+                prk: okm.prk,
+                info: okm.info,
+                len: okm.len().0,
+                len_cached: okm.len_cached,
+            }),
+            value: None,
+        }
     }
 }
 
@@ -195,10 +293,10 @@ fn fill_okm(
         return Err(error::Unspecified);
     }
 
-    let digest_alg = prk.0.algorithm().digest_algorithm();
+    let digest_alg = prk.key.algorithm().digest_algorithm();
     assert!(digest_alg.block_len() >= digest_alg.output_len());
 
-    let mut ctx = hmac::Context::with_key(&prk.0);
+    let mut ctx = hmac::Context::with_key(&prk.key);
 
     let mut n = 1u8;
     let mut out = out;
@@ -226,8 +324,45 @@ fn fill_okm(
             return Ok(());
         }
 
-        ctx = hmac::Context::with_key(&prk.0);
+        ctx = hmac::Context::with_key(&prk.key);
         ctx.update(t);
         n = n.checked_add(1).unwrap();
     }
 }
+
+/// Owned OKM (Output Keying Material) of any length.
+///
+/// Usually one can avoid using this by converting an `Okm` directly into the
+/// key type it is for, e.g. `aead::UnboundKey` or `hmac::Key`. It is useful
+/// when the keying material is for use outside of *ring*, e.g. keying
+/// material exported from a TLS connection.
+///
+/// Requires the `alloc` feature.
+#[cfg(feature = "alloc")]
+pub struct OkmBytes(Vec<u8>);
+
+#[cfg(feature = "alloc")]
+impl<L: KeyType> From<Okm<'_, L>> for OkmBytes {
+    fn from(okm: Okm<L>) -> Self {
+        let mut bytes = alloc::vec![0u8; okm.len_cached];
+        okm.fill(&mut bytes).unwrap();
+        Self(bytes)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl AsRef<[u8]> for OkmBytes {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl core::fmt::Debug for OkmBytes {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("OkmBytes")
+            .field("len", &self.0.len())
+            .finish()
+    }
+}
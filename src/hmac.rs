@@ -109,7 +109,7 @@
 //! [code for `ring::hkdf`]:
 //!     https://github.com/briansmith/ring/blob/main/src/hkdf.rs
 
-use crate::{constant_time, digest, error, hkdf, rand};
+use crate::{constant_time, digest, error, hkdf, kbkdf, rand, sskdf};
 
 /// An HMAC algorithm.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -268,6 +268,18 @@ impl From<hkdf::Okm<'_, Algorithm>> for Key {
     }
 }
 
+impl From<kbkdf::Okm<'_, Algorithm>> for Key {
+    fn from(okm: kbkdf::Okm<Algorithm>) -> Self {
+        Self::construct(*okm.len(), |buf| okm.fill(buf)).unwrap()
+    }
+}
+
+impl From<sskdf::Okm<'_, Algorithm>> for Key {
+    fn from(okm: sskdf::Okm<Algorithm>) -> Self {
+        Self::construct(*okm.len(), |buf| okm.fill(buf)).unwrap()
+    }
+}
+
 /// A context for multi-step (Init-Update-Finish) HMAC signing.
 ///
 /// Use `sign` for single-step HMAC signing.
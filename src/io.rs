@@ -18,7 +18,7 @@
 pub mod der;
 
 #[cfg(feature = "alloc")]
-mod writer;
+pub(crate) mod writer;
 
 #[cfg(feature = "alloc")]
 pub(crate) mod der_writer;
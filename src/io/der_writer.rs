@@ -26,6 +26,13 @@ pub(crate) fn write_positive_integer(output: &mut dyn Accumulator, value: &Posit
     })
 }
 
+pub(crate) fn write_bit_string_with_no_unused_bits(output: &mut dyn Accumulator, value: &[u8]) {
+    write_tlv(output, Tag::BitString, |output| {
+        output.write_byte(0); // No unused bits.
+        output.write_bytes(value);
+    })
+}
+
 pub(crate) fn write_all(tag: Tag, write_value: &dyn Fn(&mut dyn Accumulator)) -> Box<[u8]> {
     let length = {
         let mut length = LengthMeasurement::zero();
@@ -39,7 +46,7 @@ pub(crate) fn write_all(tag: Tag, write_value: &dyn Fn(&mut dyn Accumulator)) ->
     output.into()
 }
 
-fn write_tlv<F>(output: &mut dyn Accumulator, tag: Tag, write_value: F)
+pub(crate) fn write_tlv<F>(output: &mut dyn Accumulator, tag: Tag, write_value: F)
 where
     F: Fn(&mut dyn Accumulator),
 {
@@ -0,0 +1,78 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! JSON Object Signing and Encryption (JOSE).
+//!
+//! JSON Web Keys ([RFC 7517]) are supported for RSA ([RFC 7518 Section 6.3]),
+//! P-256 and P-384 ([RFC 7518 Section 6.2]), and Ed25519 and X25519
+//! ([RFC 8037]) keys, along with JWK thumbprints ([RFC 7638]).
+//!
+//! JSON Web Signatures ([RFC 7515]) can be signed and verified in the compact
+//! serialization with `RS256`, `PS256`, `ES256`, `ES384`, and `EdDSA`. A
+//! JSON Web Token ([RFC 7519]) is a JWS whose payload is a JSON claims set;
+//! validating the claims is left to the application.
+//!
+//! # Example
+//!
+//! ```
+//! # fn main() -> Result<(), ring::error::Unspecified> {
+//! use ring::{jose, rand, signature::{self, KeyPair}};
+//!
+//! let rng = rand::SystemRandom::new();
+//! let pkcs8 = signature::Ed25519KeyPair::generate_pkcs8(&rng)?;
+//! let key_pair = signature::Ed25519KeyPair::from_pkcs8(pkcs8.as_ref())
+//!     .map_err(|_| ring::error::Unspecified)?;
+//!
+//! let header = jose::Header { key_id: Some("k1"), media_type: Some("JWT") };
+//! let jwt = jose::sign_compact(
+//!     &jose::EDDSA,
+//!     jose::SigningKey::Ed25519(&key_pair),
+//!     &header,
+//!     br#"{"sub":"1234567890"}"#,
+//!     &rng,
+//! )?;
+//!
+//! let jws = jose::CompactJws::parse(&jwt)?;
+//! assert_eq!(jws.key_id_unverified(), Some("k1"));
+//! let verified = jws.verify(&jose::EDDSA, key_pair.public_key().as_ref())?;
+//! assert_eq!(verified.payload(), br#"{"sub":"1234567890"}"#);
+//!
+//! // The algorithm is chosen by the verifier, not by the JWS.
+//! assert!(jws.verify(&jose::ES256, key_pair.public_key().as_ref()).is_err());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [RFC 7517]: https://tools.ietf.org/html/rfc7517
+//! [RFC 7518 Section 6.2]: https://tools.ietf.org/html/rfc7518#section-6.2
+//! [RFC 7518 Section 6.3]: https://tools.ietf.org/html/rfc7518#section-6.3
+//! [RFC 8037]: https://tools.ietf.org/html/rfc8037
+//! [RFC 7638]: https://tools.ietf.org/html/rfc7638
+//! [RFC 7515]: https://tools.ietf.org/html/rfc7515
+//! [RFC 7519]: https://tools.ietf.org/html/rfc7519
+
+mod json;
+mod jwk;
+mod jws;
+
+pub use self::{
+    jwk::Jwk,
+    jws::{
+        sign_compact, Algorithm, CompactJws, Header, VerifiedJws, EDDSA, ES256, ES384, PS256, RS256,
+    },
+};
+pub use crate::{
+    key_components::{Curve, KeyComponents},
+    signer::SigningKey,
+};
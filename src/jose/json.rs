@@ -0,0 +1,436 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! A minimal JSON ([RFC 8259]) parser and serializer.
+//!
+//! Parsing is strict: the input must be a single UTF-8-encoded JSON value
+//! with no byte order mark, strings must not contain unpaired surrogates,
+//! and objects must not contain duplicate member names, as allowed by
+//! [RFC 7517 Section 4]. Numbers are kept in their textual form since nothing
+//! here needs their values.
+//!
+//! [RFC 8259]: https://tools.ietf.org/html/rfc8259
+//! [RFC 7517 Section 4]: https://tools.ietf.org/html/rfc7517#section-4
+
+use crate::error;
+use alloc::{string::String, vec::Vec};
+
+/// The maximum nesting depth of arrays and objects.
+const MAX_DEPTH: usize = 32;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) enum Value {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Parses `input`, which must contain exactly one JSON value, optionally
+    /// surrounded by whitespace.
+    pub(super) fn parse(input: &[u8]) -> Result<Self, error::Unspecified> {
+        let _ = core::str::from_utf8(input).map_err(|_| error::Unspecified)?;
+        untrusted::Input::from(input).read_all(error::Unspecified, |input| {
+            skip_whitespace(input);
+            let value = parse_value(input, 0)?;
+            skip_whitespace(input);
+            Ok(value)
+        })
+    }
+
+    /// Appends the serialization of the value to `out`, without any
+    /// insignificant whitespace.
+    pub(super) fn write(&self, out: &mut String) {
+        match self {
+            Self::Null => out.push_str("null"),
+            Self::Bool(true) => out.push_str("true"),
+            Self::Bool(false) => out.push_str("false"),
+            Self::Number(n) => out.push_str(n),
+            Self::String(s) => write_string(s, out),
+            Self::Array(elements) => {
+                out.push('[');
+                for (i, element) in elements.iter().enumerate() {
+                    if i != 0 {
+                        out.push(',');
+                    }
+                    element.write(out);
+                }
+                out.push(']');
+            }
+            Self::Object(members) => {
+                out.push('{');
+                for (i, (name, value)) in members.iter().enumerate() {
+                    if i != 0 {
+                        out.push(',');
+                    }
+                    write_string(name, out);
+                    out.push(':');
+                    value.write(out);
+                }
+                out.push('}');
+            }
+        }
+    }
+
+    /// The members of the value, if it is an object.
+    pub(super) fn as_object(&self) -> Option<&[(String, Self)]> {
+        match self {
+            Self::Object(members) => Some(members),
+            _ => None,
+        }
+    }
+
+    /// The value, if it is a string.
+    pub(super) fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+/// An object member whose value is the string `value`.
+pub(super) fn string_member(name: &str, value: &str) -> (String, Value) {
+    (String::from(name), Value::String(String::from(value)))
+}
+
+fn skip_whitespace(input: &mut untrusted::Reader) {
+    while input.peek(b' ') || input.peek(b'\t') || input.peek(b'\n') || input.peek(b'\r') {
+        let _ = input.read_byte();
+    }
+}
+
+fn expect_bytes(input: &mut untrusted::Reader, expected: &[u8]) -> Result<(), error::Unspecified> {
+    let actual = input.read_bytes(expected.len())?;
+    if actual.as_slice_less_safe() != expected {
+        return Err(error::Unspecified);
+    }
+    Ok(())
+}
+
+fn parse_value(input: &mut untrusted::Reader, depth: usize) -> Result<Value, error::Unspecified> {
+    if input.peek(b'{') {
+        parse_object(input, depth + 1)
+    } else if input.peek(b'[') {
+        parse_array(input, depth + 1)
+    } else if input.peek(b'"') {
+        parse_string(input).map(Value::String)
+    } else if input.peek(b't') {
+        expect_bytes(input, b"true").map(|()| Value::Bool(true))
+    } else if input.peek(b'f') {
+        expect_bytes(input, b"false").map(|()| Value::Bool(false))
+    } else if input.peek(b'n') {
+        expect_bytes(input, b"null").map(|()| Value::Null)
+    } else {
+        parse_number(input).map(Value::Number)
+    }
+}
+
+fn parse_object(input: &mut untrusted::Reader, depth: usize) -> Result<Value, error::Unspecified> {
+    if depth > MAX_DEPTH {
+        return Err(error::Unspecified);
+    }
+    expect_bytes(input, b"{")?;
+    let mut members: Vec<(String, Value)> = Vec::new();
+    skip_whitespace(input);
+    if !input.peek(b'}') {
+        loop {
+            skip_whitespace(input);
+            let name = parse_string(input)?;
+            skip_whitespace(input);
+            expect_bytes(input, b":")?;
+            skip_whitespace(input);
+            let value = parse_value(input, depth)?;
+            members.push((name, value));
+            skip_whitespace(input);
+            if !input.peek(b',') {
+                break;
+            }
+            let _ = input.read_byte();
+        }
+    }
+    expect_bytes(input, b"}")?;
+
+    // Sort the names instead of comparing each pair so that objects with many
+    // members don't take quadratic time.
+    let mut names: Vec<&str> = members.iter().map(|(name, _)| name.as_str()).collect();
+    names.sort_unstable();
+    if names.windows(2).any(|pair| pair[0] == pair[1]) {
+        return Err(error::Unspecified);
+    }
+
+    Ok(Value::Object(members))
+}
+
+fn parse_array(input: &mut untrusted::Reader, depth: usize) -> Result<Value, error::Unspecified> {
+    if depth > MAX_DEPTH {
+        return Err(error::Unspecified);
+    }
+    expect_bytes(input, b"[")?;
+    let mut elements = Vec::new();
+    skip_whitespace(input);
+    if !input.peek(b']') {
+        loop {
+            skip_whitespace(input);
+            elements.push(parse_value(input, depth)?);
+            skip_whitespace(input);
+            if !input.peek(b',') {
+                break;
+            }
+            let _ = input.read_byte();
+        }
+    }
+    expect_bytes(input, b"]")?;
+    Ok(Value::Array(elements))
+}
+
+fn parse_string(input: &mut untrusted::Reader) -> Result<String, error::Unspecified> {
+    expect_bytes(input, b"\"")?;
+    let mut out = Vec::new();
+    loop {
+        match input.read_byte()? {
+            b'"' => break,
+            b'\\' => {
+                let c = match input.read_byte()? {
+                    b'"' => '"',
+                    b'\\' => '\\',
+                    b'/' => '/',
+                    b'b' => '\u{08}',
+                    b'f' => '\u{0c}',
+                    b'n' => '\n',
+                    b'r' => '\r',
+                    b't' => '\t',
+                    b'u' => parse_unicode_escape(input)?,
+                    _ => return Err(error::Unspecified),
+                };
+                let mut buf = [0; 4];
+                out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            }
+            b if b < 0x20 => return Err(error::Unspecified),
+            b => out.push(b),
+        }
+    }
+    // The input was already verified to be UTF-8 and escapes are only ever
+    // replaced with complete characters, so this can't fail.
+    String::from_utf8(out).map_err(|_| error::Unspecified)
+}
+
+// Parses the part of a `\uXXXX` escape after the `\u`, including the second
+// escape of a surrogate pair.
+fn parse_unicode_escape(input: &mut untrusted::Reader) -> Result<char, error::Unspecified> {
+    let first = parse_hex4(input)?;
+    let code_point = match first {
+        0xd800..=0xdbff => {
+            expect_bytes(input, b"\\u")?;
+            let second = parse_hex4(input)?;
+            if !(0xdc00..=0xdfff).contains(&second) {
+                return Err(error::Unspecified);
+            }
+            0x10000 + (((first - 0xd800) << 10) | (second - 0xdc00))
+        }
+        0xdc00..=0xdfff => return Err(error::Unspecified),
+        _ => first,
+    };
+    char::from_u32(code_point).ok_or(error::Unspecified)
+}
+
+fn parse_hex4(input: &mut untrusted::Reader) -> Result<u32, error::Unspecified> {
+    let mut value = 0;
+    for _ in 0..4 {
+        let digit = char::from(input.read_byte()?)
+            .to_digit(16)
+            .ok_or(error::Unspecified)?;
+        value = (value << 4) | digit;
+    }
+    Ok(value)
+}
+
+fn parse_number(input: &mut untrusted::Reader) -> Result<String, error::Unspecified> {
+    fn skip_digits(input: &mut untrusted::Reader) -> Result<(), error::Unspecified> {
+        let mut any = false;
+        while let Some(b'0'..=b'9') = peek_digit(input) {
+            let _ = input.read_byte();
+            any = true;
+        }
+        if !any {
+            return Err(error::Unspecified);
+        }
+        Ok(())
+    }
+
+    let (number, ()) = input.read_partial(|input| {
+        if input.peek(b'-') {
+            let _ = input.read_byte();
+        }
+        if input.peek(b'0') {
+            let _ = input.read_byte();
+        } else {
+            skip_digits(input)?;
+        }
+        if input.peek(b'.') {
+            let _ = input.read_byte();
+            skip_digits(input)?;
+        }
+        if input.peek(b'e') || input.peek(b'E') {
+            let _ = input.read_byte();
+            if input.peek(b'+') || input.peek(b'-') {
+                let _ = input.read_byte();
+            }
+            skip_digits(input)?;
+        }
+        Ok::<(), error::Unspecified>(())
+    })?;
+    // Numbers consist only of ASCII characters.
+    core::str::from_utf8(number.as_slice_less_safe())
+        .map(String::from)
+        .map_err(|_| error::Unspecified)
+}
+
+fn peek_digit(input: &untrusted::Reader) -> Option<u8> {
+    (b'0'..=b'9').find(|&d| input.peek(d))
+}
+
+fn write_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0c}' => out.push_str("\\f"),
+            c if u32::from(c) < 0x20 => {
+                const HEX: &[u8; 16] = b"0123456789abcdef";
+                out.push_str("\\u00");
+                out.push(char::from(HEX[(u32::from(c) >> 4) as usize]));
+                out.push(char::from(HEX[(u32::from(c) & 0xf) as usize]));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{format, string::ToString, vec};
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Value::parse(b" {\"a\" : [1, -0.5e+3, true, false, null], \"b\": \"\\u00e9\\ud83d\\ude00\\n\"} \n"),
+            Ok(Value::Object(vec![
+                (
+                    "a".to_string(),
+                    Value::Array(vec![
+                        Value::Number("1".to_string()),
+                        Value::Number("-0.5e+3".to_string()),
+                        Value::Bool(true),
+                        Value::Bool(false),
+                        Value::Null,
+                    ])
+                ),
+                ("b".to_string(), Value::String("\u{e9}\u{1f600}\n".to_string())),
+            ]))
+        );
+        assert_eq!(Value::parse(b"[]"), Ok(Value::Array(vec![])));
+        assert_eq!(Value::parse(b"{}"), Ok(Value::Object(vec![])));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        const INVALID: &[&[u8]] = &[
+            b"",
+            b" ",
+            b"{",
+            b"{}}",
+            b"{} {}",
+            b"{\"a\":1,}",
+            b"[1,]",
+            b"[,]",
+            b"{\"a\":1,\"a\":2}",
+            b"{\"a\":1,\"b\":2,\"a\":3}",
+            b"{a:1}",
+            b"01",
+            b"1.",
+            b".5",
+            b"1e",
+            b"+1",
+            b"-",
+            b"tru",
+            b"nul",
+            b"\"\\x\"",
+            b"\"\\ud800\"",
+            b"\"\\udc00\"",
+            b"\"\\ud800\\u0041\"",
+            b"\"\\u12\"",
+            b"\"\x01\"",
+            b"\"\xff\"",
+            b"\xef\xbb\xbf{}",
+            b"'a'",
+        ];
+        for input in INVALID {
+            assert_eq!(Value::parse(input), Err(error::Unspecified), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_many_members() {
+        let members = (0..10_000)
+            .map(|i| format!("\"{}\":{}", i, i))
+            .collect::<Vec<_>>()
+            .join(",");
+        let object = format!("{{{}}}", members);
+        let value = Value::parse(object.as_bytes()).unwrap();
+        assert_eq!(value.as_object().unwrap().len(), 10_000);
+
+        let duplicate = format!("{{{},\"0\":0}}", members);
+        assert_eq!(Value::parse(duplicate.as_bytes()), Err(error::Unspecified));
+    }
+
+    #[test]
+    fn test_parse_depth() {
+        let nested = |depth| {
+            let mut s = "[".repeat(depth);
+            s.push_str(&"]".repeat(depth));
+            s
+        };
+        assert!(Value::parse(nested(MAX_DEPTH).as_bytes()).is_ok());
+        assert!(Value::parse(nested(MAX_DEPTH + 1).as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_write() {
+        let value = Value::Object(vec![
+            (
+                "a\"\\".to_string(),
+                Value::String("\u{1}\t\u{e9}".to_string()),
+            ),
+            (
+                "b".to_string(),
+                Value::Array(vec![Value::Null, Value::Number("1".to_string())]),
+            ),
+        ]);
+        let mut out = String::new();
+        value.write(&mut out);
+        assert_eq!(out, "{\"a\\\"\\\\\":\"\\u0001\\t\u{e9}\",\"b\":[null,1]}");
+        assert_eq!(Value::parse(out.as_bytes()), Ok(value));
+    }
+}
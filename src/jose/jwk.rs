@@ -0,0 +1,270 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use super::json::{string_member, Value};
+use crate::{
+    base64, digest, error,
+    key_components::{Curve, KeyComponents},
+    rsa,
+};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// A JSON Web Key ([RFC 7517]).
+///
+/// Only the members that determine the key material, `kid`, and `alg` are
+/// supported. Other members, like `use` and `key_ops`, are ignored when
+/// parsing and are never serialized.
+///
+/// [RFC 7517]: https://tools.ietf.org/html/rfc7517
+#[derive(Clone, Debug)]
+pub struct Jwk {
+    /// The key material.
+    pub components: KeyComponents,
+
+    /// The key ID (`kid`).
+    pub key_id: Option<String>,
+
+    /// The algorithm the key is intended for use with (`alg`), e.g. `ES256`.
+    pub algorithm: Option<String>,
+}
+
+impl Jwk {
+    /// Parses a JWK from its JSON serialization.
+    ///
+    /// The key type (`kty`) must be `RSA`, `EC`, or `OKP`, and for `EC` and
+    /// `OKP` keys the curve (`crv`) must be `P-256`, `P-384`, `Ed25519`, or
+    /// `X25519`. The public key is validated, but whether the private key, if
+    /// any, is consistent with it isn't checked until the components are used
+    /// to construct a key pair. RSA keys with more than two primes (`oth`)
+    /// aren't supported.
+    pub fn from_json(json: &[u8]) -> Result<Self, error::KeyRejected> {
+        let value = Value::parse(json)
+            .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
+        let members = value
+            .as_object()
+            .ok_or_else(error::KeyRejected::invalid_encoding)?;
+
+        let components = match required_member(members, "kty")? {
+            "RSA" => {
+                let public_key = rsa::PublicKeyComponents {
+                    n: uint_member(members, "n")?,
+                    e: uint_member(members, "e")?,
+                };
+                if members.iter().any(|(name, _)| name == "oth") {
+                    return Err(error::KeyRejected::invalid_component());
+                }
+                match optional_member(members, "d")? {
+                    None => KeyComponents::RsaPublicKey(public_key),
+                    Some(_) => KeyComponents::RsaKeyPair(rsa::KeyPairComponents {
+                        public_key,
+                        d: uint_member(members, "d")?,
+                        p: uint_member(members, "p")?,
+                        q: uint_member(members, "q")?,
+                        dP: uint_member(members, "dp")?,
+                        dQ: uint_member(members, "dq")?,
+                        qInv: uint_member(members, "qi")?,
+                    }),
+                }
+            }
+            kty @ ("EC" | "OKP") => {
+                let curve = match (kty, required_member(members, "crv")?) {
+                    ("EC", "P-256") => Curve::P256,
+                    ("EC", "P-384") => Curve::P384,
+                    ("OKP", "Ed25519") => Curve::Ed25519,
+                    ("OKP", "X25519") => Curve::X25519,
+                    _ => return Err(error::KeyRejected::wrong_algorithm()),
+                };
+                let len = curve.coordinate_len();
+                let public_key = match curve {
+                    Curve::P256 | Curve::P384 => {
+                        let mut public_key = Vec::with_capacity(1 + 2 * len);
+                        public_key.push(4); // Uncompressed encoding.
+                        public_key.extend_from_slice(&fixed_len_member(members, "x", len)?);
+                        public_key.extend_from_slice(&fixed_len_member(members, "y", len)?);
+                        public_key
+                    }
+                    Curve::Ed25519 | Curve::X25519 => fixed_len_member(members, "x", len)?,
+                };
+                match optional_member(members, "d")? {
+                    None => KeyComponents::EcPublicKey { curve, public_key },
+                    Some(_) => KeyComponents::EcKeyPair {
+                        curve,
+                        private_key: fixed_len_member(members, "d", curve.private_key_len())?,
+                        public_key,
+                    },
+                }
+            }
+            _ => return Err(error::KeyRejected::wrong_algorithm()),
+        };
+        components.check()?;
+
+        Ok(Self {
+            components,
+            key_id: optional_member(members, "kid")?.map(ToString::to_string),
+            algorithm: optional_member(members, "alg")?.map(ToString::to_string),
+        })
+    }
+
+    /// Serializes the JWK as JSON, without any insignificant whitespace.
+    ///
+    /// Private key components, if any, are included.
+    ///
+    /// Fails if `components` isn't valid.
+    pub fn to_json(&self) -> Result<String, error::Unspecified> {
+        let mut members = self.required_members()?;
+        match &self.components {
+            KeyComponents::RsaKeyPair(key_pair) => {
+                for (name, value) in [
+                    ("d", &key_pair.d),
+                    ("p", &key_pair.p),
+                    ("q", &key_pair.q),
+                    ("dp", &key_pair.dP),
+                    ("dq", &key_pair.dQ),
+                    ("qi", &key_pair.qInv),
+                ] {
+                    members.push(base64url_member(name, value));
+                }
+            }
+            KeyComponents::EcKeyPair { private_key, .. } => {
+                members.push(base64url_member("d", private_key));
+            }
+            KeyComponents::RsaPublicKey(_) | KeyComponents::EcPublicKey { .. } => {}
+        }
+        if let Some(key_id) = &self.key_id {
+            members.push(string_member("kid", key_id));
+        }
+        if let Some(algorithm) = &self.algorithm {
+            members.push(string_member("alg", algorithm));
+        }
+
+        let mut json = String::new();
+        Value::Object(members).write(&mut json);
+        Ok(json)
+    }
+
+    /// Computes the JWK thumbprint ([RFC 7638]) of the public key using
+    /// SHA-256.
+    ///
+    /// The thumbprint depends only on the public key, so a key pair and its
+    /// public key have the same thumbprint. The thumbprint is usually
+    /// base64url-encoded when used as a key ID.
+    ///
+    /// Fails if `components` isn't valid.
+    ///
+    /// [RFC 7638]: https://tools.ietf.org/html/rfc7638
+    pub fn thumbprint(&self) -> Result<digest::Digest, error::Unspecified> {
+        let mut json = String::new();
+        Value::Object(self.required_members()?).write(&mut json);
+        Ok(digest::digest(&digest::SHA256, json.as_bytes()))
+    }
+
+    // The members that are required for the public key, in lexicographic
+    // order as RFC 7638 Section 3.2 requires for thumbprints.
+    fn required_members(&self) -> Result<Vec<(String, Value)>, error::Unspecified> {
+        self.components
+            .check()
+            .map_err(|_: error::KeyRejected| error::Unspecified)?;
+
+        if let Some(public_key) = self.components.rsa_public_key() {
+            return Ok([
+                base64url_member("e", &public_key.e),
+                string_member("kty", "RSA"),
+                base64url_member("n", &public_key.n),
+            ]
+            .into());
+        }
+
+        let (curve, public_key) = self.components.ec_public_key().ok_or(error::Unspecified)?;
+        let (kty, crv) = match curve {
+            Curve::P256 => ("EC", "P-256"),
+            Curve::P384 => ("EC", "P-384"),
+            Curve::Ed25519 => ("OKP", "Ed25519"),
+            Curve::X25519 => ("OKP", "X25519"),
+        };
+        let mut members = [string_member("crv", crv), string_member("kty", kty)].to_vec();
+        match curve {
+            Curve::P256 | Curve::P384 => {
+                // `check()` verified that this is an uncompressed point.
+                let (x, y) = public_key[1..].split_at(curve.coordinate_len());
+                members.push(base64url_member("x", x));
+                members.push(base64url_member("y", y));
+            }
+            Curve::Ed25519 | Curve::X25519 => {
+                members.push(base64url_member("x", public_key));
+            }
+        }
+        Ok(members)
+    }
+}
+
+fn optional_member<'a>(
+    members: &'a [(String, Value)],
+    name: &str,
+) -> Result<Option<&'a str>, error::KeyRejected> {
+    members
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, value)| {
+            value
+                .as_str()
+                .ok_or_else(error::KeyRejected::invalid_encoding)
+        })
+        .transpose()
+}
+
+fn required_member<'a>(
+    members: &'a [(String, Value)],
+    name: &str,
+) -> Result<&'a str, error::KeyRejected> {
+    optional_member(members, name)?.ok_or_else(error::KeyRejected::invalid_encoding)
+}
+
+fn base64url_member_value(
+    members: &[(String, Value)],
+    name: &str,
+) -> Result<Vec<u8>, error::KeyRejected> {
+    base64::decode_url_unpadded(required_member(members, name)?.as_bytes())
+        .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())
+}
+
+// A "Base64urlUInt" (RFC 7518 Section 2), which must be encoded in the
+// minimum number of octets.
+fn uint_member(members: &[(String, Value)], name: &str) -> Result<Vec<u8>, error::KeyRejected> {
+    let value = base64url_member_value(members, name)?;
+    match value.first() {
+        None | Some(0) => Err(error::KeyRejected::invalid_encoding()),
+        Some(_) => Ok(value),
+    }
+}
+
+fn fixed_len_member(
+    members: &[(String, Value)],
+    name: &str,
+    len: usize,
+) -> Result<Vec<u8>, error::KeyRejected> {
+    let value = base64url_member_value(members, name)?;
+    if value.len() != len {
+        return Err(error::KeyRejected::invalid_encoding());
+    }
+    Ok(value)
+}
+
+fn base64url_member(name: &str, value: &[u8]) -> (String, Value) {
+    let mut encoded = String::new();
+    base64::encode_url_unpadded(value, &mut encoded);
+    (name.to_string(), Value::String(encoded))
+}
@@ -0,0 +1,251 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use super::json::{string_member, Value};
+use crate::{
+    base64, error, rand, signature,
+    signer::{SigningAlgorithm, SigningKey},
+};
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+/// A JWS algorithm ([RFC 7518 Section 3.1]).
+///
+/// There is deliberately no `Algorithm` for `none`, and the algorithm used
+/// for verification is always chosen by the caller, never by the JWS.
+///
+/// [RFC 7518 Section 3.1]: https://tools.ietf.org/html/rfc7518#section-3.1
+pub struct Algorithm {
+    name: &'static str,
+    verification_alg: &'static dyn signature::VerificationAlgorithm,
+    signing_alg: SigningAlgorithm,
+}
+
+derive_debug_via_field!(Algorithm, name);
+
+impl Algorithm {
+    /// The name of the algorithm, as used in the `alg` header parameter.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Looks up an algorithm by its name, e.g. `ES256`.
+    ///
+    /// Returns `None` for `none` and for any other unsupported algorithm.
+    pub fn from_name(name: &str) -> Option<&'static Self> {
+        ALGORITHMS.iter().copied().find(|alg| alg.name == name)
+    }
+}
+
+const ALGORITHMS: &[&Algorithm] = &[&RS256, &PS256, &ES256, &ES384, &EDDSA];
+
+/// RSASSA-PKCS1-v1_5 using SHA-256, with 2048-8192 bit keys.
+pub static RS256: Algorithm = Algorithm {
+    name: "RS256",
+    verification_alg: &signature::RSA_PKCS1_2048_8192_SHA256,
+    signing_alg: SigningAlgorithm::Rsa(&signature::RSA_PKCS1_SHA256),
+};
+
+/// RSASSA-PSS using SHA-256 and MGF1 with SHA-256, with 2048-8192 bit keys.
+pub static PS256: Algorithm = Algorithm {
+    name: "PS256",
+    verification_alg: &signature::RSA_PSS_2048_8192_SHA256,
+    signing_alg: SigningAlgorithm::Rsa(&signature::RSA_PSS_SHA256),
+};
+
+/// ECDSA using P-256 and SHA-256.
+pub static ES256: Algorithm = Algorithm {
+    name: "ES256",
+    verification_alg: &signature::ECDSA_P256_SHA256_FIXED,
+    signing_alg: SigningAlgorithm::Ecdsa(&signature::ECDSA_P256_SHA256_FIXED_SIGNING),
+};
+
+/// ECDSA using P-384 and SHA-384.
+pub static ES384: Algorithm = Algorithm {
+    name: "ES384",
+    verification_alg: &signature::ECDSA_P384_SHA384_FIXED,
+    signing_alg: SigningAlgorithm::Ecdsa(&signature::ECDSA_P384_SHA384_FIXED_SIGNING),
+};
+
+/// EdDSA using Ed25519 ([RFC 8037 Section 3.1]).
+///
+/// [RFC 8037 Section 3.1]: https://tools.ietf.org/html/rfc8037#section-3.1
+pub static EDDSA: Algorithm = Algorithm {
+    name: "EdDSA",
+    verification_alg: &signature::ED25519,
+    signing_alg: SigningAlgorithm::Ed25519,
+};
+
+/// The optional JOSE header parameters of a JWS.
+///
+/// `alg` is determined by the `Algorithm` and other header parameters aren't
+/// supported.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Header<'a> {
+    /// The key ID (`kid`).
+    pub key_id: Option<&'a str>,
+
+    /// The media type of the JWS (`typ`), e.g. `JWT`.
+    pub media_type: Option<&'a str>,
+}
+
+/// Signs `payload` and returns the JWS in compact serialization
+/// ([RFC 7515 Section 7.1]).
+///
+/// Fails if `key` can't be used with `algorithm`.
+///
+/// [RFC 7515 Section 7.1]: https://tools.ietf.org/html/rfc7515#section-7.1
+pub fn sign_compact(
+    algorithm: &'static Algorithm,
+    key: SigningKey,
+    header: &Header,
+    payload: &[u8],
+    rng: &dyn rand::SecureRandom,
+) -> Result<String, error::Unspecified> {
+    let mut members = vec![string_member("alg", algorithm.name)];
+    if let Some(key_id) = header.key_id {
+        members.push(string_member("kid", key_id));
+    }
+    if let Some(media_type) = header.media_type {
+        members.push(string_member("typ", media_type));
+    }
+    let mut header_json = String::new();
+    Value::Object(members).write(&mut header_json);
+
+    let mut jws = String::new();
+    base64::encode_url_unpadded(header_json.as_bytes(), &mut jws);
+    jws.push('.');
+    base64::encode_url_unpadded(payload, &mut jws);
+
+    let signature = algorithm.signing_alg.sign(key, jws.as_bytes(), rng)?;
+    jws.push('.');
+    base64::encode_url_unpadded(&signature, &mut jws);
+    Ok(jws)
+}
+
+/// A JWS in compact serialization ([RFC 7515 Section 7.1]) whose signature
+/// hasn't been verified yet.
+///
+/// [RFC 7515 Section 7.1]: https://tools.ietf.org/html/rfc7515#section-7.1
+#[derive(Debug)]
+pub struct CompactJws<'a> {
+    signing_input: &'a [u8],
+    alg: String,
+    key_id: Option<String>,
+    media_type: Option<String>,
+    payload: Vec<u8>,
+    signature: Vec<u8>,
+}
+
+impl<'a> CompactJws<'a> {
+    /// Parses a JWS in compact serialization.
+    ///
+    /// The JOSE header must be a JSON object containing an `alg` string. JWSs
+    /// with a `crit` header parameter are rejected, since none of the
+    /// extensions it could name are supported.
+    pub fn parse(jws: &'a str) -> Result<Self, error::Unspecified> {
+        let jws = jws.as_bytes();
+        let mut parts = jws.split(|&b| b == b'.');
+        let (header, payload, signature) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(header), Some(payload), Some(signature)) => (header, payload, signature),
+            _ => return Err(error::Unspecified),
+        };
+        if parts.next().is_some() {
+            return Err(error::Unspecified);
+        }
+        let signing_input = &jws[..(header.len() + 1 + payload.len())];
+
+        let header_json = base64::decode_url_unpadded(header)?;
+        let header_value = Value::parse(&header_json)?;
+        let members = header_value.as_object().ok_or(error::Unspecified)?;
+        let optional_string = |name| {
+            members
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, value)| {
+                    value
+                        .as_str()
+                        .map(ToString::to_string)
+                        .ok_or(error::Unspecified)
+                })
+                .transpose()
+        };
+        let alg = optional_string("alg")?.ok_or(error::Unspecified)?;
+        let key_id = optional_string("kid")?;
+        let media_type = optional_string("typ")?;
+        if members.iter().any(|(name, _)| name == "crit") {
+            return Err(error::Unspecified);
+        }
+
+        Ok(Self {
+            signing_input,
+            alg,
+            key_id,
+            media_type,
+            payload: base64::decode_url_unpadded(payload)?,
+            signature: base64::decode_url_unpadded(signature)?,
+        })
+    }
+
+    /// The key ID (`kid`), which isn't authenticated until `verify()`
+    /// succeeds. It may be used to choose the key to verify the JWS with.
+    pub fn key_id_unverified(&self) -> Option<&str> {
+        self.key_id.as_deref()
+    }
+
+    /// Verifies the signature of the JWS with `public_key`, which is
+    /// encoded as `signature::UnparsedPublicKey` expects for `algorithm`.
+    ///
+    /// Fails unless the `alg` header parameter is exactly `algorithm`'s name,
+    /// so a JWS can't choose a different algorithm than the one the key is
+    /// intended for, and `none` is never accepted.
+    pub fn verify(
+        &self,
+        algorithm: &'static Algorithm,
+        public_key: &[u8],
+    ) -> Result<VerifiedJws<'_>, error::Unspecified> {
+        if self.alg != algorithm.name {
+            return Err(error::Unspecified);
+        }
+        signature::UnparsedPublicKey::new(algorithm.verification_alg, public_key)
+            .verify(self.signing_input, &self.signature)?;
+        Ok(VerifiedJws { jws: self })
+    }
+}
+
+/// A JWS whose signature has been verified.
+#[derive(Debug)]
+pub struct VerifiedJws<'a> {
+    jws: &'a CompactJws<'a>,
+}
+
+impl VerifiedJws<'_> {
+    /// The payload. For a JWT, this is the JSON-encoded claims set.
+    pub fn payload(&self) -> &[u8] {
+        &self.jws.payload
+    }
+
+    /// The key ID (`kid`).
+    pub fn key_id(&self) -> Option<&str> {
+        self.jws.key_id.as_deref()
+    }
+
+    /// The media type of the JWS (`typ`), e.g. `JWT`.
+    pub fn media_type(&self) -> Option<&str> {
+        self.jws.media_type.as_deref()
+    }
+}
@@ -0,0 +1,249 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Key-Based Key Derivation Function (KBKDF) in counter mode with HMAC.
+//!
+//! KBKDF is specified in [NIST SP 800-108r1] Section 4.1. Each block of
+//! output `K(i)` is the HMAC, keyed with the key derivation key `K_IN`, of the
+//! block counter `[i]_r`, starting at 1, and the fixed input data, which
+//! encodes the label, the context, and the length of the output in bits
+//! `[L]`. `derive()` uses the encoding recommended in Section 5 of SP 800-108r1:
+//!
+//! ```text
+//! K(i) = HMAC(K_IN, [i]_32 || Label || 0x00 || Context || [L]_32)
+//! ```
+//!
+//! where `[i]_32` and `[L]_32` are 32-bit big-endian integers. This is the
+//! construction used by, e.g., Kerberos ([RFC 8009]).
+//!
+//! Other protocols put the counter elsewhere, use narrower counter and length
+//! fields, or omit some fields. For those, construct an [`Encoding`] and use
+//! `derive_with_encoding()`. For example, GlobalPlatform SCP03 encodes the
+//! input as
+//!
+//! ```text
+//! Label || 0x00 || [L]_16 || [i]_8 || Context
+//! ```
+//!
+//! which is `Encoding::new(&[Label, Separator, Length, Counter, Context], 1, 2)`,
+//! although SCP03 itself uses AES-CMAC rather than HMAC as the PRF.
+//!
+//! The key derivation key `K_IN` is an `hmac::Key`, and the output length is
+//! given by an `hkdf::KeyType`, so the output can be used to construct an
+//! `aead::UnboundKey` or `hmac::Key` directly.
+//!
+//! [NIST SP 800-108r1]: https://doi.org/10.6028/NIST.SP.800-108r1
+//! [RFC 8009]: https://tools.ietf.org/html/rfc8009
+
+use crate::{error, hkdf::KeyType, hmac};
+
+/// A field of the input to the PRF.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Field {
+    /// The block counter `[i]_r`, big-endian.
+    Counter,
+
+    /// The label.
+    Label,
+
+    /// The 0x00 byte that separates the label from the context.
+    Separator,
+
+    /// The context.
+    Context,
+
+    /// The length of the output in bits, `[L]`, big-endian.
+    Length,
+}
+
+// The number of distinct `Field`s.
+const MAX_FIELDS: usize = 5;
+
+/// How the block counter and the fixed input data are encoded in the input to
+/// the PRF.
+#[derive(Clone, Copy, Debug)]
+pub struct Encoding {
+    fields: [Option<Field>; MAX_FIELDS],
+    counter_len: usize,
+    length_len: usize,
+}
+
+/// `[i]_32 || Label || 0x00 || Context || [L]_32`, the encoding recommended
+/// by SP 800-108r1 and used by `derive()`.
+pub static DEFAULT_ENCODING: Encoding = Encoding {
+    fields: [
+        Some(Field::Counter),
+        Some(Field::Label),
+        Some(Field::Separator),
+        Some(Field::Context),
+        Some(Field::Length),
+    ],
+    counter_len: 4,
+    length_len: 4,
+};
+
+impl Encoding {
+    /// Constructs an encoding in which the input to the PRF is the
+    /// concatenation of `fields`, in order, with a `counter_len`-byte counter
+    /// and a `length_len`-byte length.
+    ///
+    /// Fails unless `fields` contains `Field::Counter` and contains no field
+    /// more than once, and `counter_len` and `length_len` are each between 1
+    /// and 4 (bytes).
+    pub fn new(
+        fields: &[Field],
+        counter_len: usize,
+        length_len: usize,
+    ) -> Result<Self, error::Unspecified> {
+        if !(1..=4).contains(&counter_len) || !(1..=4).contains(&length_len) {
+            return Err(error::Unspecified);
+        }
+        if fields.len() > MAX_FIELDS || !fields.contains(&Field::Counter) {
+            return Err(error::Unspecified);
+        }
+        let mut encoded = [None; MAX_FIELDS];
+        for (i, &field) in fields.iter().enumerate() {
+            if fields[..i].contains(&field) {
+                return Err(error::Unspecified);
+            }
+            encoded[i] = Some(field);
+        }
+        Ok(Self {
+            fields: encoded,
+            counter_len,
+            length_len,
+        })
+    }
+
+    fn fields(&self) -> impl Iterator<Item = Field> + '_ {
+        self.fields.iter().flatten().copied()
+    }
+}
+
+/// The KBKDF-in-counter-mode operation, using `DEFAULT_ENCODING`.
+///
+/// Fails if (and only if) `len` is too large: the length of the output in
+/// bits must fit in 32 bits.
+pub fn derive<'a, L: KeyType>(
+    key: &'a hmac::Key,
+    label: &'a [u8],
+    context: &'a [u8],
+    len: L,
+) -> Result<Okm<'a, L>, error::Unspecified> {
+    derive_with_encoding(&DEFAULT_ENCODING, key, label, context, len)
+}
+
+/// The KBKDF-in-counter-mode operation, using `encoding`.
+///
+/// Fails if (and only if) `len` is too large for `encoding`: the length of
+/// the output in bits must fit in the length field, and the number of blocks
+/// must fit in the counter.
+pub fn derive_with_encoding<'a, L: KeyType>(
+    encoding: &Encoding,
+    key: &'a hmac::Key,
+    label: &'a [u8],
+    context: &'a [u8],
+    len: L,
+) -> Result<Okm<'a, L>, error::Unspecified> {
+    let len_cached = len.len();
+    let len_bits = u32::try_from(len_cached)
+        .ok()
+        .and_then(|len| len.checked_mul(8))
+        .ok_or(error::Unspecified)?;
+    if u64::from(len_bits) >> (8 * encoding.length_len) != 0 {
+        return Err(error::Unspecified);
+    }
+    let output_len = key.algorithm().digest_algorithm().output_len();
+    let blocks = (len_cached + output_len - 1) / output_len;
+    if (blocks as u64) >> (8 * encoding.counter_len) != 0 {
+        return Err(error::Unspecified);
+    }
+    Ok(Okm {
+        key,
+        encoding: *encoding,
+        label,
+        context,
+        len,
+        len_cached,
+        len_bits,
+    })
+}
+
+/// A KBKDF OKM (Output Keying Material)
+///
+/// Intentionally not `Clone` or `Copy` as an OKM is generally only safe to
+/// use once.
+pub struct Okm<'a, L: KeyType> {
+    key: &'a hmac::Key,
+    encoding: Encoding,
+    label: &'a [u8],
+    context: &'a [u8],
+    len: L,
+    len_cached: usize,
+    len_bits: u32,
+}
+
+impl<L: KeyType> core::fmt::Debug for Okm<'_, L> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("Okm")
+            .field("key", self.key)
+            .field("encoding", &self.encoding)
+            .field("len", &self.len_cached)
+            .finish()
+    }
+}
+
+impl<L: KeyType> Okm<'_, L> {
+    /// The `KeyType` given to `derive()`.
+    #[inline]
+    pub fn len(&self) -> &L {
+        &self.len
+    }
+
+    /// Fills `out` with the output of the KBKDF operation for the given
+    /// inputs.
+    ///
+    /// Fails if (and only if) `out.len()` is not the length given to
+    /// `derive()`.
+    pub fn fill(self, out: &mut [u8]) -> Result<(), error::Unspecified> {
+        if out.len() != self.len_cached {
+            return Err(error::Unspecified);
+        }
+
+        let output_len = self.key.algorithm().digest_algorithm().output_len();
+        let counter_len = self.encoding.counter_len;
+        let length_len = self.encoding.length_len;
+
+        // `derive_with_encoding` ensured the number of blocks fits in the
+        // counter.
+        let mut i: u32 = 0;
+        for chunk in out.chunks_mut(output_len) {
+            i += 1;
+            let mut ctx = hmac::Context::with_key(self.key);
+            for field in self.encoding.fields() {
+                match field {
+                    Field::Counter => ctx.update(&i.to_be_bytes()[(4 - counter_len)..]),
+                    Field::Label => ctx.update(self.label),
+                    Field::Separator => ctx.update(&[0]),
+                    Field::Context => ctx.update(self.context),
+                    Field::Length => ctx.update(&self.len_bits.to_be_bytes()[(4 - length_len)..]),
+                }
+            }
+            let k_i = ctx.sign();
+            chunk.copy_from_slice(&k_i.as_ref()[..chunk.len()]);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,236 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! The key material of JWKs and COSE_Keys, in a form that is independent of
+//! both encodings.
+
+use crate::{cpu, debug::HexStr, ec, error, rsa};
+use alloc::vec::Vec;
+
+/// A curve, for elliptic curve keys.
+///
+/// JWK and COSE use the key type `EC` (`EC2`) for P-256 and P-384 keys and
+/// the key type `OKP` for Ed25519 and X25519 keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Curve {
+    /// P-256, for use with ECDSA and ECDH.
+    P256,
+
+    /// P-384, for use with ECDSA and ECDH.
+    P384,
+
+    /// Ed25519, for use with EdDSA.
+    Ed25519,
+
+    /// X25519, for use with ECDH.
+    X25519,
+}
+
+impl Curve {
+    // The length of a private key.
+    pub(crate) fn private_key_len(self) -> usize {
+        match self {
+            Self::P256 => 32,
+            Self::P384 => 48,
+            Self::Ed25519 | Self::X25519 => 32,
+        }
+    }
+
+    // The length of a coordinate of the public key.
+    pub(crate) fn coordinate_len(self) -> usize {
+        self.private_key_len()
+    }
+
+    // The curve for ECDSA and ECDH, for P-256 and P-384.
+    pub(crate) fn suite_b_curve(self) -> Option<&'static ec::Curve> {
+        match self {
+            Self::P256 => Some(&ec::suite_b::curve::P256),
+            Self::P384 => Some(&ec::suite_b::curve::P384),
+            Self::Ed25519 | Self::X25519 => None,
+        }
+    }
+}
+
+/// The components of a public key or key pair.
+///
+/// The components are encoded the same way that the rest of *ring* expects
+/// them, so that they can be used directly:
+///
+/// * RSA public keys can be used with `signature::RsaPublicKeyComponents`,
+///   and RSA key pairs with `rsa::KeyPair::from_components()`.
+/// * P-256 and P-384 public keys are uncompressed points, as used by
+///   `signature::UnparsedPublicKey`, and their private keys are the
+///   fixed-length big-endian scalars that
+///   `EcdsaKeyPair::from_private_key_and_public_key()` expects.
+/// * Ed25519 public keys and private key seeds are what
+///   `Ed25519KeyPair::from_seed_and_public_key()` expects.
+/// * X25519 public keys can be used with `agreement::UnparsedPublicKey`.
+///   *ring* doesn't support static X25519 private keys, so X25519 private
+///   keys are only carried through.
+///
+/// When parsing, public keys are validated but the consistency of the private
+/// key with the public key isn't checked; that is done when the components are
+/// used to construct a key pair.
+#[derive(Clone)]
+#[non_exhaustive]
+pub enum KeyComponents {
+    /// An RSA public key.
+    RsaPublicKey(rsa::PublicKeyComponents<Vec<u8>>),
+
+    /// An RSA key pair.
+    RsaKeyPair(rsa::KeyPairComponents<Vec<u8>>),
+
+    /// An elliptic curve public key.
+    EcPublicKey {
+        /// The curve.
+        curve: Curve,
+
+        /// The public key.
+        public_key: Vec<u8>,
+    },
+
+    /// An elliptic curve key pair.
+    EcKeyPair {
+        /// The curve.
+        curve: Curve,
+
+        /// The private key.
+        private_key: Vec<u8>,
+
+        /// The public key.
+        public_key: Vec<u8>,
+    },
+}
+
+impl core::fmt::Debug for KeyComponents {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        // Private components are intentionally skipped.
+        match self {
+            Self::RsaPublicKey(rsa::PublicKeyComponents { n, e })
+            | Self::RsaKeyPair(rsa::KeyPairComponents {
+                public_key: rsa::PublicKeyComponents { n, e },
+                ..
+            }) => f
+                .debug_struct(match self {
+                    Self::RsaPublicKey(_) => "RsaPublicKey",
+                    _ => "RsaKeyPair",
+                })
+                .field("n", &HexStr(n))
+                .field("e", &HexStr(e))
+                .finish(),
+            Self::EcPublicKey { curve, public_key }
+            | Self::EcKeyPair {
+                curve, public_key, ..
+            } => f
+                .debug_struct(match self {
+                    Self::EcPublicKey { .. } => "EcPublicKey",
+                    _ => "EcKeyPair",
+                })
+                .field("curve", curve)
+                .field("public_key", &HexStr(public_key))
+                .finish(),
+        }
+    }
+}
+
+impl KeyComponents {
+    /// The RSA public key components, if this is an RSA key.
+    #[cfg_attr(not(feature = "jose"), allow(dead_code))]
+    pub(crate) fn rsa_public_key(&self) -> Option<&rsa::PublicKeyComponents<Vec<u8>>> {
+        match self {
+            Self::RsaPublicKey(public_key) => Some(public_key),
+            Self::RsaKeyPair(key_pair) => Some(&key_pair.public_key),
+            Self::EcPublicKey { .. } | Self::EcKeyPair { .. } => None,
+        }
+    }
+
+    /// The curve and public key, if this is an elliptic curve key.
+    #[cfg_attr(not(feature = "jose"), allow(dead_code))]
+    pub(crate) fn ec_public_key(&self) -> Option<(Curve, &[u8])> {
+        match self {
+            Self::EcPublicKey { curve, public_key }
+            | Self::EcKeyPair {
+                curve, public_key, ..
+            } => Some((*curve, public_key)),
+            Self::RsaPublicKey(_) | Self::RsaKeyPair(_) => None,
+        }
+    }
+
+    /// Validates the public key and the lengths of the private key components.
+    pub(crate) fn check(&self) -> Result<(), error::KeyRejected> {
+        match self {
+            Self::RsaPublicKey(public_key) => check_rsa_public_key(public_key),
+            Self::RsaKeyPair(key_pair) => {
+                check_rsa_public_key(&key_pair.public_key)?;
+                let private_components = [
+                    &key_pair.d,
+                    &key_pair.p,
+                    &key_pair.q,
+                    &key_pair.dP,
+                    &key_pair.dQ,
+                    &key_pair.qInv,
+                ];
+                if private_components.iter().any(|c| c.is_empty()) {
+                    return Err(error::KeyRejected::invalid_encoding());
+                }
+                Ok(())
+            }
+            Self::EcPublicKey { curve, public_key } => check_ec_public_key(*curve, public_key),
+            Self::EcKeyPair {
+                curve,
+                private_key,
+                public_key,
+            } => {
+                check_ec_public_key(*curve, public_key)?;
+                if private_key.len() != curve.private_key_len() {
+                    return Err(error::KeyRejected::invalid_encoding());
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn check_rsa_public_key(
+    public_key: &rsa::PublicKeyComponents<Vec<u8>>,
+) -> Result<(), error::KeyRejected> {
+    // Accept any key that at least one of the RSA verification algorithms
+    // would accept, like `spki::SubjectPublicKeyInfo::from_der()`.
+    let _ = rsa::PublicKey::from_modulus_and_exponent_for_any_verification(
+        untrusted::Input::from(&public_key.n),
+        untrusted::Input::from(&public_key.e),
+        cpu::features(),
+    )?;
+    Ok(())
+}
+
+fn check_ec_public_key(curve: Curve, public_key: &[u8]) -> Result<(), error::KeyRejected> {
+    match curve {
+        Curve::P256 | Curve::P384 => {
+            let suite_b_curve = curve.suite_b_curve().unwrap();
+            // The EC public key validation requires the CPU features to have
+            // been detected.
+            let _ = cpu::features();
+            ec::suite_b::check_public_key(suite_b_curve, untrusted::Input::from(public_key))
+                .map_err(|error::Unspecified| error::KeyRejected::invalid_component())
+        }
+        Curve::Ed25519 | Curve::X25519 => {
+            if public_key.len() != curve.coordinate_len() {
+                return Err(error::KeyRejected::invalid_encoding());
+            }
+            Ok(())
+        }
+    }
+}
@@ -22,6 +22,12 @@
 //!     <th>Description
 //! <tr><td><code>alloc (default)</code>
 //!     <td>Enable features that require use of the heap, RSA in particular.
+//! <tr><td><code>cose</code>
+//!     <td>Enable the `cose` module, for COSE_Keys and COSE_Sign1 and
+//!         COSE_Encrypt0 messages. Implies `alloc`.
+//! <tr><td><code>jose</code>
+//!     <td>Enable the `jose` module, for JSON Web Keys and JSON Web
+//!         Signatures. Implies `alloc`.
 //! <tr><td><code>std</code>
 //!     <td>Enable features that use libstd, in particular
 //!         <code>std::error::Error</code> integration. Implies `alloc`.
@@ -75,11 +81,21 @@ pub mod aead;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod agreement;
 
+#[cfg(feature = "alloc")]
+mod base64;
+
 mod bits;
 
+// Only used by `aead::nacl`, which isn't available for wasm32.
+#[cfg(not(target_arch = "wasm32"))]
+mod blake2b;
+
 pub(crate) mod c;
 pub mod constant_time;
 
+#[cfg(feature = "cose")]
+pub mod cose;
+
 pub mod io;
 
 mod cpu;
@@ -89,16 +105,40 @@ mod endian;
 pub mod error;
 pub mod hkdf;
 pub mod hmac;
+
+#[cfg(feature = "jose")]
+pub mod jose;
+
+pub mod kbkdf;
+
+#[cfg(any(feature = "cose", feature = "jose"))]
+mod key_components;
+
 mod limb;
 pub mod pbkdf2;
+
+#[cfg(feature = "alloc")]
+pub mod pem;
+
 pub mod pkcs8;
 pub mod rand;
 
 #[cfg(feature = "alloc")]
 pub mod rsa;
 
+#[cfg(feature = "alloc")]
+pub mod scrypt;
+
+pub mod sec1;
 pub mod signature;
 
+#[cfg(any(feature = "cose", feature = "jose"))]
+mod signer;
+
+pub mod spki;
+pub mod sskdf;
+pub mod tls13;
+
 mod sealed {
     /// Traits that are designed to only be implemented internally in *ring*.
     //
@@ -111,10 +111,59 @@
 //!     // An attempt to log in with the right password succeeds.
 //!     assert!(db.verify_password("alice", "@74d7]404j|W}6u").is_ok());
 //! }
+//! ```
+//!
+//! ## PHC String Format Example
+//!
+//! Instead of storing the algorithm, iteration count, and salt separately,
+//! they can be stored along with the derived value in a [PHC string]. This
+//! requires the `alloc` feature.
+//!
+//! [PHC string]:
+//!     https://github.com/P-H-C/phc-string-format/blob/master/phc-sf-spec.md
+//!
+//! ```
+//! # #[cfg(feature = "alloc")]
+//! # {
+//! use ring::pbkdf2;
+//! use std::num::NonZeroU32;
+//!
+//! let min_iterations = NonZeroU32::new(100_000).unwrap();
+//! // Stored hashes with more iterations than this are rejected.
+//! let max_iterations = NonZeroU32::new(10_000_000).unwrap();
+//!
+//! // The salt should be randomly generated for each password.
+//! let salt = [0x5a; 16];
+//! let stored = pbkdf2::PhcString::derive(pbkdf2::PBKDF2_HMAC_SHA256,
+//!                                        min_iterations, &salt,
+//!                                        b"@74d7]404j|W}6u", 32)
+//!     .to_string();
+//! assert!(stored.starts_with("$pbkdf2-sha256$i=100000$"));
+//!
+//! assert_eq!(pbkdf2::verify_phc_string(&stored, b"@74d7]404j|W}6u",
+//!                                      min_iterations, max_iterations),
+//!            Ok(pbkdf2::Rehash::NotNeeded));
+//! assert!(pbkdf2::verify_phc_string(&stored, b"wrong password",
+//!                                   min_iterations, max_iterations).is_err());
+//!
+//! // After raising the minimum iteration count, the old hash still verifies
+//! // but should be replaced.
+//! let min_iterations = NonZeroU32::new(200_000).unwrap();
+//! assert_eq!(pbkdf2::verify_phc_string(&stored, b"@74d7]404j|W}6u",
+//!                                      min_iterations, max_iterations),
+//!            Ok(pbkdf2::Rehash::Needed));
+//! # }
+//! ```
 
 use crate::{constant_time, digest, error, hmac};
 use core::num::NonZeroU32;
 
+#[cfg(feature = "alloc")]
+mod phc;
+
+#[cfg(feature = "alloc")]
+pub use phc::{verify_phc_string, PhcString, Rehash};
+
 /// A PBKDF2 algorithm.
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub struct Algorithm(hmac::Algorithm);
@@ -0,0 +1,267 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use super::{
+    derive, verify, Algorithm, PBKDF2_HMAC_SHA1, PBKDF2_HMAC_SHA256, PBKDF2_HMAC_SHA384,
+    PBKDF2_HMAC_SHA512,
+};
+use crate::{base64, error, polyfill};
+use alloc::{string::String, vec, vec::Vec};
+use core::num::NonZeroU32;
+
+/// A PBKDF2 password hash in the [PHC string format].
+///
+/// A `PhcString` records the algorithm, iteration count, and salt alongside
+/// the derived value, e.g. `$pbkdf2-sha256$i=100000$<salt>$<hash>`, where the
+/// salt and hash are encoded in unpadded base64. The algorithm identifiers
+/// are:
+///
+/// | Identifier      | Algorithm
+/// |-----------------|---------------------------
+/// | `pbkdf2`        | `PBKDF2_HMAC_SHA1`
+/// | `pbkdf2-sha256` | `PBKDF2_HMAC_SHA256`
+/// | `pbkdf2-sha384` | `PBKDF2_HMAC_SHA384`
+/// | `pbkdf2-sha512` | `PBKDF2_HMAC_SHA512`
+///
+/// Use `to_string()` (via `Display`) to serialize a `PhcString`.
+///
+/// [PHC string format]:
+///     https://github.com/P-H-C/phc-string-format/blob/master/phc-sf-spec.md
+pub struct PhcString {
+    algorithm: Algorithm,
+    iterations: NonZeroU32,
+    salt: Vec<u8>,
+    hash: Vec<u8>,
+}
+
+/// Whether a password hash should be replaced with a stronger one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rehash {
+    /// The hash meets the caller's requirements.
+    NotNeeded,
+
+    /// The hash was derived with fewer iterations than the caller requires.
+    /// The application should derive a new hash from the (now verified)
+    /// secret and store it in place of the old one.
+    Needed,
+}
+
+impl PhcString {
+    /// Derives a `hash_len`-byte value from `secret` using PBKDF2 with the
+    /// given inputs and records it, along with those inputs, as a
+    /// `PhcString`.
+    ///
+    /// # Panics
+    ///
+    /// `derive` panics if `hash_len` is zero or if it is larger than
+    /// (2**32 - 1) * the digest algorithm's output length, per the PBKDF2
+    /// specification.
+    pub fn derive(
+        algorithm: Algorithm,
+        iterations: NonZeroU32,
+        salt: &[u8],
+        secret: &[u8],
+        hash_len: usize,
+    ) -> Self {
+        assert!(hash_len > 0);
+        let mut hash = vec![0u8; hash_len];
+        derive(algorithm, iterations, salt, secret, &mut hash);
+        Self {
+            algorithm,
+            iterations,
+            salt: salt.to_vec(),
+            hash,
+        }
+    }
+
+    /// Parses a PBKDF2 password hash in the PHC string format.
+    ///
+    /// The only parameter that is required is `i`, the iteration count. An
+    /// `l` parameter giving the length of the hash is also accepted, as long
+    /// as it matches the hash's actual length. Both the salt and the hash
+    /// must be present, and the hash must not be empty.
+    ///
+    /// Fails if the iteration count is larger than `max_iterations`, so that
+    /// an attacker-supplied string can't make verification take arbitrarily
+    /// long.
+    pub fn parse(phc: &str, max_iterations: NonZeroU32) -> Result<Self, error::Unspecified> {
+        let mut fields = phc.strip_prefix('$').ok_or(error::Unspecified)?.split('$');
+        let mut next_field = || fields.next().ok_or(error::Unspecified);
+
+        let algorithm = algorithm_from_id(next_field()?)?;
+
+        let mut params = next_field()?.split(',');
+        let i = params
+            .next()
+            .and_then(|param| param.strip_prefix("i="))
+            .ok_or(error::Unspecified)?;
+        let iterations = NonZeroU32::new(parse_decimal(i)?).ok_or(error::Unspecified)?;
+        if iterations > max_iterations {
+            return Err(error::Unspecified);
+        }
+        let hash_len = match params.next() {
+            Some(param) => {
+                let l = param.strip_prefix("l=").ok_or(error::Unspecified)?;
+                Some(parse_decimal(l)?)
+            }
+            None => None,
+        };
+        if params.next().is_some() {
+            return Err(error::Unspecified);
+        }
+
+        let salt = base64::decode_unpadded(next_field()?.as_bytes())?;
+        let hash = base64::decode_unpadded(next_field()?.as_bytes())?;
+        if hash.is_empty() {
+            return Err(error::Unspecified);
+        }
+        if let Some(hash_len) = hash_len {
+            if u64::from(hash_len) != polyfill::u64_from_usize(hash.len()) {
+                return Err(error::Unspecified);
+            }
+        }
+
+        if fields.next().is_some() {
+            return Err(error::Unspecified);
+        }
+
+        Ok(Self {
+            algorithm,
+            iterations,
+            salt,
+            hash,
+        })
+    }
+
+    /// The PBKDF2 algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// The iteration count.
+    #[inline]
+    pub fn iterations(&self) -> NonZeroU32 {
+        self.iterations
+    }
+
+    /// The salt.
+    #[inline]
+    pub fn salt(&self) -> &[u8] {
+        &self.salt
+    }
+
+    /// The previously-derived value.
+    #[inline]
+    pub fn hash(&self) -> &[u8] {
+        &self.hash
+    }
+
+    /// Verifies `secret` against the hash using `pbkdf2::verify` with the
+    /// algorithm, iteration count, and salt recorded in the `PhcString`.
+    ///
+    /// If verification succeeds and the hash was derived with fewer than
+    /// `min_iterations` iterations, `Rehash::Needed` is returned.
+    pub fn verify(
+        &self,
+        secret: &[u8],
+        min_iterations: NonZeroU32,
+    ) -> Result<Rehash, error::Unspecified> {
+        verify(
+            self.algorithm,
+            self.iterations,
+            &self.salt,
+            secret,
+            &self.hash,
+        )?;
+        if self.iterations < min_iterations {
+            return Ok(Rehash::Needed);
+        }
+        Ok(Rehash::NotNeeded)
+    }
+}
+
+impl core::fmt::Display for PhcString {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "${}$i={}$",
+            id_from_algorithm(self.algorithm),
+            self.iterations
+        )?;
+        let mut encoded = String::new();
+        base64::encode_unpadded(&self.salt, &mut encoded);
+        encoded.push('$');
+        base64::encode_unpadded(&self.hash, &mut encoded);
+        f.write_str(&encoded)
+    }
+}
+
+impl core::fmt::Debug for PhcString {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("PhcString")
+            .field("algorithm", &id_from_algorithm(self.algorithm))
+            .field("iterations", &self.iterations)
+            .finish()
+    }
+}
+
+/// Parses `phc` and verifies `secret` against it.
+///
+/// This is equivalent to `PhcString::parse(phc, max_iterations)?.verify(
+/// secret, min_iterations)`. Parse failures and verification failures are not
+/// distinguished.
+pub fn verify_phc_string(
+    phc: &str,
+    secret: &[u8],
+    min_iterations: NonZeroU32,
+    max_iterations: NonZeroU32,
+) -> Result<Rehash, error::Unspecified> {
+    PhcString::parse(phc, max_iterations)?.verify(secret, min_iterations)
+}
+
+fn id_from_algorithm(algorithm: Algorithm) -> &'static str {
+    if algorithm == PBKDF2_HMAC_SHA1 {
+        "pbkdf2"
+    } else if algorithm == PBKDF2_HMAC_SHA256 {
+        "pbkdf2-sha256"
+    } else if algorithm == PBKDF2_HMAC_SHA384 {
+        "pbkdf2-sha384"
+    } else if algorithm == PBKDF2_HMAC_SHA512 {
+        "pbkdf2-sha512"
+    } else {
+        unreachable!()
+    }
+}
+
+fn algorithm_from_id(id: &str) -> Result<Algorithm, error::Unspecified> {
+    match id {
+        "pbkdf2" => Ok(PBKDF2_HMAC_SHA1),
+        "pbkdf2-sha256" => Ok(PBKDF2_HMAC_SHA256),
+        "pbkdf2-sha384" => Ok(PBKDF2_HMAC_SHA384),
+        "pbkdf2-sha512" => Ok(PBKDF2_HMAC_SHA512),
+        _ => Err(error::Unspecified),
+    }
+}
+
+// Decimal parameter values must be canonical: no sign and no leading zeros.
+fn parse_decimal(value: &str) -> Result<u32, error::Unspecified> {
+    if value.is_empty()
+        || !value.bytes().all(|b| b.is_ascii_digit())
+        || (value.len() > 1 && value.starts_with('0'))
+    {
+        return Err(error::Unspecified);
+    }
+    value.parse().map_err(|_| error::Unspecified)
+}
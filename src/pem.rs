@@ -0,0 +1,203 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! PEM encoding and decoding of keys, as specified in [RFC 7468].
+//!
+//! Only the strict form of [RFC 7468 Section 3] is accepted when decoding:
+//! the input must consist of exactly one PEM block, optionally followed by a
+//! line ending, with no explanatory text or whitespace around it and no
+//! headers. Every line of the Base64-encoded contents except the last must be
+//! exactly 64 characters long, and the contents must be canonically encoded
+//! with padding. Lines may end in either LF or CRLF.
+//!
+//! [RFC 7468]: https://tools.ietf.org/html/rfc7468
+//! [RFC 7468 Section 3]: https://tools.ietf.org/html/rfc7468#section-3
+
+use crate::{base64, error};
+use alloc::{string::String, vec::Vec};
+
+/// The label of a PEM block, which identifies the type of its contents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Label {
+    /// `PRIVATE KEY`: a PKCS#8 `PrivateKeyInfo` or `OneAsymmetricKey`, as
+    /// used by `from_pkcs8()` and produced by `generate_pkcs8()`
+    /// ([RFC 7468 Section 10]).
+    ///
+    /// [RFC 7468 Section 10]: https://tools.ietf.org/html/rfc7468#section-10
+    PrivateKey,
+
+    /// `ENCRYPTED PRIVATE KEY`: a PKCS#8 `EncryptedPrivateKeyInfo`, as used
+    /// by `from_encrypted_pkcs8()` and produced by
+    /// `pkcs8::Document::encrypt()` ([RFC 7468 Section 11]).
+    ///
+    /// [RFC 7468 Section 11]: https://tools.ietf.org/html/rfc7468#section-11
+    EncryptedPrivateKey,
+
+    /// `PUBLIC KEY`: a SubjectPublicKeyInfo, as used by
+    /// `spki::SubjectPublicKeyInfo::from_der()` and produced by the
+    /// `to_spki()` methods of public keys ([RFC 7468 Section 13]).
+    ///
+    /// [RFC 7468 Section 13]: https://tools.ietf.org/html/rfc7468#section-13
+    PublicKey,
+
+    /// `RSA PRIVATE KEY`: an `RSAPrivateKey`, as used by
+    /// `rsa::KeyPair::from_der()`.
+    RsaPrivateKey,
+
+    /// `EC PRIVATE KEY`: a SEC 1 `ECPrivateKey`, as used by
+    /// `EcdsaKeyPair::from_sec1()` and produced by
+    /// `EcdsaKeyPair::to_sec1_less_safe()`.
+    EcPrivateKey,
+}
+
+impl Label {
+    /// The label as it appears in the encapsulation boundaries.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PrivateKey => "PRIVATE KEY",
+            Self::EncryptedPrivateKey => "ENCRYPTED PRIVATE KEY",
+            Self::PublicKey => "PUBLIC KEY",
+            Self::RsaPrivateKey => "RSA PRIVATE KEY",
+            Self::EcPrivateKey => "EC PRIVATE KEY",
+        }
+    }
+}
+
+const LABELS: &[Label] = &[
+    Label::PrivateKey,
+    Label::EncryptedPrivateKey,
+    Label::PublicKey,
+    Label::RsaPrivateKey,
+    Label::EcPrivateKey,
+];
+
+const LINE_LEN: usize = 64;
+
+const BEGIN: &[u8] = b"-----BEGIN ";
+const END: &[u8] = b"-----END ";
+const BOUNDARY_SUFFIX: &[u8] = b"-----";
+
+/// Decodes a PEM block, returning its label and its binary (usually DER)
+/// contents.
+///
+/// Fails if `pem` isn't a single PEM block in the strict form described in
+/// the module-level documentation, or if the label isn't one of the `Label`s.
+pub fn decode(pem: &[u8]) -> Result<(Label, Vec<u8>), error::Unspecified> {
+    let mut lines = Lines(pem);
+
+    let label = lines
+        .next()?
+        .strip_prefix(BEGIN)
+        .and_then(|line| line.strip_suffix(BOUNDARY_SUFFIX))
+        .ok_or(error::Unspecified)?;
+    let label = *LABELS
+        .iter()
+        .find(|l| l.as_str().as_bytes() == label)
+        .ok_or(error::Unspecified)?;
+
+    let mut encoded = Vec::with_capacity(pem.len());
+    let mut last_line_len = LINE_LEN;
+    let end = loop {
+        let line = lines.next()?;
+        if line.starts_with(END) {
+            break line;
+        }
+        // Only the last line may be shorter than `LINE_LEN`.
+        if line.is_empty() || line.len() > LINE_LEN || last_line_len != LINE_LEN {
+            return Err(error::Unspecified);
+        }
+        last_line_len = line.len();
+        encoded.extend_from_slice(line);
+    };
+
+    let end_label = end
+        .strip_prefix(END)
+        .and_then(|line| line.strip_suffix(BOUNDARY_SUFFIX))
+        .ok_or(error::Unspecified)?;
+    if end_label != label.as_str().as_bytes() || !lines.0.is_empty() {
+        return Err(error::Unspecified);
+    }
+
+    let contents = base64::decode_padded(&encoded)?;
+    Ok((label, contents))
+}
+
+/// Decodes a PEM block with the label `expected`, returning its binary
+/// (usually DER) contents.
+///
+/// This is like `decode()`, except it also fails if the block's label isn't
+/// `expected`.
+pub fn decode_expecting(expected: Label, pem: &[u8]) -> Result<Vec<u8>, error::Unspecified> {
+    let (label, contents) = decode(pem)?;
+    if label != expected {
+        return Err(error::Unspecified);
+    }
+    Ok(contents)
+}
+
+/// Encodes `contents` as a PEM block with the given label, in the strict
+/// form described in the module-level documentation, using LF line endings.
+///
+/// `contents` is usually a DER document, such as a `pkcs8::Document` or an
+/// `spki::Document`.
+pub fn encode(label: Label, contents: &[u8]) -> String {
+    let mut encoded = String::new();
+    base64::encode_padded(contents, &mut encoded);
+
+    let label = label.as_str();
+    let mut pem = String::with_capacity(
+        (2 * (BEGIN.len() + label.len() + BOUNDARY_SUFFIX.len() + 1))
+            + encoded.len()
+            + (encoded.len() / LINE_LEN)
+            + 1,
+    );
+    pem.push_str("-----BEGIN ");
+    pem.push_str(label);
+    pem.push_str("-----\n");
+    // The Base64 alphabet is ASCII, so splitting it into lines is safe.
+    for line in encoded.as_bytes().chunks(LINE_LEN) {
+        pem.extend(line.iter().map(|&b| char::from(b)));
+        pem.push('\n');
+    }
+    pem.push_str("-----END ");
+    pem.push_str(label);
+    pem.push_str("-----\n");
+    pem
+}
+
+/// Splits the input into lines, each of which must end in LF or CRLF, except
+/// the last, for which the line ending is optional.
+struct Lines<'a>(&'a [u8]);
+
+impl<'a> Lines<'a> {
+    fn next(&mut self) -> Result<&'a [u8], error::Unspecified> {
+        if self.0.is_empty() {
+            return Err(error::Unspecified);
+        }
+        let (line, rest) = match self.0.iter().position(|&b| b == b'\n') {
+            Some(i) => {
+                let line = &self.0[..i];
+                (line.strip_suffix(b"\r").unwrap_or(line), &self.0[(i + 1)..])
+            }
+            None => (self.0, &[][..]),
+        };
+        self.0 = rest;
+        // A bare CR isn't a valid line ending.
+        if line.contains(&b'\r') {
+            return Err(error::Unspecified);
+        }
+        Ok(line)
+    }
+}
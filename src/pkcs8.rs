@@ -18,6 +18,24 @@
 
 use crate::{ec, error, io::der};
 
+#[cfg(feature = "alloc")]
+use crate::rand;
+
+#[cfg(feature = "alloc")]
+use core::num::NonZeroU32;
+
+#[cfg(feature = "alloc")]
+mod pbes2;
+
+#[cfg(feature = "alloc")]
+pub use pbes2::{
+    EncryptedDocument, EncryptionAlgorithm, PBES2_HMAC_SHA256_AES_256_CBC,
+    PBES2_HMAC_SHA256_AES_256_GCM,
+};
+
+#[cfg(feature = "alloc")]
+pub(crate) use pbes2::decrypt;
+
 pub(crate) struct PublicKeyOptions {
     /// Should the wrong public key ASN.1 tagging used by early implementations
     /// of PKCS#8 v2 (including earlier versions of *ring*) be accepted?
@@ -53,7 +71,7 @@ pub(crate) struct Template {
 
 impl Template {
     #[inline]
-    fn alg_id_value(&self) -> untrusted::Input {
+    pub(crate) fn alg_id_value(&self) -> untrusted::Input {
         untrusted::Input::from(self.alg_id_value_())
     }
 
@@ -187,6 +205,30 @@ impl AsRef<[u8]> for Document {
     }
 }
 
+impl Document {
+    /// Encrypts the document under `password`, producing a PKCS#8
+    /// `EncryptedPrivateKeyInfo`.
+    ///
+    /// The encryption key is derived from `password` using PBKDF2-HMAC-SHA256
+    /// with `iterations` iterations and a random salt. The result can be
+    /// loaded with the `from_encrypted_pkcs8()` method of the key pair type
+    /// that generated the document.
+    ///
+    /// `from_encrypted_pkcs8()` accepts documents encrypted with any of the
+    /// `EncryptionAlgorithm`s, and also AES-128-GCM, as long as PBKDF2 uses
+    /// HMAC-SHA256 and the AES-GCM tag is 128 bits.
+    #[cfg(feature = "alloc")]
+    pub fn encrypt(
+        &self,
+        algorithm: &'static EncryptionAlgorithm,
+        password: &[u8],
+        iterations: NonZeroU32,
+        rng: &dyn rand::SecureRandom,
+    ) -> Result<EncryptedDocument, error::Unspecified> {
+        pbes2::encrypt(algorithm, self.as_ref(), password, iterations, rng)
+    }
+}
+
 pub(crate) fn wrap_key(template: &Template, private_key: &[u8], public_key: &[u8]) -> Document {
     let mut result = Document {
         bytes: [0; ec::PKCS8_DOCUMENT_MAX_LEN],
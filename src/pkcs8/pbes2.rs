@@ -0,0 +1,457 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Password-based encryption of PKCS#8 documents with PBES2.
+//!
+//! PBES2 is specified in [RFC 8018 Section 6.2]; the `EncryptedPrivateKeyInfo`
+//! structure is specified in [RFC 5958 Section 3]. Only PBKDF2 with
+//! HMAC-SHA256 is supported for key derivation, with AES-256-CBC
+//! ([RFC 8018 Appendix B.2.5]) or AES-GCM ([RFC 5084]) for encryption.
+//!
+//! [RFC 8018 Section 6.2]: https://tools.ietf.org/html/rfc8018#section-6.2
+//! [RFC 5958 Section 3]: https://tools.ietf.org/html/rfc5958#section-3
+//! [RFC 8018 Appendix B.2.5]: https://tools.ietf.org/html/rfc8018#appendix-B.2.5
+//! [RFC 5084]: https://tools.ietf.org/html/rfc5084
+
+use crate::{
+    aead::{self, aes::cbc},
+    debug, error,
+    io::{der, der_writer, writer::Accumulator, Positive},
+    pbkdf2, rand,
+};
+use alloc::{boxed::Box, vec::Vec};
+use core::num::NonZeroU32;
+
+/// A password-based encryption algorithm for PKCS#8 documents.
+#[derive(Debug)]
+pub struct EncryptionAlgorithm {
+    cipher: Cipher,
+}
+
+/// PBES2 with PBKDF2-HMAC-SHA256 key derivation and AES-256-CBC encryption.
+///
+/// This is what `openssl pkcs8 -topk8 -v2 aes-256-cbc -v2prf hmacWithSHA256`
+/// produces, so it is the most interoperable choice. CBC mode doesn't
+/// authenticate the ciphertext.
+pub static PBES2_HMAC_SHA256_AES_256_CBC: EncryptionAlgorithm = EncryptionAlgorithm {
+    cipher: Cipher::Aes256Cbc,
+};
+
+/// PBES2 with PBKDF2-HMAC-SHA256 key derivation and AES-256-GCM encryption,
+/// with a 96-bit nonce and a 128-bit tag.
+pub static PBES2_HMAC_SHA256_AES_256_GCM: EncryptionAlgorithm = EncryptionAlgorithm {
+    cipher: Cipher::Aes256Gcm,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Cipher {
+    Aes256Cbc,
+    Aes128Gcm,
+    Aes256Gcm,
+}
+
+impl Cipher {
+    fn from_oid(oid: untrusted::Input) -> Result<Self, error::KeyRejected> {
+        [Self::Aes256Cbc, Self::Aes128Gcm, Self::Aes256Gcm]
+            .iter()
+            .copied()
+            .find(|cipher| cipher.oid() == oid.as_slice_less_safe())
+            .ok_or_else(error::KeyRejected::wrong_algorithm)
+    }
+
+    fn oid(self) -> &'static [u8] {
+        match self {
+            // 2.16.840.1.101.3.4.1.42
+            Self::Aes256Cbc => &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x01, 0x2a],
+            // 2.16.840.1.101.3.4.1.6
+            Self::Aes128Gcm => &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x01, 0x06],
+            // 2.16.840.1.101.3.4.1.46
+            Self::Aes256Gcm => &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x01, 0x2e],
+        }
+    }
+
+    fn key_len(self) -> usize {
+        match self {
+            Self::Aes256Cbc => cbc::KEY_LEN,
+            Self::Aes128Gcm => aead::AES_128_GCM.key_len(),
+            Self::Aes256Gcm => aead::AES_256_GCM.key_len(),
+        }
+    }
+
+    fn iv_len(self) -> usize {
+        match self {
+            Self::Aes256Cbc => cbc::IV_LEN,
+            Self::Aes128Gcm | Self::Aes256Gcm => aead::NONCE_LEN,
+        }
+    }
+
+    fn encrypt(
+        self,
+        key: &[u8],
+        iv: &[u8],
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>, error::Unspecified> {
+        match self {
+            Self::Aes256Cbc => cbc::encrypt(key.try_into()?, iv.try_into()?, plaintext),
+            Self::Aes128Gcm | Self::Aes256Gcm => {
+                let key = self.aead_key(key)?;
+                let nonce = aead::Nonce::try_assume_unique_for_key(iv)?;
+                let mut in_out = plaintext.to_vec();
+                key.seal_in_place_append_tag(nonce, aead::Aad::empty(), &mut in_out)?;
+                Ok(in_out)
+            }
+        }
+    }
+
+    fn decrypt(
+        self,
+        key: &[u8],
+        iv: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, error::Unspecified> {
+        match self {
+            Self::Aes256Cbc => cbc::decrypt(key.try_into()?, iv.try_into()?, ciphertext),
+            Self::Aes128Gcm | Self::Aes256Gcm => {
+                let key = self.aead_key(key)?;
+                let nonce = aead::Nonce::try_assume_unique_for_key(iv)?;
+                let mut in_out = ciphertext.to_vec();
+                let plaintext_len = key
+                    .open_in_place(nonce, aead::Aad::empty(), &mut in_out)?
+                    .len();
+                in_out.truncate(plaintext_len);
+                Ok(in_out)
+            }
+        }
+    }
+
+    fn aead_key(self, key: &[u8]) -> Result<aead::LessSafeKey, error::Unspecified> {
+        let algorithm = match self {
+            Self::Aes128Gcm => &aead::AES_128_GCM,
+            Self::Aes256Gcm => &aead::AES_256_GCM,
+            Self::Aes256Cbc => return Err(error::Unspecified),
+        };
+        aead::UnboundKey::new(algorithm, key).map(aead::LessSafeKey::new)
+    }
+}
+
+// 1.2.840.113549.1.5.13
+const PBES2_OID: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x05, 0x0d];
+
+// 1.2.840.113549.1.5.12
+const PBKDF2_OID: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x05, 0x0c];
+
+// The value of the `AlgorithmIdentifier` for hmacWithSHA256
+// (1.2.840.113549.2.9), with NULL parameters.
+const HMAC_SHA256_ALG_ID: &[u8] = &[
+    0x06, 0x08, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x02, 0x09, 0x05, 0x00,
+];
+
+static PBKDF2_ALG: pbkdf2::Algorithm = pbkdf2::PBKDF2_HMAC_SHA256;
+
+const SALT_LEN: usize = 16;
+
+// The only tag length that is supported for AES-GCM, in bytes.
+const GCM_TAG_LEN: u8 = 16;
+
+const MAX_KEY_LEN: usize = 32;
+const MAX_IV_LEN: usize = 16;
+
+/// An encrypted PKCS#8 document (an `EncryptedPrivateKeyInfo`).
+#[derive(Clone)]
+pub struct EncryptedDocument(Box<[u8]>);
+
+impl AsRef<[u8]> for EncryptedDocument {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl core::fmt::Debug for EncryptedDocument {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_tuple("EncryptedDocument")
+            .field(&debug::HexStr(self.as_ref()))
+            .finish()
+    }
+}
+
+struct Params<'a> {
+    salt: untrusted::Input<'a>,
+    iterations: NonZeroU32,
+    cipher: Cipher,
+    iv: untrusted::Input<'a>,
+}
+
+/// Parses an `EncryptedPrivateKeyInfo` and decrypts it with `password`,
+/// returning the encoded `PrivateKeyInfo` or `OneAsymmetricKey`.
+///
+/// Documents whose PBKDF2 iteration count exceeds `max_iterations` are
+/// rejected before any key derivation is done, so that decrypting an
+/// untrusted document can't be made arbitrarily expensive.
+pub(crate) fn decrypt(
+    input: untrusted::Input,
+    password: &[u8],
+    max_iterations: NonZeroU32,
+) -> Result<Vec<u8>, error::KeyRejected> {
+    let (params, encrypted_data) =
+        input.read_all(error::KeyRejected::invalid_encoding(), |input| {
+            der::nested(
+                input,
+                der::Tag::Sequence,
+                error::KeyRejected::invalid_encoding(),
+                |input| {
+                    let params = der::nested(
+                        input,
+                        der::Tag::Sequence,
+                        error::KeyRejected::invalid_encoding(),
+                        |input| {
+                            expect_oid(input, PBES2_OID)?;
+                            der::nested(
+                                input,
+                                der::Tag::Sequence,
+                                error::KeyRejected::invalid_encoding(),
+                                pbes2_params,
+                            )
+                        },
+                    )?;
+                    let encrypted_data =
+                        der::expect_tag_and_get_value(input, der::Tag::OctetString)
+                            .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
+                    Ok((params, encrypted_data))
+                },
+            )
+        })?;
+
+    if params.iterations > max_iterations {
+        return Err(error::KeyRejected::too_large());
+    }
+
+    let mut key = [0u8; MAX_KEY_LEN];
+    let key = &mut key[..params.cipher.key_len()];
+    pbkdf2::derive(
+        PBKDF2_ALG,
+        params.iterations,
+        params.salt.as_slice_less_safe(),
+        password,
+        key,
+    );
+    params
+        .cipher
+        .decrypt(
+            key,
+            params.iv.as_slice_less_safe(),
+            encrypted_data.as_slice_less_safe(),
+        )
+        .map_err(|error::Unspecified| error::KeyRejected::decryption_failed())
+}
+
+fn pbes2_params<'a>(input: &mut untrusted::Reader<'a>) -> Result<Params<'a>, error::KeyRejected> {
+    let (salt, iterations, key_len) = der::nested(
+        input,
+        der::Tag::Sequence,
+        error::KeyRejected::invalid_encoding(),
+        |input| {
+            expect_oid(input, PBKDF2_OID)?;
+            der::nested(
+                input,
+                der::Tag::Sequence,
+                error::KeyRejected::invalid_encoding(),
+                pbkdf2_params,
+            )
+        },
+    )?;
+
+    let (cipher, iv) = der::nested(
+        input,
+        der::Tag::Sequence,
+        error::KeyRejected::invalid_encoding(),
+        |input| {
+            let oid = der::expect_tag_and_get_value(input, der::Tag::OID)
+                .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
+            let cipher = Cipher::from_oid(oid)?;
+            let iv = match cipher {
+                Cipher::Aes256Cbc => der::expect_tag_and_get_value(input, der::Tag::OctetString)
+                    .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?,
+                Cipher::Aes128Gcm | Cipher::Aes256Gcm => der::nested(
+                    input,
+                    der::Tag::Sequence,
+                    error::KeyRejected::invalid_encoding(),
+                    gcm_params,
+                )?,
+            };
+            if iv.len() != cipher.iv_len() {
+                return Err(error::KeyRejected::invalid_encoding());
+            }
+            Ok((cipher, iv))
+        },
+    )?;
+
+    if let Some(key_len) = key_len {
+        if usize::from(key_len) != cipher.key_len() {
+            return Err(error::KeyRejected::invalid_encoding());
+        }
+    }
+
+    Ok(Params {
+        salt,
+        iterations,
+        cipher,
+        iv,
+    })
+}
+
+fn pbkdf2_params<'a>(
+    input: &mut untrusted::Reader<'a>,
+) -> Result<(untrusted::Input<'a>, NonZeroU32, Option<u8>), error::KeyRejected> {
+    let salt = der::expect_tag_and_get_value(input, der::Tag::OctetString)
+        .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
+
+    let iterations = der::positive_integer(input)
+        .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
+    let iterations = iterations.big_endian_without_leading_zero();
+    if iterations.len() > 4 {
+        return Err(error::KeyRejected::invalid_encoding());
+    }
+    let iterations = iterations
+        .iter()
+        .fold(0u32, |acc, &b| (acc << 8) | u32::from(b));
+    let iterations =
+        NonZeroU32::new(iterations).ok_or_else(error::KeyRejected::invalid_encoding)?;
+
+    let key_len = if input.peek(der::Tag::Integer.into()) {
+        let key_len = der::small_nonnegative_integer(input)
+            .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
+        Some(key_len)
+    } else {
+        None
+    };
+
+    // The PRF defaults to HMAC-SHA1, which isn't supported.
+    if input.at_end() {
+        return Err(error::KeyRejected::wrong_algorithm());
+    }
+    let prf = der::expect_tag_and_get_value(input, der::Tag::Sequence)
+        .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
+    if prf.as_slice_less_safe() != HMAC_SHA256_ALG_ID {
+        return Err(error::KeyRejected::wrong_algorithm());
+    }
+
+    Ok((salt, iterations, key_len))
+}
+
+fn gcm_params<'a>(
+    input: &mut untrusted::Reader<'a>,
+) -> Result<untrusted::Input<'a>, error::KeyRejected> {
+    let nonce = der::expect_tag_and_get_value(input, der::Tag::OctetString)
+        .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
+    // The tag length defaults to 12 bytes, which isn't supported.
+    if input.at_end() {
+        return Err(error::KeyRejected::wrong_algorithm());
+    }
+    let tag_len = der::small_nonnegative_integer(input)
+        .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
+    if tag_len != GCM_TAG_LEN {
+        return Err(error::KeyRejected::wrong_algorithm());
+    }
+    Ok(nonce)
+}
+
+fn expect_oid(input: &mut untrusted::Reader, oid: &[u8]) -> Result<(), error::KeyRejected> {
+    let actual = der::expect_tag_and_get_value(input, der::Tag::OID)
+        .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
+    if actual.as_slice_less_safe() != oid {
+        return Err(error::KeyRejected::wrong_algorithm());
+    }
+    Ok(())
+}
+
+pub(super) fn encrypt(
+    algorithm: &EncryptionAlgorithm,
+    plaintext: &[u8],
+    password: &[u8],
+    iterations: NonZeroU32,
+    rng: &dyn rand::SecureRandom,
+) -> Result<EncryptedDocument, error::Unspecified> {
+    let cipher = algorithm.cipher;
+
+    let mut salt = [0u8; SALT_LEN];
+    rng.fill(&mut salt)?;
+    let mut iv = [0u8; MAX_IV_LEN];
+    let iv = &mut iv[..cipher.iv_len()];
+    rng.fill(iv)?;
+    let iv: &[u8] = iv;
+
+    let mut key = [0u8; MAX_KEY_LEN];
+    let key = &mut key[..cipher.key_len()];
+    pbkdf2::derive(PBKDF2_ALG, iterations, &salt, password, key);
+    let encrypted_data = cipher.encrypt(key, iv, plaintext)?;
+
+    let iterations = iterations.get().to_be_bytes();
+    let leading_zeros = iterations.iter().take_while(|&&b| b == 0).count();
+    let iterations = Positive::from_be_bytes(untrusted::Input::from(&iterations[leading_zeros..]))?;
+
+    let write_encryption_scheme = |output: &mut dyn Accumulator| {
+        der_writer::write_tlv(output, der::Tag::OID, |output| {
+            output.write_bytes(cipher.oid())
+        });
+        match cipher {
+            Cipher::Aes256Cbc => der_writer::write_tlv(output, der::Tag::OctetString, |output| {
+                output.write_bytes(iv)
+            }),
+            Cipher::Aes128Gcm | Cipher::Aes256Gcm => {
+                der_writer::write_tlv(output, der::Tag::Sequence, |output| {
+                    der_writer::write_tlv(output, der::Tag::OctetString, |output| {
+                        output.write_bytes(iv)
+                    });
+                    der_writer::write_tlv(output, der::Tag::Integer, |output| {
+                        output.write_byte(GCM_TAG_LEN)
+                    });
+                })
+            }
+        }
+    };
+
+    let write_pbes2_params = |output: &mut dyn Accumulator| {
+        der_writer::write_tlv(output, der::Tag::Sequence, |output| {
+            der_writer::write_tlv(output, der::Tag::OID, |output| {
+                output.write_bytes(PBKDF2_OID)
+            });
+            der_writer::write_tlv(output, der::Tag::Sequence, |output| {
+                der_writer::write_tlv(output, der::Tag::OctetString, |output| {
+                    output.write_bytes(&salt)
+                });
+                der_writer::write_positive_integer(output, &iterations);
+                der_writer::write_tlv(output, der::Tag::Sequence, |output| {
+                    output.write_bytes(HMAC_SHA256_ALG_ID)
+                });
+            });
+        });
+        der_writer::write_tlv(output, der::Tag::Sequence, write_encryption_scheme);
+    };
+
+    Ok(EncryptedDocument(der_writer::write_all(
+        der::Tag::Sequence,
+        &|output| {
+            der_writer::write_tlv(output, der::Tag::Sequence, |output| {
+                der_writer::write_tlv(output, der::Tag::OID, |output| {
+                    output.write_bytes(PBES2_OID)
+                });
+                der_writer::write_tlv(output, der::Tag::Sequence, write_pbes2_params);
+            });
+            der_writer::write_tlv(output, der::Tag::OctetString, |output| {
+                output.write_bytes(&encrypted_data)
+            });
+        },
+    )))
+}
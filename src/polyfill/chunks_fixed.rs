@@ -59,6 +59,7 @@ macro_rules! define_chunks_fixed {
 }
 
 // Sorted by the first value, then the second value.
+define_chunks_fixed!(8, 4);
 define_chunks_fixed!(12, 4);
 define_chunks_fixed!(16, 4);
 define_chunks_fixed!(16, 8);
@@ -31,6 +31,10 @@ pub(crate) mod padding;
 // Maximum RSA modulus size supported for signature verification (in bytes).
 const PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN: usize = bigint::MODULUS_MAX_LIMBS * limb::LIMB_BYTES;
 
+// The value of the `AlgorithmIdentifier` for `rsaEncryption`, including the
+// NULL parameters, which is used for both PKCS#8 and SubjectPublicKeyInfo.
+pub(crate) const RSA_ENCRYPTION: &[u8] = include_bytes!("data/alg-rsa-encryption.der");
+
 // Keep in sync with the documentation comment for `KeyPair`.
 const PRIVATE_KEY_PUBLIC_MODULUS_MAX_BITS: bits::BitLength = bits::BitLength::from_usize_bits(4096);
 
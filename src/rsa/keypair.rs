@@ -27,6 +27,7 @@ use crate::{
     io::der,
     pkcs8, rand, signature,
 };
+use core::num::NonZeroU32;
 
 /// An RSA key pair, used for signing.
 pub struct KeyPair {
@@ -95,15 +96,41 @@ impl KeyPair {
     /// [RFC 5958]:
     ///     https://tools.ietf.org/html/rfc5958
     pub fn from_pkcs8(pkcs8: &[u8]) -> Result<Self, KeyRejected> {
-        const RSA_ENCRYPTION: &[u8] = include_bytes!("../data/alg-rsa-encryption.der");
         let (der, _) = pkcs8::unwrap_key_(
-            untrusted::Input::from(RSA_ENCRYPTION),
+            untrusted::Input::from(super::RSA_ENCRYPTION),
             pkcs8::Version::V1Only,
             untrusted::Input::from(pkcs8),
         )?;
         Self::from_der(der.as_slice_less_safe())
     }
 
+    /// Parses an RSA private key in an encrypted PKCS#8 document, an
+    /// `EncryptedPrivateKeyInfo`, after decrypting it with `password`.
+    ///
+    /// Keys encrypted like this are accepted:
+    ///
+    /// ```sh
+    /// openssl pkcs8 -topk8 -v2 aes-256-cbc -v2prf hmacWithSHA256 -outform der \
+    ///     -in rsa-2048-private-key.pem > rsa-2048-private-key.encrypted.pk8
+    /// ```
+    ///
+    /// Documents that use more than `max_iterations` PBKDF2 iterations are
+    /// rejected with a `TooLarge` error without being decrypted.
+    ///
+    /// In all other respects, this is just like `from_pkcs8()`.
+    pub fn from_encrypted_pkcs8(
+        encrypted_pkcs8: &[u8],
+        password: &[u8],
+        max_iterations: NonZeroU32,
+    ) -> Result<Self, KeyRejected> {
+        let pkcs8 = pkcs8::decrypt(
+            untrusted::Input::from(encrypted_pkcs8),
+            password,
+            max_iterations,
+        )?;
+        Self::from_pkcs8(&pkcs8)
+    }
+
     /// Parses an RSA private key that is not inside a PKCS#8 wrapper.
     ///
     /// The private key must be encoded as a binary DER-encoded ASN.1
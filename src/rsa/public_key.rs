@@ -18,6 +18,7 @@ use crate::{
     bits, cpu, error,
     io::{self, der, der_writer},
     limb::LIMB_BYTES,
+    spki,
 };
 use alloc::boxed::Box;
 
@@ -78,6 +79,49 @@ impl PublicKey {
         Ok(Self { n, e, serialized })
     }
 
+    /// Parses and validates a DER-encoded `RSAPublicKey`, accepting any key
+    /// that at least one of the RSA verification algorithms would accept.
+    pub(crate) fn from_der(
+        input: untrusted::Input,
+        cpu_features: cpu::Features,
+    ) -> Result<Self, error::KeyRejected> {
+        let (n, e) = super::parse_public_key(input)
+            .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
+        Self::from_modulus_and_exponent_for_any_verification(
+            n.big_endian_without_leading_zero_as_input(),
+            e.big_endian_without_leading_zero_as_input(),
+            cpu_features,
+        )
+    }
+
+    /// Validates the big-endian-encoded modulus `n` and public exponent `e`,
+    /// accepting any key that at least one of the RSA verification algorithms
+    /// would accept.
+    pub(crate) fn from_modulus_and_exponent_for_any_verification(
+        n: untrusted::Input,
+        e: untrusted::Input,
+        cpu_features: cpu::Features,
+    ) -> Result<Self, error::KeyRejected> {
+        let n_max_bits = bits::BitLength::from_usize_bytes(PUBLIC_KEY_PUBLIC_MODULUS_MAX_LEN)
+            .map_err(|error::Unspecified| error::KeyRejected::unexpected_error())?;
+        Self::from_modulus_and_exponent(
+            n,
+            e,
+            bits::BitLength::from_usize_bits(1024),
+            n_max_bits,
+            PublicExponent::_3,
+            cpu_features,
+        )
+    }
+
+    /// The public key as a DER-encoded SubjectPublicKeyInfo, identified by
+    /// `rsaEncryption` ([RFC 3279 Section 2.3.1]).
+    ///
+    /// [RFC 3279 Section 2.3.1]: https://tools.ietf.org/html/rfc3279#section-2.3.1
+    pub fn to_spki(&self) -> spki::Document {
+        spki::wrap_key(spki::KeyType::Rsa, &self.serialized)
+    }
+
     /// The length, in bytes, of the public modulus.
     ///
     /// The modulus length is rounded up to a whole number of bytes if its
@@ -0,0 +1,325 @@
+// Copyright 2015-2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! scrypt derivation and verification.
+//!
+//! Use `derive` to derive scrypt outputs. Use `verify` to verify secret
+//! against previously-derived outputs.
+//!
+//! scrypt is specified in [RFC 7914], which also gives the test vectors.
+//! scrypt is built on PBKDF2-HMAC-SHA256 (see `ring::pbkdf2`) and the
+//! Salsa20/8 core.
+//!
+//! scrypt is a memory-hard function; the memory needed for a derivation is
+//! determined by the cost parameters. `Params::new` checks the parameters
+//! against a caller-supplied memory limit so that parameters read from an
+//! untrusted source (e.g. a wallet file) cannot be used to exhaust memory.
+//!
+//! [RFC 7914]: https://tools.ietf.org/html/rfc7914
+//!
+//! # Examples
+//!
+//! ```
+//! use ring::scrypt;
+//!
+//! // N = 2**14, r = 8, p = 1, using no more than 32 MiB of memory.
+//! let params = scrypt::Params::new(14, 8, 1, 32 * 1024 * 1024).unwrap();
+//!
+//! let salt = b"SodiumChloride";
+//! let mut derived = [0u8; 32];
+//! scrypt::derive(params, salt, b"pleaseletmein", &mut derived);
+//!
+//! assert!(scrypt::verify(params, salt, b"pleaseletmein", &derived).is_ok());
+//! assert!(scrypt::verify(params, salt, b"wrong password", &derived).is_err());
+//! ```
+
+use crate::{aead::salsa20, constant_time, error, pbkdf2, polyfill};
+use alloc::{vec, vec::Vec};
+use core::num::NonZeroU32;
+
+/// Validated scrypt cost parameters.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Params {
+    log_n: u8,
+    r: u32,
+    p: u32,
+    memory_len: usize,
+}
+
+impl Params {
+    /// Validates the cost parameters `N = 2**log_n`, `r`, and `p`.
+    ///
+    /// | Parameter | RFC 7914 Section 6 Term
+    /// |-----------|-----------------------------------------
+    /// | log_n     | log2(N) (CPU/Memory cost parameter)
+    /// | r         | r (block size parameter)
+    /// | p         | p (parallelization parameter)
+    ///
+    /// Fails if the parameters are not valid per RFC 7914, i.e. unless
+    /// `1 <= log_n < 16 * r` and `1 <= r * p < 2**30`. Also fails if the
+    /// amount of memory that `derive` and `verify` would allocate, as
+    /// returned by `memory_len()`, is larger than `max_memory_len` bytes.
+    pub fn new(
+        log_n: u8,
+        r: u32,
+        p: u32,
+        max_memory_len: usize,
+    ) -> Result<Self, error::Unspecified> {
+        if log_n == 0 || r == 0 || p == 0 {
+            return Err(error::Unspecified);
+        }
+        // N must be less than 2**(128 * r / 8).
+        if u64::from(log_n) >= u64::from(r) * 16 {
+            return Err(error::Unspecified);
+        }
+        if u64::from(r) * u64::from(p) >= 1 << 30 {
+            return Err(error::Unspecified);
+        }
+
+        let memory_len = memory_len(log_n, r, p).ok_or(error::Unspecified)?;
+        if memory_len > max_memory_len {
+            return Err(error::Unspecified);
+        }
+
+        Ok(Self {
+            log_n,
+            r,
+            p,
+            memory_len,
+        })
+    }
+
+    /// log2(N), the base-2 logarithm of the CPU/memory cost parameter.
+    #[inline]
+    pub fn log_n(&self) -> u8 {
+        self.log_n
+    }
+
+    /// The block size parameter `r`.
+    #[inline]
+    pub fn r(&self) -> u32 {
+        self.r
+    }
+
+    /// The parallelization parameter `p`.
+    #[inline]
+    pub fn p(&self) -> u32 {
+        self.p
+    }
+
+    /// The number of bytes of memory a derivation with these parameters
+    /// allocates.
+    #[inline]
+    pub fn memory_len(&self) -> usize {
+        self.memory_len
+    }
+
+    fn block_len(&self) -> usize {
+        BLOCK_LEN_PER_R * polyfill::usize_from_u32(self.r)
+    }
+
+    fn n(&self) -> usize {
+        1 << self.log_n
+    }
+}
+
+// 128 * r * N bytes for V, 128 * r * p bytes for B, and 128 * r bytes for
+// each of the two `scryptBlockMix` work buffers.
+fn memory_len(log_n: u8, r: u32, p: u32) -> Option<usize> {
+    let block_len = polyfill::usize_from_u32(r).checked_mul(BLOCK_LEN_PER_R)?;
+    let n = 1usize.checked_shl(u32::from(log_n))?;
+    let blocks = n.checked_add(polyfill::usize_from_u32(p))?.checked_add(2)?;
+    block_len.checked_mul(blocks)
+}
+
+const BLOCK_LEN_PER_R: usize = 128;
+
+const ONE: NonZeroU32 = match NonZeroU32::new(1) {
+    Some(one) => one,
+    None => unreachable!(),
+};
+
+/// Fills `out` with the key derived using scrypt with the given inputs.
+///
+/// Do not use `derive` as part of verifying a secret; use `verify` instead, to
+/// minimize the effectiveness of timing attacks.
+///
+/// | Parameter   | RFC 7914 Section 6 Term
+/// |-------------|-------------------------------------------
+/// | params      | N, r, p
+/// | salt        | S (salt)
+/// | secret      | P (passphrase)
+/// | out         | DK (derived key)
+/// | out.len()   | dkLen (derived key length)
+///
+/// # Panics
+///
+/// `derive` panics if `out.len()` is larger than (2**32 - 1) * 32, per the
+/// scrypt specification.
+pub fn derive(params: Params, salt: &[u8], secret: &[u8], out: &mut [u8]) {
+    let block_len = params.block_len();
+    let mut b = vec![0u8; block_len * polyfill::usize_from_u32(params.p)];
+    pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, ONE, salt, secret, &mut b);
+
+    let words_per_block = block_len / 4;
+    let mut v = vec![0u32; words_per_block * params.n()];
+    let mut x = vec![0u32; words_per_block];
+    let mut y = vec![0u32; words_per_block];
+    for b_i in b.chunks_mut(block_len) {
+        ro_mix(b_i, params.n(), &mut v, &mut x, &mut y);
+    }
+
+    pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, ONE, &b, secret, out);
+}
+
+/// Verifies that a previously-derived (e.g., using `derive`) scrypt value
+/// matches the scrypt value derived from the other inputs.
+///
+/// The comparison is done in constant time to prevent timing attacks. The
+/// comparison will fail if `previously_derived` is empty (has a length of
+/// zero).
+///
+/// | Parameter                  | RFC 7914 Section 6 Term
+/// |----------------------------|--------------------------------------------
+/// | params                     | N, r, p
+/// | salt                       | S (salt)
+/// | secret                     | P (passphrase)
+/// | `previously_derived`       | DK (derived key)
+/// | `previously_derived.len()` | dkLen (derived key length)
+///
+/// # Panics
+///
+/// `verify` panics if `previously_derived.len()` is larger than
+/// (2**32 - 1) * 32, per the scrypt specification.
+pub fn verify(
+    params: Params,
+    salt: &[u8],
+    secret: &[u8],
+    previously_derived: &[u8],
+) -> Result<(), error::Unspecified> {
+    if previously_derived.is_empty() {
+        return Err(error::Unspecified);
+    }
+    let mut derived: Vec<u8> = vec![0u8; previously_derived.len()];
+    derive(params, salt, secret, &mut derived);
+    constant_time::verify_slices_are_equal(&derived, previously_derived)
+}
+
+// `scryptROMix` from RFC 7914 Section 5. `b` is `128 * r` bytes; `v` is
+// `N` blocks; `x` and `y` are one block each.
+fn ro_mix(b: &mut [u8], n: usize, v: &mut [u32], x: &mut [u32], y: &mut [u32]) {
+    x.iter_mut()
+        .zip(b.chunks_exact(4))
+        .for_each(|(x, b)| *x = u32::from_le_bytes(b.try_into().unwrap()));
+
+    for v_i in v.chunks_exact_mut(x.len()) {
+        v_i.copy_from_slice(x);
+        block_mix(x, y);
+    }
+
+    let n_mask = polyfill::u64_from_usize(n - 1);
+    for _ in 0..n {
+        let j = integerify(x) & n_mask;
+        let j = usize::try_from(j).unwrap();
+        let v_j = &v[(j * x.len())..][..x.len()];
+        x.iter_mut().zip(v_j).for_each(|(x, v)| *x ^= *v);
+        block_mix(x, y);
+    }
+
+    b.chunks_exact_mut(4)
+        .zip(x.iter())
+        .for_each(|(b, x)| b.copy_from_slice(&x.to_le_bytes()));
+}
+
+// The first 64 bits of the last 64-byte block, interpreted as a
+// little-endian integer.
+fn integerify(b: &[u32]) -> u64 {
+    let last = &b[(b.len() - SALSA_WORDS)..];
+    u64::from(last[0]) | (u64::from(last[1]) << 32)
+}
+
+// `scryptBlockMix` from RFC 7914 Section 4. `b` and `y` are `2 * r` Salsa20
+// blocks each; the output is written back to `b`.
+fn block_mix(b: &mut [u32], y: &mut [u32]) {
+    let two_r = b.len() / SALSA_WORDS;
+
+    let mut x = [0u32; SALSA_WORDS];
+    x.copy_from_slice(&b[((two_r - 1) * SALSA_WORDS)..]);
+
+    for (i, b_i) in b.chunks_exact(SALSA_WORDS).enumerate() {
+        x.iter_mut().zip(b_i).for_each(|(x, b)| *x ^= *b);
+        salsa20_8(&mut x);
+
+        // Even-numbered blocks go to the first half of the output and
+        // odd-numbered blocks go to the second half.
+        let dst = (i / 2) + ((i % 2) * (two_r / 2));
+        y[(dst * SALSA_WORDS)..][..SALSA_WORDS].copy_from_slice(&x);
+    }
+
+    b.copy_from_slice(y);
+}
+
+const SALSA_WORDS: usize = salsa20::WORDS;
+
+// The Salsa20/8 core from RFC 7914 Section 3.
+fn salsa20_8(b: &mut [u32; SALSA_WORDS]) {
+    salsa20::core(b, 8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 7914 Section 8.
+    #[test]
+    fn test_salsa20_8() {
+        let input: [u8; 64] = [
+            0x7e, 0x87, 0x9a, 0x21, 0x4f, 0x3e, 0xc9, 0x86, 0x7c, 0xa9, 0x40, 0xe6, 0x41, 0x71,
+            0x8f, 0x26, 0xba, 0xee, 0x55, 0x5b, 0x8c, 0x61, 0xc1, 0xb5, 0x0d, 0xf8, 0x46, 0x11,
+            0x6d, 0xcd, 0x3b, 0x1d, 0xee, 0x24, 0xf3, 0x19, 0xdf, 0x9b, 0x3d, 0x85, 0x14, 0x12,
+            0x1e, 0x4b, 0x5a, 0xc5, 0xaa, 0x32, 0x76, 0x02, 0x1d, 0x29, 0x09, 0xc7, 0x48, 0x29,
+            0xed, 0xeb, 0xc6, 0x8d, 0xb8, 0xb8, 0xc2, 0x5e,
+        ];
+        let expected: [u8; 64] = [
+            0xa4, 0x1f, 0x85, 0x9c, 0x66, 0x08, 0xcc, 0x99, 0x3b, 0x81, 0xca, 0xcb, 0x02, 0x0c,
+            0xef, 0x05, 0x04, 0x4b, 0x21, 0x81, 0xa2, 0xfd, 0x33, 0x7d, 0xfd, 0x7b, 0x1c, 0x63,
+            0x96, 0x68, 0x2f, 0x29, 0xb4, 0x39, 0x31, 0x68, 0xe3, 0xc9, 0xe6, 0xbc, 0xfe, 0x6b,
+            0xc5, 0xb7, 0xa0, 0x6d, 0x96, 0xba, 0xe4, 0x24, 0xcc, 0x10, 0x2c, 0x91, 0x74, 0x5c,
+            0x24, 0xad, 0x67, 0x3d, 0xc7, 0x61, 0x8f, 0x81,
+        ];
+
+        let mut x = [0u32; SALSA_WORDS];
+        x.iter_mut()
+            .zip(input.chunks_exact(4))
+            .for_each(|(x, b)| *x = u32::from_le_bytes(b.try_into().unwrap()));
+        salsa20_8(&mut x);
+
+        let mut actual = [0u8; 64];
+        actual
+            .chunks_exact_mut(4)
+            .zip(x.iter())
+            .for_each(|(b, x)| b.copy_from_slice(&x.to_le_bytes()));
+        assert_eq!(&actual[..], &expected[..]);
+    }
+
+    #[test]
+    fn test_params_memory_len() {
+        let params = Params::new(14, 8, 1, usize::MAX).unwrap();
+        assert_eq!(params.memory_len(), 1024 * ((1 << 14) + 1 + 2));
+
+        // Exactly at the limit is OK; one byte less is not.
+        assert!(Params::new(14, 8, 1, params.memory_len()).is_ok());
+        assert!(Params::new(14, 8, 1, params.memory_len() - 1).is_err());
+    }
+}
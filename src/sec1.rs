@@ -0,0 +1,145 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! The SEC 1 `ECPrivateKey` structure is specified in [RFC 5915].
+//!
+//! This is the format that OpenSSL labels `EC PRIVATE KEY`; PKCS#8 documents
+//! for ECC keys also contain an `ECPrivateKey`.
+//!
+//! [RFC 5915]: https://tools.ietf.org/html/rfc5915
+
+use crate::{ec, error, io::der};
+
+/// Parses an `ECPrivateKey`, returning the private key and the public key, if
+/// the public key is present.
+///
+/// `curve_oid` must be the encoded OID, including the tag and length, that
+/// identifies the curve. If the `parameters` field is present then it must
+/// name that curve.
+pub(crate) fn unwrap_key<'a>(
+    curve_oid: untrusted::Input,
+    input: untrusted::Input<'a>,
+) -> Result<(untrusted::Input<'a>, Option<untrusted::Input<'a>>), error::KeyRejected> {
+    input.read_all(error::KeyRejected::invalid_encoding(), |input| {
+        // https://tools.ietf.org/html/rfc5915#section-3
+        der::nested(
+            input,
+            der::Tag::Sequence,
+            error::KeyRejected::invalid_encoding(),
+            |input| unwrap_key_(curve_oid, input),
+        )
+    })
+}
+
+fn unwrap_key_<'a>(
+    curve_oid: untrusted::Input,
+    input: &mut untrusted::Reader<'a>,
+) -> Result<(untrusted::Input<'a>, Option<untrusted::Input<'a>>), error::KeyRejected> {
+    let version = der::small_nonnegative_integer(input)
+        .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
+    if version != 1 {
+        return Err(error::KeyRejected::version_not_supported());
+    }
+
+    let private_key = der::expect_tag_and_get_value(input, der::Tag::OctetString)
+        .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
+
+    // [0] parameters (optional).
+    if input.peek(u8::from(der::Tag::ContextSpecificConstructed0)) {
+        let actual_alg_id =
+            der::expect_tag_and_get_value(input, der::Tag::ContextSpecificConstructed0)
+                .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
+        if actual_alg_id.as_slice_less_safe() != curve_oid.as_slice_less_safe() {
+            return Err(error::KeyRejected::wrong_algorithm());
+        }
+    }
+
+    // [1] publicKey (optional).
+    let public_key = if input.at_end() {
+        None
+    } else {
+        let public_key = der::nested(
+            input,
+            der::Tag::ContextSpecificConstructed1,
+            error::Unspecified,
+            der::bit_string_with_no_unused_bits,
+        )
+        .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
+        Some(public_key)
+    };
+
+    Ok((private_key, public_key))
+}
+
+/// A generated SEC 1 `ECPrivateKey` document.
+pub struct Document {
+    bytes: [u8; ec::SEC1_DOCUMENT_MAX_LEN],
+    len: usize,
+}
+
+impl AsRef<[u8]> for Document {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+/// Formats an `ECPrivateKey` with the `parameters` field set to `curve_oid`
+/// and with the public key present.
+///
+/// `curve_oid` must be the encoded OID, including the tag and length, and it
+/// must be short enough that every field except the outermost `SEQUENCE` has a
+/// single-byte length.
+pub(crate) fn wrap_key(curve_oid: &[u8], private_key: &[u8], public_key: &[u8]) -> Document {
+    const VERSION_1: &[u8] = &[der::Tag::Integer as u8, 1, 1];
+
+    let public_key_len = 1 + public_key.len();
+    let contents_len = VERSION_1.len()
+        + (2 + private_key.len())
+        + (2 + curve_oid.len())
+        + (2 + 2 + public_key_len);
+
+    let mut result = Document {
+        bytes: [0; ec::SEC1_DOCUMENT_MAX_LEN],
+        len: 0,
+    };
+    let mut write = |bytes: &[u8]| {
+        result.bytes[result.len..][..bytes.len()].copy_from_slice(bytes);
+        result.len += bytes.len();
+    };
+
+    if contents_len < 0x80 {
+        write(&[der::Tag::Sequence.into(), contents_len as u8]);
+    } else {
+        write(&[der::Tag::Sequence.into(), 0x81, contents_len as u8]);
+    }
+    write(VERSION_1);
+    write(&[der::Tag::OctetString.into(), private_key.len() as u8]);
+    write(private_key);
+    write(&[
+        der::Tag::ContextSpecificConstructed0.into(),
+        curve_oid.len() as u8,
+    ]);
+    write(curve_oid);
+    write(&[
+        der::Tag::ContextSpecificConstructed1.into(),
+        (2 + public_key_len) as u8,
+        der::Tag::BitString.into(),
+        public_key_len as u8,
+        0, // No unused bits.
+    ]);
+    write(public_key);
+
+    result
+}
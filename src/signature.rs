@@ -43,7 +43,8 @@
 //!
 //! The public key is encoding in uncompressed form using the
 //! Octet-String-to-Elliptic-Curve-Point algorithm in
-//! [SEC 1: Elliptic Curve Cryptography, Version 2.0].
+//! [SEC 1: Elliptic Curve Cryptography, Version 2.0]. Public keys in
+//! compressed form are also accepted for verification.
 //!
 //! During verification, the public key is validated using the ECC Partial
 //! Public-Key Validation Routine from Section 5.6.2.3.3 of
@@ -62,7 +63,8 @@
 //!
 //! The public key is encoding in uncompressed form using the
 //! Octet-String-to-Elliptic-Curve-Point algorithm in
-//! [SEC 1: Elliptic Curve Cryptography, Version 2.0].
+//! [SEC 1: Elliptic Curve Cryptography, Version 2.0]. Public keys in
+//! compressed form are also accepted for verification.
 //!
 //! During verification, the public key is validated using the ECC Partial
 //! Public-Key Validation Routine from Section 5.6.2.3.3 of
@@ -0,0 +1,66 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Signing for JWSs and COSE_Sign1 messages, which support the same kinds of
+//! key pairs.
+
+use crate::{error, rand, rsa, signature};
+use alloc::{vec, vec::Vec};
+
+/// A key pair for signing JWSs and COSE_Sign1 messages.
+#[derive(Clone, Copy, Debug)]
+pub enum SigningKey<'a> {
+    /// An RSA key pair, for `PS256`, and for `RS256` in JWSs.
+    Rsa(&'a rsa::KeyPair),
+
+    /// An ECDSA key pair, for `ES256` and `ES384`. The key pair must have been
+    /// constructed with the `*_FIXED_SIGNING` algorithm for the curve.
+    Ecdsa(&'a signature::EcdsaKeyPair),
+
+    /// An Ed25519 key pair, for `EdDSA`.
+    Ed25519(&'a signature::Ed25519KeyPair),
+}
+
+/// How a JWS or COSE signature algorithm signs.
+pub(crate) enum SigningAlgorithm {
+    Rsa(&'static dyn signature::RsaEncoding),
+    Ecdsa(&'static signature::EcdsaSigningAlgorithm),
+    Ed25519,
+}
+
+impl SigningAlgorithm {
+    /// Signs `message` with `key`, failing if `key` can't be used with the
+    /// algorithm.
+    pub(crate) fn sign(
+        &self,
+        key: SigningKey,
+        message: &[u8],
+        rng: &dyn rand::SecureRandom,
+    ) -> Result<Vec<u8>, error::Unspecified> {
+        match (self, key) {
+            (Self::Rsa(encoding), SigningKey::Rsa(key_pair)) => {
+                let mut signature = vec![0; key_pair.public().modulus_len()];
+                key_pair.sign(*encoding, rng, message, &mut signature)?;
+                Ok(signature)
+            }
+            (Self::Ecdsa(alg), SigningKey::Ecdsa(key_pair)) if key_pair.algorithm() == *alg => {
+                Ok(key_pair.sign(rng, message)?.as_ref().to_vec())
+            }
+            (Self::Ed25519, SigningKey::Ed25519(key_pair)) => {
+                Ok(key_pair.sign(message).as_ref().to_vec())
+            }
+            _ => Err(error::Unspecified),
+        }
+    }
+}
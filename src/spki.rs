@@ -0,0 +1,242 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! SubjectPublicKeyInfo is specified in [RFC 5280 Section 4.1].
+//!
+//! [RFC 5280 Section 4.1]: https://tools.ietf.org/html/rfc5280#section-4.1
+
+use crate::{
+    cpu,
+    ec::{self, curve25519::ed25519, suite_b::ecdsa::signing},
+    error,
+    io::der,
+};
+
+#[cfg(feature = "alloc")]
+use crate::{
+    debug,
+    io::{der_writer, writer::write_copy},
+};
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+
+/// The type of key in a `SubjectPublicKeyInfo`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum KeyType {
+    /// An RSA key, identified by `rsaEncryption` ([RFC 3279 Section 2.3.1]).
+    ///
+    /// [RFC 3279 Section 2.3.1]: https://tools.ietf.org/html/rfc3279#section-2.3.1
+    #[cfg(feature = "alloc")]
+    Rsa,
+
+    /// A P-256 key, identified by `id-ecPublicKey` with the `secp256r1` named
+    /// curve ([RFC 5480 Section 2.1.1]).
+    ///
+    /// [RFC 5480 Section 2.1.1]: https://tools.ietf.org/html/rfc5480#section-2.1.1
+    EcP256,
+
+    /// A P-384 key, identified by `id-ecPublicKey` with the `secp384r1` named
+    /// curve ([RFC 5480 Section 2.1.1]).
+    ///
+    /// [RFC 5480 Section 2.1.1]: https://tools.ietf.org/html/rfc5480#section-2.1.1
+    EcP384,
+
+    /// An Ed25519 key, identified by `id-Ed25519` ([RFC 8410 Section 3]).
+    ///
+    /// [RFC 8410 Section 3]: https://tools.ietf.org/html/rfc8410#section-3
+    Ed25519,
+
+    /// An X25519 key, identified by `id-X25519` ([RFC 8410 Section 3]).
+    ///
+    /// [RFC 8410 Section 3]: https://tools.ietf.org/html/rfc8410#section-3
+    #[cfg(not(target_arch = "wasm32"))]
+    X25519,
+}
+
+impl KeyType {
+    // The value of the `AlgorithmIdentifier`, not including the outermost
+    // `SEQUENCE` tag and length.
+    fn alg_id_value(self) -> untrusted::Input<'static> {
+        match self {
+            #[cfg(feature = "alloc")]
+            Self::Rsa => untrusted::Input::from(crate::rsa::RSA_ENCRYPTION),
+            Self::EcP256 => signing::EC_PUBLIC_KEY_P256_PKCS8_V1_TEMPLATE.alg_id_value(),
+            Self::EcP384 => signing::EC_PUBLIC_KEY_P384_PKCS8_V1_TEMPLATE.alg_id_value(),
+            Self::Ed25519 => ed25519::signing::PKCS8_TEMPLATE.alg_id_value(),
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::X25519 => untrusted::Input::from(X25519_ALG_ID),
+        }
+    }
+
+    // The key type for public keys on `curve`.
+    #[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+    pub(crate) fn for_curve(curve: &ec::Curve) -> Self {
+        match curve.id {
+            ec::CurveID::P256 => Self::EcP256,
+            ec::CurveID::P384 => Self::EcP384,
+            #[cfg(not(target_arch = "wasm32"))]
+            ec::CurveID::Curve25519 => Self::X25519,
+        }
+    }
+}
+
+// The OID 1.3.101.110.
+#[cfg(not(target_arch = "wasm32"))]
+const X25519_ALG_ID: &[u8] = &[0x06, 0x03, 0x2b, 0x65, 0x6e];
+
+const KEY_TYPES: &[KeyType] = &[
+    #[cfg(feature = "alloc")]
+    KeyType::Rsa,
+    KeyType::EcP256,
+    KeyType::EcP384,
+    KeyType::Ed25519,
+    #[cfg(not(target_arch = "wasm32"))]
+    KeyType::X25519,
+];
+
+/// A parsed and validated SubjectPublicKeyInfo.
+#[derive(Clone, Copy, Debug)]
+pub struct SubjectPublicKeyInfo<'a> {
+    key_type: KeyType,
+    public_key: &'a [u8],
+}
+
+impl<'a> SubjectPublicKeyInfo<'a> {
+    /// Parses a DER-encoded SubjectPublicKeyInfo and validates the public key
+    /// it contains.
+    ///
+    /// The algorithm identifier must exactly match the DER encoding of one of
+    /// the `KeyType`s; in particular, EC keys must use a named curve, and the
+    /// NULL parameters of RSA keys must be present.
+    ///
+    /// EC public keys must be uncompressed points that are on the curve. RSA
+    /// public keys must be acceptable to at least one of the RSA verification
+    /// algorithms, i.e. at least 1024 bits with a public exponent of at least
+    /// 3. Ed25519 public keys must be 32 bytes.
+    pub fn from_der(spki: &'a [u8]) -> Result<Self, error::KeyRejected> {
+        let (alg_id, public_key) = untrusted::Input::from(spki).read_all(
+            error::KeyRejected::invalid_encoding(),
+            |input| {
+                der::nested(
+                    input,
+                    der::Tag::Sequence,
+                    error::KeyRejected::invalid_encoding(),
+                    |input| {
+                        let alg_id = der::expect_tag_and_get_value(input, der::Tag::Sequence)
+                            .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
+                        let public_key = der::bit_string_with_no_unused_bits(input)
+                            .map_err(|error::Unspecified| error::KeyRejected::invalid_encoding())?;
+                        Ok((alg_id, public_key))
+                    },
+                )
+            },
+        )?;
+
+        let key_type = *KEY_TYPES
+            .iter()
+            .find(|key_type| {
+                key_type.alg_id_value().as_slice_less_safe() == alg_id.as_slice_less_safe()
+            })
+            .ok_or_else(error::KeyRejected::wrong_algorithm)?;
+
+        // The EC public key validation requires the CPU features to have been
+        // detected.
+        let _ = cpu::features();
+        match key_type {
+            #[cfg(feature = "alloc")]
+            KeyType::Rsa => {
+                let _ = crate::rsa::PublicKey::from_der(public_key, cpu::features())?;
+            }
+            KeyType::EcP256 => check_ec_public_key(&ec::suite_b::curve::P256, public_key)?,
+            KeyType::EcP384 => check_ec_public_key(&ec::suite_b::curve::P384, public_key)?,
+            KeyType::Ed25519 => {
+                if public_key.len() != ed25519::ED25519_PUBLIC_KEY_LEN {
+                    return Err(error::KeyRejected::invalid_encoding());
+                }
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            KeyType::X25519 => {
+                if public_key.len() != ec::curve25519::x25519::PUBLIC_KEY_LEN {
+                    return Err(error::KeyRejected::invalid_encoding());
+                }
+            }
+        }
+
+        Ok(Self {
+            key_type,
+            public_key: public_key.as_slice_less_safe(),
+        })
+    }
+
+    /// The type of the key.
+    #[inline]
+    pub fn key_type(&self) -> KeyType {
+        self.key_type
+    }
+
+    /// The public key, in the form that `signature::UnparsedPublicKey`
+    /// expects for verification algorithms of the key's type.
+    ///
+    /// That is a DER-encoded `RSAPublicKey` for RSA keys, an uncompressed
+    /// point for EC keys, and the 32-byte public key for Ed25519 and X25519
+    /// keys. X25519 keys are used with `agreement::UnparsedPublicKey` instead.
+    #[inline]
+    pub fn public_key(&self) -> &'a [u8] {
+        self.public_key
+    }
+}
+
+fn check_ec_public_key(
+    curve: &ec::Curve,
+    public_key: untrusted::Input,
+) -> Result<(), error::KeyRejected> {
+    ec::suite_b::check_public_key(curve, public_key)
+        .map_err(|error::Unspecified| error::KeyRejected::invalid_component())
+}
+
+/// A generated SubjectPublicKeyInfo document.
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+pub struct Document(Box<[u8]>);
+
+#[cfg(feature = "alloc")]
+impl AsRef<[u8]> for Document {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl core::fmt::Debug for Document {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_tuple("Document")
+            .field(&debug::HexStr(self.as_ref()))
+            .finish()
+    }
+}
+
+/// Wraps `public_key`, which must be in the form that `public_key()` returns
+/// for `key_type`, in a SubjectPublicKeyInfo.
+#[cfg(feature = "alloc")]
+pub(crate) fn wrap_key(key_type: KeyType, public_key: &[u8]) -> Document {
+    let alg_id = key_type.alg_id_value();
+    Document(der_writer::write_all(der::Tag::Sequence, &|output| {
+        der_writer::write_tlv(output, der::Tag::Sequence, |output| {
+            write_copy(output, alg_id)
+        });
+        der_writer::write_bit_string_with_no_unused_bits(output, public_key);
+    }))
+}
@@ -0,0 +1,172 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! Single-Step Key Derivation Function (SSKDF), a.k.a. the One-Step KDF or the
+//! Concatenation KDF.
+//!
+//! SSKDF is specified in [NIST SP 800-56Cr2] Section 4. It derives keying
+//! material from a shared secret `Z`, usually the output of a key agreement
+//! such as `agreement::agree_ephemeral`. Each block of output is computed as
+//!
+//! ```text
+//! K(i) = H([i]_32 || Z || FixedInfo)
+//! ```
+//!
+//! where `[i]_32` is the 32-bit big-endian block counter, starting at 1, and
+//! `H` is either a digest algorithm (`derive_with_digest`) or HMAC keyed with
+//! a salt (`derive_with_hmac`). The digest variant is the one used by, e.g.,
+//! JOSE's ECDH-ES ([RFC 7518 Section 4.6]).
+//!
+//! The output length is given by an `hkdf::KeyType`, so the output can be used
+//! to construct an `aead::UnboundKey` or `hmac::Key` directly.
+//!
+//! [NIST SP 800-56Cr2]: https://doi.org/10.6028/NIST.SP.800-56Cr2
+//! [RFC 7518 Section 4.6]: https://tools.ietf.org/html/rfc7518#section-4.6
+
+use crate::{digest, error, hkdf::KeyType, hmac, polyfill};
+
+/// The SSKDF operation with a digest algorithm as the auxiliary function `H`
+/// (SP 800-56Cr2 Section 4.1, Option 1).
+///
+/// The concatenation of the elements of `fixed_info` is used as `FixedInfo`.
+///
+/// Fails if (and only if) `len` is too large.
+pub fn derive_with_digest<'a, L: KeyType>(
+    algorithm: &'static digest::Algorithm,
+    z: &'a [u8],
+    fixed_info: &'a [&'a [u8]],
+    len: L,
+) -> Result<Okm<'a, L>, error::Unspecified> {
+    Okm::new(Prf::Digest(algorithm), z, fixed_info, len)
+}
+
+/// The SSKDF operation with HMAC as the auxiliary function `H`
+/// (SP 800-56Cr2 Section 4.1, Option 2). `salt` is the HMAC key.
+///
+/// The concatenation of the elements of `fixed_info` is used as `FixedInfo`.
+///
+/// Fails if (and only if) `len` is too large.
+pub fn derive_with_hmac<'a, L: KeyType>(
+    salt: &'a hmac::Key,
+    z: &'a [u8],
+    fixed_info: &'a [&'a [u8]],
+    len: L,
+) -> Result<Okm<'a, L>, error::Unspecified> {
+    Okm::new(Prf::Hmac(salt), z, fixed_info, len)
+}
+
+#[derive(Debug)]
+enum Prf<'a> {
+    Digest(&'static digest::Algorithm),
+    Hmac(&'a hmac::Key),
+}
+
+impl Prf<'_> {
+    fn output_len(&self) -> usize {
+        match self {
+            Self::Digest(algorithm) => algorithm.output_len(),
+            Self::Hmac(key) => key.algorithm().digest_algorithm().output_len(),
+        }
+    }
+}
+
+/// An SSKDF OKM (Output Keying Material)
+///
+/// Intentionally not `Clone` or `Copy` as an OKM is generally only safe to
+/// use once.
+pub struct Okm<'a, L: KeyType> {
+    prf: Prf<'a>,
+    z: &'a [u8],
+    fixed_info: &'a [&'a [u8]],
+    len: L,
+    len_cached: usize,
+}
+
+impl<L: KeyType> core::fmt::Debug for Okm<'_, L> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        // `z` is secret, so it is intentionally omitted.
+        f.debug_struct("Okm")
+            .field("prf", &self.prf)
+            .field("len", &self.len_cached)
+            .finish()
+    }
+}
+
+impl<'a, L: KeyType> Okm<'a, L> {
+    fn new(
+        prf: Prf<'a>,
+        z: &'a [u8],
+        fixed_info: &'a [&'a [u8]],
+        len: L,
+    ) -> Result<Self, error::Unspecified> {
+        let len_cached = len.len();
+
+        // The counter is 32 bits and must not wrap.
+        let max_len = u64::from(u32::MAX) * polyfill::u64_from_usize(prf.output_len());
+        if polyfill::u64_from_usize(len_cached) > max_len {
+            return Err(error::Unspecified);
+        }
+
+        Ok(Self {
+            prf,
+            z,
+            fixed_info,
+            len,
+            len_cached,
+        })
+    }
+}
+
+impl<L: KeyType> Okm<'_, L> {
+    /// The `KeyType` given to `derive_with_digest()` or `derive_with_hmac()`.
+    #[inline]
+    pub fn len(&self) -> &L {
+        &self.len
+    }
+
+    /// Fills `out` with the output of the SSKDF operation for the given
+    /// inputs.
+    ///
+    /// Fails if (and only if) `out.len()` is not the length given to
+    /// `derive_with_digest()` or `derive_with_hmac()`.
+    pub fn fill(self, out: &mut [u8]) -> Result<(), error::Unspecified> {
+        if out.len() != self.len_cached {
+            return Err(error::Unspecified);
+        }
+
+        let mut counter: u32 = 0;
+        for chunk in out.chunks_mut(self.prf.output_len()) {
+            counter += 1;
+            let counter = counter.to_be_bytes();
+            match self.prf {
+                Prf::Digest(algorithm) => {
+                    let mut ctx = digest::Context::new(algorithm);
+                    ctx.update(&counter);
+                    ctx.update(self.z);
+                    self.fixed_info.iter().for_each(|info| ctx.update(info));
+                    chunk.copy_from_slice(&ctx.finish().as_ref()[..chunk.len()]);
+                }
+                Prf::Hmac(salt) => {
+                    let mut ctx = hmac::Context::with_key(salt);
+                    ctx.update(&counter);
+                    ctx.update(self.z);
+                    self.fixed_info.iter().for_each(|info| ctx.update(info));
+                    chunk.copy_from_slice(&ctx.sign().as_ref()[..chunk.len()]);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,587 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+//! The TLS 1.3 key schedule.
+//!
+//! The key schedule is specified in [RFC 8446 Section 7.1]. It is a sequence
+//! of HKDF-Extract operations, each producing a secret from which the
+//! traffic secrets for one phase of the handshake are derived:
+//!
+//! ```text
+//! PSK -> EarlySecret -(ECDHE)-> HandshakeSecret -> MasterSecret
+//! ```
+//!
+//! `EarlySecret`, `HandshakeSecret`, and `MasterSecret` model these phases;
+//! each derives the secrets of its phase and is consumed to move on to the
+//! next phase. The derived secrets are `Secret`s, from which traffic keys,
+//! IVs, Finished values, and exported keying material are derived.
+//!
+//! The transcript hash is given as a `digest::Context` into which the
+//! handshake messages have been fed so far; it is not consumed.
+//!
+//! QUIC ([RFC 9001]) uses the same key schedule; `Secret` can also derive
+//! QUIC header protection keys.
+//!
+//! [RFC 8446 Section 7.1]: https://tools.ietf.org/html/rfc8446#section-7.1
+//! [RFC 9001]: https://tools.ietf.org/html/rfc9001
+
+use crate::{aead, digest, error, hkdf, hmac};
+
+/// The `HKDF-Expand-Label` function from [RFC 8446 Section 7.1].
+///
+/// `label` must not include the "tls13 " prefix; it is added automatically.
+///
+/// Fails if `len` is longer than HKDF allows or than fits in a `uint16`, or
+/// if `label` or `context` is longer than 255 bytes when encoded.
+///
+/// [RFC 8446 Section 7.1]: https://tools.ietf.org/html/rfc8446#section-7.1
+pub fn expand_label<T, L>(
+    secret: &hkdf::Prk,
+    label: &[u8],
+    context: &[u8],
+    len: L,
+) -> Result<T, error::Unspecified>
+where
+    L: hkdf::KeyType,
+    T: for<'a> From<hkdf::Okm<'a, L>>,
+{
+    let hkdf_label = HkdfLabel::new(label, context, len.len())?;
+    let info = hkdf_label.as_info();
+    let okm = secret.expand(&info, len)?;
+    Ok(T::from(okm))
+}
+
+const LABEL_PREFIX: &[u8] = b"tls13 ";
+
+// struct {
+//     uint16 length = Length;
+//     opaque label<7..255> = "tls13 " + Label;
+//     opaque context<0..255> = Context;
+// } HkdfLabel;
+struct HkdfLabel<'a> {
+    length: [u8; 2],
+    label_len: [u8; 1],
+    label: &'a [u8],
+    context_len: [u8; 1],
+    context: &'a [u8],
+}
+
+impl<'a> HkdfLabel<'a> {
+    fn new(label: &'a [u8], context: &'a [u8], len: usize) -> Result<Self, error::Unspecified> {
+        let length = u16::try_from(len).map_err(|_| error::Unspecified)?;
+        let label_len =
+            u8::try_from(LABEL_PREFIX.len() + label.len()).map_err(|_| error::Unspecified)?;
+        let context_len = u8::try_from(context.len()).map_err(|_| error::Unspecified)?;
+        Ok(Self {
+            length: length.to_be_bytes(),
+            label_len: [label_len],
+            label,
+            context_len: [context_len],
+            context,
+        })
+    }
+
+    fn as_info(&self) -> [&[u8]; 6] {
+        [
+            &self.length,
+            &self.label_len,
+            LABEL_PREFIX,
+            self.label,
+            &self.context_len,
+            self.context,
+        ]
+    }
+}
+
+/// A secret derived by the TLS 1.3 key schedule, e.g. a traffic secret.
+#[derive(Clone, Debug)]
+pub struct Secret {
+    algorithm: hkdf::Algorithm,
+    prk: hkdf::Prk,
+}
+
+impl Secret {
+    /// The HKDF algorithm, which determines the transcript hash algorithm.
+    #[inline]
+    pub fn algorithm(&self) -> hkdf::Algorithm {
+        self.algorithm
+    }
+
+    /// The secret as an HKDF PRK, for use with `expand_label`.
+    #[inline]
+    pub fn prk(&self) -> &hkdf::Prk {
+        &self.prk
+    }
+
+    /// Derives the traffic key for `algorithm` from this traffic secret, as
+    /// `HKDF-Expand-Label(Secret, "key", "", key_length)`.
+    pub fn traffic_key(&self, algorithm: &'static aead::Algorithm) -> aead::UnboundKey {
+        expand_label(&self.prk, b"key", &[], algorithm).unwrap()
+    }
+
+    /// Derives the traffic IV from this traffic secret, as
+    /// `HKDF-Expand-Label(Secret, "iv", "", iv_length)`.
+    pub fn traffic_iv(&self) -> Iv {
+        Iv::derive(&self.prk, b"iv")
+    }
+
+    /// Derives the QUIC header protection key for `algorithm` from this
+    /// traffic secret, as `HKDF-Expand-Label(Secret, "quic hp", "",
+    /// key_length)` ([RFC 9001 Section 5.4]).
+    ///
+    /// [RFC 9001 Section 5.4]: https://tools.ietf.org/html/rfc9001#section-5.4
+    pub fn header_protection_key(
+        &self,
+        algorithm: &'static aead::quic::Algorithm,
+    ) -> aead::quic::HeaderProtectionKey {
+        expand_label(&self.prk, b"quic hp", &[], algorithm).unwrap()
+    }
+
+    /// Derives the next application traffic secret from this one for a key
+    /// update ([RFC 8446 Section 7.2]).
+    ///
+    /// [RFC 8446 Section 7.2]: https://tools.ietf.org/html/rfc8446#section-7.2
+    pub fn next_application_traffic_secret(&self) -> Self {
+        self.expand_label_secret(b"traffic upd", &[])
+    }
+
+    /// Computes the `verify_data` of a Finished message, using this secret as
+    /// the base key ([RFC 8446 Section 4.4.4]). The base key is the
+    /// handshake traffic secret of the sender of the Finished message, or the
+    /// binder key when computing a PSK binder.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `transcript`'s digest algorithm isn't the key schedule's.
+    ///
+    /// [RFC 8446 Section 4.4.4]: https://tools.ietf.org/html/rfc8446#section-4.4.4
+    pub fn finished_verify_data(&self, transcript: &digest::Context) -> hmac::Tag {
+        let transcript_hash = self.transcript_hash(transcript);
+        hmac::sign(&self.finished_key(), transcript_hash.as_ref())
+    }
+
+    /// Verifies the `verify_data` of a Finished message (or a PSK binder) in
+    /// constant time. See `finished_verify_data`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `transcript`'s digest algorithm isn't the key schedule's.
+    pub fn verify_finished(
+        &self,
+        transcript: &digest::Context,
+        verify_data: &[u8],
+    ) -> Result<(), error::Unspecified> {
+        let transcript_hash = self.transcript_hash(transcript);
+        hmac::verify(&self.finished_key(), transcript_hash.as_ref(), verify_data)
+    }
+
+    /// Fills `out` with the TLS exporter output for `label` and
+    /// `context_value`, using this secret as the (early) exporter master
+    /// secret ([RFC 8446 Section 7.5]).
+    ///
+    /// Fails if `label` or `out` is too long.
+    ///
+    /// [RFC 8446 Section 7.5]: https://tools.ietf.org/html/rfc8446#section-7.5
+    pub fn export_keying_material(
+        &self,
+        label: &[u8],
+        context_value: &[u8],
+        out: &mut [u8],
+    ) -> Result<(), error::Unspecified> {
+        let empty_hash = digest::digest(self.digest_algorithm(), &[]);
+        let hkdf_label = HkdfLabel::new(label, empty_hash.as_ref(), self.hash_len())?;
+        let secret: hkdf::Prk = self
+            .prk
+            .expand(&hkdf_label.as_info(), self.algorithm)?
+            .into();
+
+        let context_hash = digest::digest(self.digest_algorithm(), context_value);
+        let hkdf_label = HkdfLabel::new(b"exporter", context_hash.as_ref(), out.len())?;
+        secret
+            .expand(&hkdf_label.as_info(), OutLen(out.len()))?
+            .fill(out)
+    }
+
+    fn finished_key(&self) -> hmac::Key {
+        expand_label(&self.prk, b"finished", &[], self.algorithm.hmac_algorithm()).unwrap()
+    }
+
+    // `Derive-Secret(Secret, Label, Messages)`, where `transcript_hash` is
+    // `Transcript-Hash(Messages)`.
+    fn derive_secret(&self, label: &[u8], transcript_hash: &[u8]) -> Self {
+        self.expand_label_secret(label, transcript_hash)
+    }
+
+    fn derive_secret_for_transcript(&self, label: &[u8], transcript: &digest::Context) -> Self {
+        let transcript_hash = self.transcript_hash(transcript);
+        self.derive_secret(label, transcript_hash.as_ref())
+    }
+
+    // `Derive-Secret(Secret, Label, "")`.
+    fn derive_secret_for_empty_transcript(&self, label: &[u8]) -> Self {
+        let empty_hash = digest::digest(self.digest_algorithm(), &[]);
+        self.derive_secret(label, empty_hash.as_ref())
+    }
+
+    fn expand_label_secret(&self, label: &[u8], context: &[u8]) -> Self {
+        Self {
+            algorithm: self.algorithm,
+            prk: expand_label(&self.prk, label, context, self.algorithm).unwrap(),
+        }
+    }
+
+    fn expand_label_fill(
+        &self,
+        label: &[u8],
+        context: &[u8],
+        out: &mut [u8],
+    ) -> Result<(), error::Unspecified> {
+        let hkdf_label = HkdfLabel::new(label, context, out.len())?;
+        self.prk
+            .expand(&hkdf_label.as_info(), OutLen(out.len()))?
+            .fill(out)
+    }
+
+    // The salt for the next HKDF-Extract in the key schedule.
+    fn derived_salt(&self) -> hkdf::Salt {
+        let empty_hash = digest::digest(self.digest_algorithm(), &[]);
+        expand_label(&self.prk, b"derived", empty_hash.as_ref(), self.algorithm).unwrap()
+    }
+
+    fn transcript_hash(&self, transcript: &digest::Context) -> digest::Digest {
+        assert_eq!(transcript.algorithm(), self.digest_algorithm());
+        transcript.clone().finish()
+    }
+
+    fn digest_algorithm(&self) -> &'static digest::Algorithm {
+        self.algorithm.hmac_algorithm().digest_algorithm()
+    }
+
+    fn hash_len(&self) -> usize {
+        self.digest_algorithm().output_len()
+    }
+}
+
+// HKDF-Extract(salt, IKM), where a missing IKM is a string of `Hash.length`
+// zeros.
+fn extract(algorithm: hkdf::Algorithm, salt: &hkdf::Salt, ikm: Option<&[u8]>) -> Secret {
+    let zeros = [0u8; digest::MAX_OUTPUT_LEN];
+    let hash_len = algorithm.hmac_algorithm().digest_algorithm().output_len();
+    let ikm = ikm.unwrap_or(&zeros[..hash_len]);
+    Secret {
+        algorithm,
+        prk: salt.extract(ikm),
+    }
+}
+
+struct OutLen(usize);
+
+impl hkdf::KeyType for OutLen {
+    fn len(&self) -> usize {
+        self.0
+    }
+}
+
+/// A TLS 1.3 traffic IV.
+///
+/// The per-record nonce is constructed from the IV and the record sequence
+/// number as described in [RFC 8446 Section 5.3].
+///
+/// [RFC 8446 Section 5.3]: https://tools.ietf.org/html/rfc8446#section-5.3
+pub struct Iv([u8; aead::NONCE_LEN]);
+
+impl Iv {
+    pub(crate) fn new(value: [u8; aead::NONCE_LEN]) -> Self {
+        Self(value)
+    }
+
+    // `HKDF-Expand-Label(secret, label, "", iv_length)`.
+    pub(crate) fn derive(secret: &hkdf::Prk, label: &[u8]) -> Self {
+        let mut iv = [0u8; aead::NONCE_LEN];
+        let hkdf_label = HkdfLabel::new(label, &[], iv.len()).unwrap();
+        secret
+            .expand(&hkdf_label.as_info(), OutLen(iv.len()))
+            .and_then(|okm| okm.fill(&mut iv))
+            .unwrap();
+        Self::new(iv)
+    }
+
+    /// The nonce for the record with the given sequence number: the sequence
+    /// number, encoded in network byte order and left-padded with zeros to
+    /// the length of the IV, XORed with the IV.
+    pub fn nonce_for(&self, sequence_number: u64) -> aead::Nonce {
+        let mut nonce = self.0;
+        nonce[(aead::NONCE_LEN - 8)..]
+            .iter_mut()
+            .zip(sequence_number.to_be_bytes().iter())
+            .for_each(|(n, s)| *n ^= *s);
+        aead::Nonce::assume_unique_for_key(nonce)
+    }
+}
+
+impl core::fmt::Debug for Iv {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.debug_struct("Iv").finish()
+    }
+}
+
+/// The Early Secret, the first phase of the key schedule.
+#[derive(Debug)]
+pub struct EarlySecret(Secret);
+
+impl EarlySecret {
+    /// Computes the Early Secret from the given PSK. If no PSK is being used,
+    /// `psk` should be `None`.
+    pub fn new(algorithm: hkdf::Algorithm, psk: Option<&[u8]>) -> Self {
+        let zeros = [0u8; digest::MAX_OUTPUT_LEN];
+        let hash_len = algorithm.hmac_algorithm().digest_algorithm().output_len();
+        let salt = hkdf::Salt::new(algorithm, &zeros[..hash_len]);
+        Self(extract(algorithm, &salt, psk))
+    }
+
+    /// Computes the Early Secret from the PSK associated with a ticket that
+    /// was issued with `ticket_nonce` in a connection with the given
+    /// resumption master secret ([RFC 8446 Section 4.6.1]).
+    ///
+    /// [RFC 8446 Section 4.6.1]: https://tools.ietf.org/html/rfc8446#section-4.6.1
+    pub fn from_resumption_master_secret(
+        resumption_master_secret: &Secret,
+        ticket_nonce: &[u8],
+    ) -> Result<Self, error::Unspecified> {
+        let mut psk = [0u8; digest::MAX_OUTPUT_LEN];
+        let psk = &mut psk[..resumption_master_secret.hash_len()];
+        resumption_master_secret.expand_label_fill(b"resumption", ticket_nonce, psk)?;
+        Ok(Self::new(resumption_master_secret.algorithm, Some(psk)))
+    }
+
+    /// The binder key for an external PSK, `Derive-Secret(., "ext binder",
+    /// "")`.
+    pub fn external_binder_key(&self) -> Secret {
+        self.0.derive_secret_for_empty_transcript(b"ext binder")
+    }
+
+    /// The binder key for a resumption PSK, `Derive-Secret(., "res binder",
+    /// "")`.
+    pub fn resumption_binder_key(&self) -> Secret {
+        self.0.derive_secret_for_empty_transcript(b"res binder")
+    }
+
+    /// `client_early_traffic_secret`; `transcript` covers the ClientHello.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `transcript`'s digest algorithm isn't the key schedule's.
+    pub fn client_early_traffic_secret(&self, transcript: &digest::Context) -> Secret {
+        self.0
+            .derive_secret_for_transcript(b"c e traffic", transcript)
+    }
+
+    /// `early_exporter_master_secret`; `transcript` covers the ClientHello.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `transcript`'s digest algorithm isn't the key schedule's.
+    pub fn early_exporter_master_secret(&self, transcript: &digest::Context) -> Secret {
+        self.0
+            .derive_secret_for_transcript(b"e exp master", transcript)
+    }
+
+    /// Moves on to the Handshake Secret using the (EC)DHE shared secret. If
+    /// (EC)DHE isn't being used (`psk_ke` mode), `shared_secret` should be
+    /// `None`.
+    pub fn into_handshake_secret(self, shared_secret: Option<&[u8]>) -> HandshakeSecret {
+        let salt = self.0.derived_salt();
+        HandshakeSecret(extract(self.0.algorithm, &salt, shared_secret))
+    }
+}
+
+/// The Handshake Secret, the second phase of the key schedule.
+#[derive(Debug)]
+pub struct HandshakeSecret(Secret);
+
+impl HandshakeSecret {
+    /// `client_handshake_traffic_secret`; `transcript` covers ClientHello
+    /// through ServerHello.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `transcript`'s digest algorithm isn't the key schedule's.
+    pub fn client_handshake_traffic_secret(&self, transcript: &digest::Context) -> Secret {
+        self.0
+            .derive_secret_for_transcript(b"c hs traffic", transcript)
+    }
+
+    /// `server_handshake_traffic_secret`; `transcript` covers ClientHello
+    /// through ServerHello.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `transcript`'s digest algorithm isn't the key schedule's.
+    pub fn server_handshake_traffic_secret(&self, transcript: &digest::Context) -> Secret {
+        self.0
+            .derive_secret_for_transcript(b"s hs traffic", transcript)
+    }
+
+    /// Moves on to the Master Secret.
+    pub fn into_master_secret(self) -> MasterSecret {
+        let salt = self.0.derived_salt();
+        MasterSecret(extract(self.0.algorithm, &salt, None))
+    }
+}
+
+/// The Master Secret, the final phase of the key schedule.
+#[derive(Debug)]
+pub struct MasterSecret(Secret);
+
+impl MasterSecret {
+    /// `client_application_traffic_secret_0`; `transcript` covers
+    /// ClientHello through the server's Finished.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `transcript`'s digest algorithm isn't the key schedule's.
+    pub fn client_application_traffic_secret_0(&self, transcript: &digest::Context) -> Secret {
+        self.0
+            .derive_secret_for_transcript(b"c ap traffic", transcript)
+    }
+
+    /// `server_application_traffic_secret_0`; `transcript` covers
+    /// ClientHello through the server's Finished.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `transcript`'s digest algorithm isn't the key schedule's.
+    pub fn server_application_traffic_secret_0(&self, transcript: &digest::Context) -> Secret {
+        self.0
+            .derive_secret_for_transcript(b"s ap traffic", transcript)
+    }
+
+    /// `exporter_master_secret`; `transcript` covers ClientHello through the
+    /// server's Finished.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `transcript`'s digest algorithm isn't the key schedule's.
+    pub fn exporter_master_secret(&self, transcript: &digest::Context) -> Secret {
+        self.0
+            .derive_secret_for_transcript(b"exp master", transcript)
+    }
+
+    /// `resumption_master_secret`; `transcript` covers ClientHello through
+    /// the client's Finished.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `transcript`'s digest algorithm isn't the key schedule's.
+    pub fn resumption_master_secret(&self, transcript: &digest::Context) -> Secret {
+        self.0
+            .derive_secret_for_transcript(b"res master", transcript)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::from_hex;
+
+    fn bytes_of(secret: &Secret, label: &[u8], len: usize) -> [u8; 32] {
+        let mut r = [0u8; 32];
+        secret.expand_label_fill(label, &[], &mut r[..len]).unwrap();
+        r
+    }
+
+    // The handshake phase of the "Simple 1-RTT Handshake" trace in RFC 8448
+    // Section 3.
+    #[test]
+    fn test_rfc8448_simple_1rtt_handshake_secrets() {
+        let shared_secret =
+            from_hex("8bd4054fb55b9d63fdfbacf9f04b9f0d35e6d63f537563efd46272900f89492d").unwrap();
+        // Transcript-Hash(ClientHello, ServerHello).
+        let transcript_hash =
+            from_hex("860c06edc07858ee8e78f0e7428c58edd6b43f2ca3e6e95f02ed063cf0e1cad8").unwrap();
+
+        let early_secret = EarlySecret::new(hkdf::HKDF_SHA256, None);
+        let handshake_secret = early_secret.into_handshake_secret(Some(&shared_secret));
+
+        let client = handshake_secret
+            .0
+            .derive_secret(b"c hs traffic", &transcript_hash);
+        assert_eq!(
+            &bytes_of(&client, b"key", 16)[..16],
+            from_hex("dbfaa693d1762c5b666af5d950258d01").unwrap()
+        );
+        assert_eq!(
+            &bytes_of(&client, b"iv", 12)[..12],
+            from_hex("5bd3c71b836e0b76bb73265f").unwrap()
+        );
+        assert_eq!(
+            client.traffic_iv().nonce_for(0).as_ref(),
+            &from_hex("5bd3c71b836e0b76bb73265f").unwrap()[..]
+        );
+        assert_eq!(
+            &bytes_of(&client, b"finished", 32)[..32],
+            from_hex("b80ad01015fb2f0bd65ff7d4da5d6bf83f84821d1f87fdc7d3c75b5a7b42d9c4").unwrap()
+        );
+
+        let server = handshake_secret
+            .0
+            .derive_secret(b"s hs traffic", &transcript_hash);
+        assert_eq!(
+            &bytes_of(&server, b"key", 16)[..16],
+            from_hex("3fce516009c21727d0f2e4e86ee403bc").unwrap()
+        );
+        assert_eq!(
+            &bytes_of(&server, b"iv", 12)[..12],
+            from_hex("5d313eb2671276ee13000b30").unwrap()
+        );
+        assert_eq!(
+            &bytes_of(&server, b"finished", 32)[..32],
+            from_hex("008d3b66f816ea559f96b537e885c31fc068bf492c652f01f288a1d8cdc19fc8").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_hkdf_label_lengths() {
+        let secret = EarlySecret::new(hkdf::HKDF_SHA256, None).0;
+
+        let max_label = [b'a'; 255 - 6];
+        assert!(secret
+            .expand_label_fill(&max_label, &[], &mut [0; 32])
+            .is_ok());
+        let too_long_label = [b'a'; 255 - 6 + 1];
+        assert!(secret
+            .expand_label_fill(&too_long_label, &[], &mut [0; 32])
+            .is_err());
+
+        let max_context = [0u8; 255];
+        assert!(secret
+            .expand_label_fill(b"a", &max_context, &mut [0; 32])
+            .is_ok());
+        let too_long_context = [0u8; 256];
+        assert!(secret
+            .expand_label_fill(b"a", &too_long_context, &mut [0; 32])
+            .is_err());
+    }
+
+    #[test]
+    fn test_iv_nonce_for() {
+        let iv = Iv([
+            0x5b, 0xd3, 0xc7, 0x1b, 0x83, 0x6e, 0x0b, 0x76, 0xbb, 0x73, 0x26, 0x5f,
+        ]);
+        assert_eq!(
+            iv.nonce_for(0x0102_0304_0506_0708).as_ref(),
+            &[0x5b, 0xd3, 0xc7, 0x1b, 0x82, 0x6c, 0x08, 0x72, 0xbe, 0x75, 0x21, 0x57]
+        );
+    }
+}
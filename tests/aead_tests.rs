@@ -59,9 +59,13 @@ macro_rules! test_aead {
                         $test_file,
                         [
                             less_safe_key_open_in_place,
+                            less_safe_key_open_in_place_separate_tag_vectored,
+                            less_safe_key_open_to,
                             less_safe_key_open_within,
                             less_safe_key_seal_in_place_append_tag,
                             less_safe_key_seal_in_place_separate_tag,
+                            less_safe_key_seal_in_place_separate_tag_vectored,
+                            less_safe_key_seal_to,
                             opening_key_open_in_place,
                             opening_key_open_within,
                             sealing_key_seal_in_place_append_tag,
@@ -398,6 +402,191 @@ fn less_safe_key_open_within(
     })
 }
 
+fn less_safe_key_seal_to(
+    alg: &'static aead::Algorithm,
+    tc: KnownAnswerTestCase,
+) -> Result<(), error::Unspecified> {
+    let key = make_less_safe_key(alg, tc.key);
+
+    let mut expected = Vec::from(tc.ciphertext);
+    expected.extend_from_slice(tc.tag);
+
+    let mut out = vec![0u8; expected.len()];
+    key.seal_to(
+        aead::Nonce::assume_unique_for_key(tc.nonce),
+        tc.aad,
+        tc.plaintext,
+        &mut out,
+    )?;
+    assert_eq!(out, expected);
+
+    // The output must be exactly the length of the ciphertext and tag.
+    for wrong_len in [0, expected.len() - 1, expected.len() + 1] {
+        let mut out = vec![0u8; wrong_len];
+        assert!(key
+            .seal_to(
+                aead::Nonce::assume_unique_for_key(tc.nonce),
+                tc.aad,
+                tc.plaintext,
+                &mut out,
+            )
+            .is_err());
+    }
+
+    Ok(())
+}
+
+fn less_safe_key_open_to(
+    alg: &'static aead::Algorithm,
+    tc: KnownAnswerTestCase,
+) -> Result<(), error::Unspecified> {
+    let key = make_less_safe_key(alg, tc.key);
+
+    let mut ciphertext_and_tag = Vec::from(tc.ciphertext);
+    ciphertext_and_tag.extend_from_slice(tc.tag);
+
+    let mut out = vec![0u8; tc.plaintext.len()];
+    key.open_to(
+        aead::Nonce::assume_unique_for_key(tc.nonce),
+        tc.aad,
+        &ciphertext_and_tag,
+        &mut out,
+    )?;
+    assert_eq!(out, tc.plaintext);
+
+    // The output must be exactly the length of the plaintext.
+    let mut wrong_lens = vec![tc.plaintext.len() + 1];
+    wrong_lens.extend(tc.plaintext.len().checked_sub(1));
+    for wrong_len in wrong_lens {
+        let mut out = vec![0u8; wrong_len];
+        assert!(key
+            .open_to(
+                aead::Nonce::assume_unique_for_key(tc.nonce),
+                tc.aad,
+                &ciphertext_and_tag,
+                &mut out,
+            )
+            .is_err());
+    }
+
+    // On failure, the output is zeroed.
+    let last = ciphertext_and_tag.len() - 1;
+    ciphertext_and_tag[last] ^= 1;
+    let mut out = vec![1u8; tc.plaintext.len()];
+    assert!(key
+        .open_to(
+            aead::Nonce::assume_unique_for_key(tc.nonce),
+            tc.aad,
+            &ciphertext_and_tag,
+            &mut out,
+        )
+        .is_err());
+    assert!(out.iter().all(|&b| b == 0));
+
+    // Too short to contain a tag.
+    assert!(key
+        .open_to(
+            aead::Nonce::assume_unique_for_key(tc.nonce),
+            tc.aad,
+            &ciphertext_and_tag[..(aead::MAX_TAG_LEN - 1)],
+            &mut [],
+        )
+        .is_err());
+
+    Ok(())
+}
+
+/// Fragment length patterns for the vectored tests, which are repeated until
+/// the input is exhausted. These include empty fragments and fragments that
+/// end in the middle of a block, for both 16-byte and 64-byte blocks.
+const FRAGMENT_LENS: &[&[usize]] = &[
+    &[usize::MAX],
+    &[1],
+    &[0, 3],
+    &[15, 0, 17],
+    &[16],
+    &[63, 1, 0],
+    &[64],
+    &[65, 5],
+    &[200, 0, 0, 1],
+];
+
+fn split_into_fragments<'a>(mut input: &'a mut [u8], lens: &[usize]) -> Vec<&'a mut [u8]> {
+    let mut fragments = Vec::new();
+    for &len in lens.iter().cycle() {
+        if input.is_empty() {
+            break;
+        }
+        let (fragment, rest) = input.split_at_mut(core::cmp::min(len, input.len()));
+        fragments.push(fragment);
+        input = rest;
+    }
+    fragments
+}
+
+fn split_aad(aad: &[u8]) -> [&[u8]; 3] {
+    let (first, second) = aad.split_at(aad.len() / 2);
+    [first, &[], second]
+}
+
+fn less_safe_key_seal_in_place_separate_tag_vectored(
+    alg: &'static aead::Algorithm,
+    tc: KnownAnswerTestCase,
+) -> Result<(), error::Unspecified> {
+    let key = make_less_safe_key(alg, tc.key);
+    let aad = split_aad(tc.aad.as_ref());
+
+    for lens in FRAGMENT_LENS {
+        let mut in_out = Vec::from(tc.plaintext);
+        let tag = key.seal_in_place_separate_tag_vectored(
+            aead::Nonce::assume_unique_for_key(tc.nonce),
+            &aad,
+            &mut split_into_fragments(&mut in_out, lens),
+        )?;
+        assert_eq!(tag.as_ref(), tc.tag);
+        assert_eq!(in_out, tc.ciphertext);
+    }
+
+    Ok(())
+}
+
+fn less_safe_key_open_in_place_separate_tag_vectored(
+    alg: &'static aead::Algorithm,
+    tc: KnownAnswerTestCase,
+) -> Result<(), error::Unspecified> {
+    let key = make_less_safe_key(alg, tc.key);
+    let aad = split_aad(tc.aad.as_ref());
+    let tag: aead::Tag = tc.tag.try_into().unwrap();
+
+    for lens in FRAGMENT_LENS {
+        let mut in_out = Vec::from(tc.ciphertext);
+        key.open_in_place_separate_tag_vectored(
+            aead::Nonce::assume_unique_for_key(tc.nonce),
+            &aad,
+            tag,
+            &mut split_into_fragments(&mut in_out, lens),
+        )?;
+        assert_eq!(in_out, tc.plaintext);
+
+        // On failure, every fragment is zeroed.
+        let mut wrong_tag = [0u8; aead::MAX_TAG_LEN];
+        wrong_tag.copy_from_slice(tc.tag);
+        wrong_tag[0] ^= 1;
+        let mut in_out = Vec::from(tc.ciphertext);
+        assert!(key
+            .open_in_place_separate_tag_vectored(
+                aead::Nonce::assume_unique_for_key(tc.nonce),
+                &aad,
+                aead::Tag::from(wrong_tag),
+                &mut split_into_fragments(&mut in_out, lens),
+            )
+            .is_err());
+        assert!(in_out.iter().all(|&b| b == 0));
+    }
+
+    Ok(())
+}
+
 #[allow(clippy::range_plus_one)]
 fn key_sizes(aead_alg: &'static aead::Algorithm) {
     let key_len = aead_alg.key_len();
@@ -442,6 +631,122 @@ fn test_aead_nonce_sizes() {
     assert!(aead::Nonce::try_assume_unique_for_key(&nonce[..16]).is_err()); // 128 bits.
 }
 
+#[test]
+fn aead_aegis_128l() {
+    test_aegis(
+        &aead::aegis::AEGIS_128L,
+        test_file!("aead_aegis_128l_tests.txt"),
+    );
+}
+
+#[test]
+fn aead_aegis_256() {
+    test_aegis(
+        &aead::aegis::AEGIS_256,
+        test_file!("aead_aegis_256_tests.txt"),
+    );
+}
+
+fn test_aegis(algorithm: &'static aead::aegis::Algorithm, test_file: test::File) {
+    use aead::aegis;
+
+    assert_eq!(algorithm.tag_len(), aegis::TAG_LEN);
+    assert_eq!(algorithm.nonce_len(), algorithm.key_len());
+    let key_data = vec![0u8; algorithm.key_len() * 2];
+    assert!(aegis::LessSafeKey::new(algorithm, &key_data[..algorithm.key_len()]).is_ok());
+    assert!(aegis::LessSafeKey::new(algorithm, &key_data[..(algorithm.key_len() - 1)]).is_err());
+    assert!(aegis::LessSafeKey::new(algorithm, &key_data[..(algorithm.key_len() + 1)]).is_err());
+    assert!(aegis::LessSafeKey::new(algorithm, &[]).is_err());
+
+    test::run(test_file, |section, test_case| {
+        assert_eq!(section, "");
+        let key = test_case.consume_bytes("KEY");
+        let nonce = test_case.consume_bytes("NONCE");
+        let plaintext = test_case.consume_bytes("IN");
+        let aad = test_case.consume_bytes("AD");
+        let ct = test_case.consume_bytes("CT");
+        let tag = test_case.consume_bytes("TAG");
+
+        assert_eq!(nonce.len(), algorithm.nonce_len());
+        let key = aegis::LessSafeKey::new(algorithm, &key)?;
+        assert_eq!(key.algorithm(), algorithm);
+        let nonce = || aegis::Nonce::try_assume_unique_for_key(&nonce).unwrap();
+        let aad = aead::Aad::from(&aad[..]);
+        let mut ciphertext_and_tag = ct.clone();
+        ciphertext_and_tag.extend_from_slice(&tag);
+
+        let mut in_out = plaintext.clone();
+        key.seal_in_place_append_tag(nonce(), aad, &mut in_out)?;
+        assert_eq!(in_out, ciphertext_and_tag);
+
+        let mut in_out = plaintext.clone();
+        let actual_tag = key.seal_in_place_separate_tag(nonce(), aad, &mut in_out)?;
+        assert_eq!(actual_tag.as_ref(), &tag[..]);
+        assert_eq!(in_out, ct);
+
+        let mut in_out = ciphertext_and_tag.clone();
+        let actual_plaintext = key.open_in_place(nonce(), aad, &mut in_out)?;
+        assert_eq!(actual_plaintext, &plaintext[..]);
+
+        for prefix_len in [0, 1, 17, 32] {
+            let mut in_out = vec![123u8; prefix_len];
+            in_out.extend_from_slice(&ciphertext_and_tag);
+            let actual_plaintext = key.open_within(nonce(), aad, &mut in_out, prefix_len..)?;
+            assert_eq!(actual_plaintext, &plaintext[..]);
+        }
+
+        let tag = aegis::Tag::try_from(&tag[..])?;
+        for prefix_len in [0, 1, 17, 32] {
+            let mut in_out = vec![123u8; prefix_len];
+            in_out.extend_from_slice(&ct);
+            let actual_plaintext =
+                key.open_in_place_separate_tag(nonce(), aad, tag, &mut in_out, prefix_len..)?;
+            assert_eq!(actual_plaintext, &plaintext[..]);
+        }
+
+        // On failure, the plaintext is zeroed.
+        let mut wrong_tag = [0u8; aegis::TAG_LEN];
+        wrong_tag.copy_from_slice(tag.as_ref());
+        wrong_tag[0] ^= 1;
+        let mut in_out = ct.clone();
+        assert!(key
+            .open_in_place_separate_tag(nonce(), aad, aegis::Tag::from(wrong_tag), &mut in_out, 0..)
+            .is_err());
+        assert!(in_out.iter().all(|&b| b == 0));
+
+        Ok(())
+    })
+}
+
+// Test that AEGIS nonces are only accepted for keys of the same algorithm.
+#[test]
+fn test_aegis_nonce_mismatch() {
+    use aead::aegis;
+
+    let nonce = [0u8; 33];
+    for len in [0, 1, aead::NONCE_LEN, 15, 17, 31, 33] {
+        assert!(aegis::Nonce::try_assume_unique_for_key(&nonce[..len]).is_err());
+    }
+
+    for algorithm in [&aegis::AEGIS_128L, &aegis::AEGIS_256] {
+        let key = aegis::LessSafeKey::new(algorithm, &vec![0u8; algorithm.key_len()]).unwrap();
+        for nonce_len in [16, 32] {
+            let nonce = aegis::Nonce::try_assume_unique_for_key(&nonce[..nonce_len]).unwrap();
+            let mut in_out = [0u8; 10];
+            let result = key.seal_in_place_separate_tag(nonce, aead::Aad::empty(), &mut in_out);
+            assert_eq!(result.is_ok(), algorithm.nonce_len() == nonce_len);
+        }
+    }
+}
+
+#[test]
+fn test_aegis_key_debug() {
+    use aead::aegis;
+
+    let key = aegis::LessSafeKey::new(&aegis::AEGIS_256, &[0u8; 32]).unwrap();
+    assert_eq!("LessSafeKey { algorithm: AEGIS_256 }", format!("{:?}", key));
+}
+
 #[allow(clippy::range_plus_one)]
 #[test]
 fn aead_chacha20_poly1305_openssh() {
@@ -490,6 +795,110 @@ fn aead_chacha20_poly1305_openssh() {
     );
 }
 
+#[test]
+fn aead_aes_gcm_openssh() {
+    use aead::aes_gcm_openssh::{OpeningKey, SealingKey, IV_LEN, PACKET_LENGTH_LEN, TAG_LEN};
+
+    test::run(
+        test_file!("aead_aes_gcm_openssh_tests.txt"),
+        |section, test_case| {
+            let algorithm = match section {
+                "AES_128_GCM" => &aead::AES_128_GCM,
+                "AES_256_GCM" => &aead::AES_256_GCM,
+                _ => unreachable!(),
+            };
+            let key_bytes = test_case.consume_bytes("KEY");
+            let iv = {
+                let as_vec = test_case.consume_bytes("IV");
+                let mut as_array = [0u8; IV_LEN];
+                as_array.copy_from_slice(&as_vec);
+                as_array
+            };
+            let initial_sequence_number = test_case.consume_usize("INITIAL_SEQUENCE_NUMBER");
+            assert_eq!(
+                initial_sequence_number as u32 as usize,
+                initial_sequence_number
+            );
+            let initial_sequence_number = initial_sequence_number as u32;
+            let sequence_number = test_case.consume_usize("SEQUENCE_NUMBER");
+            assert_eq!(sequence_number as u32 as usize, sequence_number);
+            let sequence_num = sequence_number as u32;
+            let plaintext = test_case.consume_bytes("IN");
+            let ct = test_case.consume_bytes("CT");
+            let expected_tag = test_case.consume_bytes("TAG");
+
+            let mut tag = [0u8; TAG_LEN];
+            let mut s_in_out = plaintext.clone();
+            let mut s_key =
+                SealingKey::new(algorithm, &key_bytes, &iv, initial_sequence_number).unwrap();
+            s_key.seal_in_place(sequence_num, &mut s_in_out[..], &mut tag)?;
+            assert_eq!(&ct, &s_in_out);
+            assert_eq!(&expected_tag, &tag);
+
+            let o_key =
+                OpeningKey::new(algorithm, &key_bytes, &iv, initial_sequence_number).unwrap();
+            let encrypted_packet_length: [u8; PACKET_LENGTH_LEN] =
+                ct[..PACKET_LENGTH_LEN].try_into().unwrap();
+            assert_eq!(
+                &o_key.decrypt_packet_length(sequence_num, encrypted_packet_length),
+                &plaintext[..PACKET_LENGTH_LEN]
+            );
+
+            // The wrong sequence number uses the wrong nonce.
+            {
+                let mut o_in_out = ct.clone();
+                let o_result =
+                    o_key.open_in_place(sequence_num.wrapping_add(1), &mut o_in_out[..], &tag);
+                assert_eq!(o_result, Err(error::Unspecified));
+            }
+
+            // The packet length is authenticated.
+            {
+                let mut o_in_out = ct.clone();
+                o_in_out[0] ^= 1;
+                let o_result = o_key.open_in_place(sequence_num, &mut o_in_out[..], &tag);
+                assert_eq!(o_result, Err(error::Unspecified));
+            }
+
+            {
+                let o_result = o_key.open_in_place(sequence_num, &mut s_in_out[..], &tag);
+                assert_eq!(o_result, Ok(&plaintext[4..]));
+            }
+            assert_eq!(&s_in_out[..4], &ct[..4]);
+            assert_eq!(&s_in_out[4..], &plaintext[4..]);
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn aead_aes_gcm_openssh_key_tests() {
+    use aead::aes_gcm_openssh::{OpeningKey, SealingKey, IV_LEN, TAG_LEN};
+
+    let iv = [0u8; IV_LEN];
+    for &(algorithm, key_len) in &[(&aead::AES_128_GCM, 16), (&aead::AES_256_GCM, 32)] {
+        let key_bytes = vec![0u8; key_len + 1];
+        assert!(SealingKey::new(algorithm, &key_bytes[..key_len], &iv, 0).is_ok());
+        assert!(SealingKey::new(algorithm, &key_bytes[..(key_len - 1)], &iv, 0).is_err());
+        assert!(SealingKey::new(algorithm, &key_bytes, &iv, 0).is_err());
+        assert!(OpeningKey::new(algorithm, &key_bytes[..key_len], &iv, 0).is_ok());
+        assert!(OpeningKey::new(algorithm, &key_bytes, &iv, 0).is_err());
+
+        // Packets too short to have a packet length are rejected.
+        let o_key = OpeningKey::new(algorithm, &key_bytes[..key_len], &iv, 0).unwrap();
+        let mut in_out = [0u8; 3];
+        assert!(o_key
+            .open_in_place(0, &mut in_out, &[0u8; TAG_LEN])
+            .is_err());
+    }
+
+    // Only AES-GCM is supported.
+    let key_bytes = [0u8; 32];
+    assert!(SealingKey::new(&aead::CHACHA20_POLY1305, &key_bytes, &iv, 0).is_err());
+    assert!(OpeningKey::new(&aead::CHACHA20_POLY1305, &key_bytes, &iv, 0).is_err());
+}
+
 #[test]
 fn aead_test_aad_traits() {
     test::compile_time_assert_copy::<aead::Aad<&'_ [u8]>>();
@@ -600,6 +1009,281 @@ fn test_aead_lesssafekey_clone_chacha20_poly1305() {
     test_aead_lesssafekey_clone_for_algorithm(&aead::CHACHA20_POLY1305);
 }
 
+#[test]
+fn test_aead_usage_limited_sealing_key() {
+    use aead::{BoundKey, NonceSequence};
+
+    let key_bytes = [0; 16];
+    let key = aead::UnboundKey::new(&aead::AES_128_GCM, &key_bytes).unwrap();
+    let mut key = aead::UsageLimited::new(
+        aead::SealingKey::new(key, CounterNonceSequence(0)),
+        aead::UsageLimits::new(3, 25, u64::MAX),
+    );
+    let expected_key = make_less_safe_key(&aead::AES_128_GCM, &key_bytes);
+
+    let mut in_out = [0u8; 10];
+    let tag = key
+        .seal_in_place_separate_tag(aead::Aad::empty(), &mut in_out)
+        .unwrap();
+    let mut expected_in_out = [0u8; 10];
+    let expected_tag = expected_key
+        .seal_in_place_separate_tag(
+            CounterNonceSequence(0).advance().unwrap(),
+            aead::Aad::empty(),
+            &mut expected_in_out,
+        )
+        .unwrap();
+    assert_eq!(in_out, expected_in_out);
+    assert_eq!(tag.as_ref(), expected_tag.as_ref());
+
+    let mut in_out = vec![0u8; 10];
+    key.seal_in_place_append_tag(aead::Aad::empty(), &mut in_out)
+        .unwrap();
+    assert_eq!((key.sealed_records(), key.sealed_bytes()), (2, 20));
+    assert!(!key.limit_reached());
+
+    // Too many bytes; nothing is sealed or counted.
+    let mut in_out = [0u8; 10];
+    assert!(key
+        .seal_in_place_separate_tag(aead::Aad::empty(), &mut in_out)
+        .is_err());
+    assert_eq!(in_out, [0u8; 10]);
+    assert_eq!((key.sealed_records(), key.sealed_bytes()), (2, 20));
+
+    assert!(key
+        .seal_in_place_separate_tag(aead::Aad::empty(), &mut in_out[..5])
+        .is_ok());
+    assert_eq!((key.sealed_records(), key.sealed_bytes()), (3, 25));
+    assert!(key.limit_reached());
+
+    // Too many records.
+    assert!(key
+        .seal_in_place_separate_tag(aead::Aad::empty(), &mut [])
+        .is_err());
+    assert_eq!(key.sealed_records(), 3);
+}
+
+#[test]
+fn test_aead_usage_limited_opening_key() {
+    use aead::{BoundKey, NonceSequence};
+
+    let key_bytes = [0; 32];
+    let sealing_key = make_less_safe_key(&aead::CHACHA20_POLY1305, &key_bytes);
+    let key = aead::UnboundKey::new(&aead::CHACHA20_POLY1305, &key_bytes).unwrap();
+    let mut key = aead::UsageLimited::new(
+        aead::OpeningKey::new(key, CounterNonceSequence(0)),
+        aead::UsageLimits::new(u64::MAX, u64::MAX, 2),
+    );
+    assert_eq!(key.limits().max_failed_opens(), 2);
+
+    let sealed = |counter| {
+        let mut in_out = vec![1, 2, 3];
+        sealing_key
+            .seal_in_place_append_tag(
+                CounterNonceSequence(counter).advance().unwrap(),
+                aead::Aad::empty(),
+                &mut in_out,
+            )
+            .unwrap();
+        in_out
+    };
+
+    // Sealed with the wrong nonce.
+    assert!(key
+        .open_in_place(aead::Aad::empty(), &mut sealed(1))
+        .is_err());
+    assert_eq!(key.failed_opens(), 1);
+
+    assert_eq!(
+        key.open_in_place(aead::Aad::empty(), &mut sealed(1)),
+        Ok(&mut [1, 2, 3][..])
+    );
+    assert_eq!(key.failed_opens(), 1);
+    assert!(!key.limit_reached());
+
+    assert!(key
+        .open_within(aead::Aad::empty(), &mut sealed(0), 0..)
+        .is_err());
+    assert_eq!(key.failed_opens(), 2);
+    assert!(key.limit_reached());
+
+    // Valid input is rejected once the limit is reached.
+    assert!(key
+        .open_in_place(aead::Aad::empty(), &mut sealed(3))
+        .is_err());
+    assert_eq!(key.failed_opens(), 2);
+}
+
+#[test]
+fn test_aead_usage_limited_less_safe_key() {
+    let nonce = || aead::Nonce::assume_unique_for_key([0; aead::NONCE_LEN]);
+    let mut key = aead::UsageLimited::new(
+        make_less_safe_key(&aead::AES_256_GCM, &[0; 32]),
+        aead::UsageLimits::new(3, 30, 3),
+    );
+
+    let plaintext = [1u8; 10];
+    let mut sealed = [0u8; 10 + aead::MAX_TAG_LEN];
+    key.seal_to(nonce(), aead::Aad::empty(), &plaintext, &mut sealed)
+        .unwrap();
+    assert_eq!((key.sealed_records(), key.sealed_bytes()), (1, 10));
+
+    let mut first = [1u8; 4];
+    let mut second = [1u8; 6];
+    let tag = key
+        .seal_in_place_separate_tag_vectored(nonce(), &[], &mut [&mut first, &mut second])
+        .unwrap();
+    assert_eq!((key.sealed_records(), key.sealed_bytes()), (2, 20));
+    assert_eq!(&sealed[..4], &first);
+    assert_eq!(&sealed[4..10], &second);
+    assert_eq!(&sealed[10..], tag.as_ref());
+
+    // Too many bytes; nothing is sealed or counted.
+    let mut out = [0u8; 11 + aead::MAX_TAG_LEN];
+    assert!(key
+        .seal_to(nonce(), aead::Aad::empty(), &[1u8; 11], &mut out)
+        .is_err());
+    let mut fragment = [1u8; 11];
+    assert!(key
+        .seal_in_place_separate_tag_vectored(nonce(), &[], &mut [&mut fragment])
+        .is_err());
+    assert_eq!(fragment, [1u8; 11]);
+    assert_eq!((key.sealed_records(), key.sealed_bytes()), (2, 20));
+
+    let mut wrong_tag = [0u8; aead::MAX_TAG_LEN];
+    wrong_tag.copy_from_slice(tag.as_ref());
+    wrong_tag[0] ^= 1;
+    let wrong_tag = aead::Tag::from(wrong_tag);
+
+    let mut opened = [0u8; 10];
+    key.open_to(nonce(), aead::Aad::empty(), &sealed, &mut opened)
+        .unwrap();
+    assert_eq!(opened, plaintext);
+    let mut in_out = sealed;
+    assert!(key
+        .open_in_place_separate_tag(
+            nonce(),
+            aead::Aad::empty(),
+            wrong_tag,
+            &mut in_out[..10],
+            0..
+        )
+        .is_err());
+    assert_eq!(key.failed_opens(), 1);
+
+    let mut wrong = sealed;
+    wrong[0] ^= 1;
+    assert!(key
+        .open_to(nonce(), aead::Aad::empty(), &wrong, &mut opened)
+        .is_err());
+    assert_eq!(key.failed_opens(), 2);
+
+    let (mut first, mut second) = (first, second);
+    assert!(key
+        .open_in_place_separate_tag_vectored(
+            nonce(),
+            &[],
+            wrong_tag,
+            &mut [&mut first, &mut second]
+        )
+        .is_err());
+    assert_eq!(key.failed_opens(), 3);
+    assert!(key.limit_reached());
+
+    // Valid input is rejected once the limit is reached.
+    let mut in_out = sealed;
+    assert!(key
+        .open_in_place_separate_tag(nonce(), aead::Aad::empty(), tag, &mut in_out[..10], 0..)
+        .is_err());
+    assert_eq!(key.failed_opens(), 3);
+}
+
+#[test]
+fn test_aead_usage_limits_quic() {
+    let aes_gcm = aead::UsageLimits::quic(&aead::AES_128_GCM).unwrap();
+    assert_eq!(
+        aes_gcm,
+        aead::UsageLimits::quic(&aead::AES_256_GCM).unwrap()
+    );
+    assert_eq!(aes_gcm.max_sealed_records(), 1 << 23);
+    assert_eq!(aes_gcm.max_sealed_bytes(), u64::MAX);
+    assert_eq!(aes_gcm.max_failed_opens(), 1 << 52);
+
+    let chacha20_poly1305 = aead::UsageLimits::quic(&aead::CHACHA20_POLY1305).unwrap();
+    assert_eq!(chacha20_poly1305.max_sealed_records(), u64::MAX);
+    assert_eq!(chacha20_poly1305.max_failed_opens(), 1 << 36);
+}
+
+#[test]
+fn test_aead_nonce_sequences() {
+    use aead::{nonce_sequence, NonceSequence};
+
+    fn nonces(sequence: &mut impl NonceSequence, n: usize) -> Vec<[u8; aead::NONCE_LEN]> {
+        (0..n)
+            .map(|_| *sequence.advance().unwrap().as_ref())
+            .collect()
+    }
+
+    assert_eq!(
+        nonces(&mut nonce_sequence::Counter::new(), 3),
+        [
+            [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+            [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2],
+        ]
+    );
+
+    let iv = [0xa5; aead::NONCE_LEN];
+    assert_eq!(
+        nonces(&mut nonce_sequence::XorCounter::new(iv), 3),
+        [
+            [0xa5, 0xa5, 0xa5, 0xa5, 0xa5, 0xa5, 0xa5, 0xa5, 0xa5, 0xa5, 0xa5, 0xa5],
+            [0xa5, 0xa5, 0xa5, 0xa5, 0xa5, 0xa5, 0xa5, 0xa5, 0xa5, 0xa5, 0xa5, 0xa4],
+            [0xa5, 0xa5, 0xa5, 0xa5, 0xa5, 0xa5, 0xa5, 0xa5, 0xa5, 0xa5, 0xa5, 0xa7],
+        ]
+    );
+
+    assert_eq!(
+        nonces(&mut nonce_sequence::PrefixCounter::new([1, 2, 3, 4]), 3),
+        [
+            [1, 2, 3, 4, 0, 0, 0, 0, 0, 0, 0, 0],
+            [1, 2, 3, 4, 0, 0, 0, 0, 0, 0, 0, 1],
+            [1, 2, 3, 4, 0, 0, 0, 0, 0, 0, 0, 2],
+        ]
+    );
+}
+
+#[test]
+fn test_aead_nonce_sequence_tls13() {
+    use aead::{nonce_sequence, BoundKey};
+    use ring::{hkdf, tls13};
+
+    let secret = tls13::EarlySecret::new(hkdf::HKDF_SHA256, None)
+        .into_handshake_secret(Some(&[1; 32]))
+        .client_handshake_traffic_secret(&ring::digest::Context::new(&ring::digest::SHA256));
+    let mut key = aead::SealingKey::new(
+        secret.traffic_key(&aead::AES_128_GCM),
+        nonce_sequence::XorCounter::from(secret.traffic_iv()),
+    );
+    let expected_key = aead::LessSafeKey::new(secret.traffic_key(&aead::AES_128_GCM));
+    let iv = secret.traffic_iv();
+
+    for sequence_number in 0..3 {
+        let mut in_out = vec![0u8; 10];
+        key.seal_in_place_append_tag(aead::Aad::empty(), &mut in_out)
+            .unwrap();
+        let mut expected = vec![0u8; 10];
+        expected_key
+            .seal_in_place_append_tag(
+                iv.nonce_for(sequence_number),
+                aead::Aad::empty(),
+                &mut expected,
+            )
+            .unwrap();
+        assert_eq!(in_out, expected);
+    }
+}
+
 fn make_key<K: aead::BoundKey<OneNonceSequence>>(
     algorithm: &'static aead::Algorithm,
     key: &[u8],
@@ -630,3 +1314,14 @@ impl aead::NonceSequence for OneNonceSequence {
         self.0.take().ok_or(error::Unspecified)
     }
 }
+
+struct CounterNonceSequence(u32);
+
+impl aead::NonceSequence for CounterNonceSequence {
+    fn advance(&mut self) -> Result<aead::Nonce, error::Unspecified> {
+        let mut nonce = [0u8; aead::NONCE_LEN];
+        nonce[8..].copy_from_slice(&self.0.to_be_bytes());
+        self.0 += 1;
+        Ok(aead::Nonce::assume_unique_for_key(nonce))
+    }
+}
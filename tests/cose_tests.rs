@@ -0,0 +1,333 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#![cfg(feature = "cose")]
+
+use ring::{
+    aead,
+    cose::{self, Curve, Key, KeyComponents},
+    rand, rsa,
+    signature::{self, KeyPair},
+    test, test_file,
+};
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[test]
+fn cose_key_tests() {
+    test::run(test_file!("cose_key_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+
+        let input = test_case.consume_bytes("Input");
+        let error = test_case.consume_optional_string("Error");
+
+        match (Key::from_cbor(&input), error) {
+            (Ok(key), None) => {
+                let output = test_case.consume_optional_bytes("Output").unwrap_or(input);
+                assert_eq!(key.to_cbor().unwrap(), output);
+                if let Some(expected) = test_case.consume_optional_bytes("PublicKey") {
+                    let public_key = match &key.components {
+                        KeyComponents::EcPublicKey { public_key, .. }
+                        | KeyComponents::EcKeyPair { public_key, .. } => public_key,
+                        _ => unreachable!(),
+                    };
+                    assert_eq!(public_key, &expected);
+                }
+                check_key_pair(&key.components);
+            }
+            (Err(e), None) => panic!("Failed with error \"{}\", but expected to succeed", e),
+            (Ok(_), Some(e)) => panic!("Succeeded, but expected error \"{}\"", e),
+            (Err(actual), Some(expected)) => assert_eq!(format!("{}", actual), expected),
+        }
+
+        Ok(())
+    });
+}
+
+// Verifies that the private key, if any, is consistent with the public key.
+fn check_key_pair(components: &KeyComponents) {
+    let rng = rand::SystemRandom::new();
+    match components {
+        KeyComponents::RsaKeyPair(components) => {
+            let _ = rsa::KeyPair::from_components(components).unwrap();
+        }
+        KeyComponents::EcKeyPair {
+            curve,
+            private_key,
+            public_key,
+        } => match curve {
+            Curve::P256 | Curve::P384 => {
+                let alg = if *curve == Curve::P256 {
+                    &signature::ECDSA_P256_SHA256_FIXED_SIGNING
+                } else {
+                    &signature::ECDSA_P384_SHA384_FIXED_SIGNING
+                };
+                let _ = signature::EcdsaKeyPair::from_private_key_and_public_key(
+                    alg,
+                    private_key,
+                    public_key,
+                    &rng,
+                )
+                .unwrap();
+            }
+            Curve::Ed25519 => {
+                let _ =
+                    signature::Ed25519KeyPair::from_seed_and_public_key(private_key, public_key)
+                        .unwrap();
+            }
+            _ => unreachable!(),
+        },
+        _ => {}
+    }
+}
+
+#[test]
+fn cose_key_from_key_pair_test() {
+    let rng = rand::SystemRandom::new();
+    let pkcs8 = signature::Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+    let key_pair = signature::Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+
+    let key = Key {
+        components: KeyComponents::EcKeyPair {
+            curve: Curve::Ed25519,
+            private_key: key_pair.seed_less_safe().to_vec(),
+            public_key: key_pair.public_key().as_ref().to_vec(),
+        },
+        key_id: Some(b"key".to_vec()),
+        algorithm: Some(-8),
+    };
+    let parsed = Key::from_cbor(&key.to_cbor().unwrap()).unwrap();
+    assert_eq!(parsed.key_id.as_deref(), Some(&b"key"[..]));
+    assert_eq!(parsed.algorithm, Some(-8));
+    assert_eq!(parsed.to_cbor().unwrap(), key.to_cbor().unwrap());
+    check_key_pair(&parsed.components);
+}
+
+#[test]
+fn cose_sign1_verify_tests() {
+    test::run(test_file!("cose_sign1_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+
+        let alg = test_case.consume_string("Alg").parse().unwrap();
+        let alg = cose::SignatureAlgorithm::from_id(alg).unwrap();
+        let public_key = test_case.consume_bytes("PublicKey");
+        let input = test_case.consume_bytes("Input");
+        let external_aad = test_case.consume_bytes("ExternalAad");
+        let expected_result = test_case.consume_string("Result");
+
+        // Check that the signature, the last element of the message, is over
+        // the given Sig_structure.
+        if let Some(to_be_signed) = test_case.consume_optional_bytes("ToBeSigned") {
+            assert_eq!(alg.id(), cose::ES256.id());
+            let signature = &input[(input.len() - 64)..];
+            signature::UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_FIXED, &public_key)
+                .verify(&to_be_signed, signature)
+                .unwrap();
+        }
+
+        let result = cose::Sign1::parse(&input).and_then(|sign1| {
+            sign1
+                .verify(alg, &public_key, &external_aad)
+                .map(<[u8]>::to_vec)
+        });
+        match (result, expected_result.as_str()) {
+            (Ok(payload), "P") => assert_eq!(payload, test_case.consume_bytes("Payload")),
+            (Err(_), "F") => {}
+            (result, expected) => panic!("Expected {} but got {:?}", expected, result),
+        }
+
+        Ok(())
+    });
+}
+
+#[test]
+fn cose_signature_algorithm_from_id_test() {
+    for alg in [&cose::ES256, &cose::ES384, &cose::EDDSA, &cose::PS256] {
+        assert_eq!(
+            cose::SignatureAlgorithm::from_id(alg.id()).unwrap().id(),
+            alg.id()
+        );
+    }
+    assert!(cose::SignatureAlgorithm::from_id(0).is_none());
+    assert!(cose::SignatureAlgorithm::from_id(-257).is_none()); // RS256
+}
+
+#[test]
+fn cose_sign1_sign_test() {
+    // The "EdDSA with `kid`" case in cose_sign1_tests.txt.
+    const ED25519_SEED: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+    const ED25519_SIGN1: &str = "d28443a10127a10442313154546869732069732074686520636f6e74656e742e58406354488f9f290e36cd80e23762e664a5cb03e4267c66a8cffaef7c66d89a40bf2cbb8222432a08e5ee410d8b540c6931d26fb6af673f7e2100655d8bae765c04";
+
+    let rng = rand::SystemRandom::new();
+
+    let ed25519 =
+        signature::Ed25519KeyPair::from_seed_unchecked(&test::from_hex(ED25519_SEED).unwrap())
+            .unwrap();
+    let rsa = rsa::KeyPair::from_pkcs8(include_bytes!("rsa_test_private_key_2048.p8")).unwrap();
+    let p256 = signature::EcdsaKeyPair::from_pkcs8(
+        &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+        include_bytes!("ecdsa_test_private_key_p256.p8"),
+        &rng,
+    )
+    .unwrap();
+    let p384_pkcs8 =
+        signature::EcdsaKeyPair::generate_pkcs8(&signature::ECDSA_P384_SHA384_FIXED_SIGNING, &rng)
+            .unwrap();
+    let p384 = signature::EcdsaKeyPair::from_pkcs8(
+        &signature::ECDSA_P384_SHA384_FIXED_SIGNING,
+        p384_pkcs8.as_ref(),
+        &rng,
+    )
+    .unwrap();
+
+    let content = b"This is the content.";
+    let sign1 = cose::sign1(
+        &cose::EDDSA,
+        cose::SigningKey::Ed25519(&ed25519),
+        Some(b"11"),
+        content,
+        b"",
+        &rng,
+    )
+    .unwrap();
+    assert_eq!(sign1, test::from_hex(ED25519_SIGN1).unwrap());
+
+    let external_aad = b"aad";
+    let rsa_public_key = rsa.public_key().as_ref();
+    for (alg, key, public_key) in [
+        (
+            &cose::ES256,
+            cose::SigningKey::Ecdsa(&p256),
+            p256.public_key().as_ref(),
+        ),
+        (
+            &cose::ES384,
+            cose::SigningKey::Ecdsa(&p384),
+            p384.public_key().as_ref(),
+        ),
+        (
+            &cose::EDDSA,
+            cose::SigningKey::Ed25519(&ed25519),
+            ed25519.public_key().as_ref(),
+        ),
+        (&cose::PS256, cose::SigningKey::Rsa(&rsa), rsa_public_key),
+    ] {
+        let sign1 = cose::sign1(alg, key, Some(b"k1"), content, external_aad, &rng).unwrap();
+        let sign1 = cose::Sign1::parse(&sign1).unwrap();
+        assert_eq!(sign1.key_id_unverified(), Some(&b"k1"[..]));
+        assert_eq!(
+            sign1.verify(alg, public_key, external_aad).unwrap(),
+            content
+        );
+        assert!(sign1.verify(alg, public_key, b"").is_err());
+    }
+
+    // Keys that don't match the algorithm.
+    for (alg, key) in [
+        (&cose::PS256, cose::SigningKey::Ed25519(&ed25519)),
+        (&cose::ES256, cose::SigningKey::Rsa(&rsa)),
+        (&cose::ES256, cose::SigningKey::Ecdsa(&p384)),
+        (&cose::ES384, cose::SigningKey::Ecdsa(&p256)),
+        (&cose::EDDSA, cose::SigningKey::Ecdsa(&p256)),
+    ] {
+        assert!(cose::sign1(alg, key, None, content, b"", &rng).is_err());
+    }
+}
+
+fn aead_algorithm(name: &str) -> &'static aead::Algorithm {
+    match name {
+        "AES_128_GCM" => &aead::AES_128_GCM,
+        "AES_256_GCM" => &aead::AES_256_GCM,
+        "CHACHA20_POLY1305" => &aead::CHACHA20_POLY1305,
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn cose_encrypt0_decrypt_tests() {
+    test::run(
+        test_file!("cose_encrypt0_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+
+            let alg = aead_algorithm(&test_case.consume_string("Alg"));
+            let key = test_case.consume_bytes("Key");
+            let key = aead::LessSafeKey::new(aead::UnboundKey::new(alg, &key).unwrap());
+            let input = test_case.consume_bytes("Input");
+            let external_aad = test_case.consume_bytes("ExternalAad");
+            let plaintext = test_case.consume_optional_bytes("Plaintext");
+            let iv = test_case.consume_optional_bytes("Iv");
+            let to_be_encrypted = test_case.consume_optional_bytes("ToBeEncrypted");
+            let expected_result = test_case.consume_string("Result");
+
+            // Check that the ciphertext, the last element of the message, is
+            // authenticated with the given Enc_structure as the AAD.
+            if let (Some(iv), Some(to_be_encrypted), Some(plaintext)) =
+                (iv, to_be_encrypted, &plaintext)
+            {
+                let mut in_out = input[(input.len() - plaintext.len() - alg.tag_len())..].to_vec();
+                let nonce = aead::Nonce::try_assume_unique_for_key(&iv).unwrap();
+                let actual = key
+                    .open_in_place(nonce, aead::Aad::from(to_be_encrypted), &mut in_out)
+                    .unwrap();
+                assert_eq!(actual, &plaintext[..]);
+            }
+
+            let result = cose::Encrypt0::parse(&input)
+                .and_then(|encrypt0| encrypt0.decrypt(&key, &external_aad));
+            match (result, expected_result.as_str()) {
+                (Ok(actual), "P") => assert_eq!(Some(actual), plaintext),
+                (Err(_), "F") => {}
+                (result, expected) => panic!("Expected {} but got {:?}", expected, result),
+            }
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn cose_encrypt0_encrypt_test() {
+    // The "AES-128-GCM" case in cose_encrypt0_tests.txt.
+    const KEY: &str = "849b57219dae48de646d07dbb533566e";
+    const IV: &str = "02d1f7e6f26c43d4868d87ce";
+    const ENCRYPT0: &str = "d08343a10101a1054c02d1f7e6f26c43d4868d87ce582460973a94bb2898009ee52ecfd9ab1dd25867374b162e2c03568b41f57c3cc16f9166250a";
+
+    let content = b"This is the content.";
+    let key = aead::LessSafeKey::new(
+        aead::UnboundKey::new(&aead::AES_128_GCM, &test::from_hex(KEY).unwrap()).unwrap(),
+    );
+    let nonce = || aead::Nonce::try_assume_unique_for_key(&test::from_hex(IV).unwrap()).unwrap();
+    let encrypt0 = cose::encrypt0(&key, nonce(), None, content, b"").unwrap();
+    assert_eq!(encrypt0, test::from_hex(ENCRYPT0).unwrap());
+
+    let external_aad = b"aad";
+    for alg in [
+        &aead::AES_128_GCM,
+        &aead::AES_256_GCM,
+        &aead::CHACHA20_POLY1305,
+    ] {
+        let key_bytes = vec![7; alg.key_len()];
+        let key = aead::LessSafeKey::new(aead::UnboundKey::new(alg, &key_bytes).unwrap());
+        let encrypt0 = cose::encrypt0(&key, nonce(), Some(b"k1"), content, external_aad).unwrap();
+        let encrypt0 = cose::Encrypt0::parse(&encrypt0).unwrap();
+        assert_eq!(encrypt0.key_id_unverified(), Some(&b"k1"[..]));
+        assert_eq!(encrypt0.decrypt(&key, external_aad).unwrap(), content);
+        assert!(encrypt0.decrypt(&key, b"").is_err());
+    }
+}
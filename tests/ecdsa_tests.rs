@@ -12,6 +12,8 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
+#[cfg(feature = "alloc")]
+use ring::pem;
 use ring::{
     rand,
     signature::{self, KeyPair},
@@ -86,6 +88,108 @@ fn ecdsa_from_pkcs8_test() {
     );
 }
 
+#[test]
+fn ecdsa_from_sec1_test() {
+    let rng = rand::SystemRandom::new();
+
+    test::run(
+        test_file!("ecdsa_from_sec1_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+
+            let curve_name = test_case.consume_string("Curve");
+            let (this, other) = match curve_name.as_str() {
+                "P-256" => (
+                    &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+                    &signature::ECDSA_P384_SHA384_FIXED_SIGNING,
+                ),
+                "P-384" => (
+                    &signature::ECDSA_P384_SHA384_FIXED_SIGNING,
+                    &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+                ),
+                _ => unreachable!(),
+            };
+
+            let input = test_case.consume_bytes("Input");
+            let error = test_case.consume_optional_string("Error");
+
+            match (
+                signature::EcdsaKeyPair::from_sec1(this, &input, &rng),
+                error,
+            ) {
+                (Ok(key_pair), None) => {
+                    let public_key = test_case.consume_bytes("PublicKey");
+                    assert_eq!(key_pair.public_key().as_ref(), &public_key[..]);
+
+                    // The serialized form always has the parameters and the
+                    // public key.
+                    let sec1 = key_pair.to_sec1_less_safe();
+                    let reparsed =
+                        signature::EcdsaKeyPair::from_sec1(this, sec1.as_ref(), &rng).unwrap();
+                    assert_eq!(reparsed.public_key().as_ref(), &public_key[..]);
+                    assert_eq!(reparsed.to_sec1_less_safe().as_ref(), sec1.as_ref());
+                }
+                (Err(e), None) => panic!("Failed with error \"{}\", but expected to succeed", e),
+                (Ok(_), Some(e)) => panic!("Succeeded, but expected error \"{}\"", e),
+                (Err(actual), Some(expected)) => assert_eq!(format!("{}", actual), expected),
+            };
+
+            assert!(signature::EcdsaKeyPair::from_sec1(other, &input, &rng).is_err());
+
+            Ok(())
+        },
+    );
+}
+
+// `to_sec1_less_safe()` produces what `openssl ec` produces.
+#[cfg(feature = "alloc")]
+#[test]
+fn ecdsa_to_sec1_test() {
+    const PRIVATE_KEY: &[u8] = include_bytes!("ecdsa_test_private_key_p256.p8");
+    const PRIVATE_KEY_SEC1_PEM: &str = include_str!("ecdsa_test_private_key_p256_sec1.pem");
+
+    let rng = rand::SystemRandom::new();
+    let alg = &signature::ECDSA_P256_SHA256_FIXED_SIGNING;
+    let key_pair = signature::EcdsaKeyPair::from_pkcs8(alg, PRIVATE_KEY, &rng).unwrap();
+    let expected =
+        pem::decode_expecting(pem::Label::EcPrivateKey, PRIVATE_KEY_SEC1_PEM.as_bytes()).unwrap();
+    assert_eq!(key_pair.to_sec1_less_safe().as_ref(), &expected[..]);
+
+    let from_sec1 = signature::EcdsaKeyPair::from_sec1(alg, &expected, &rng).unwrap();
+    assert_eq!(
+        from_sec1.public_key().as_ref(),
+        key_pair.public_key().as_ref()
+    );
+}
+
+#[test]
+fn ecdsa_to_pkcs8_test() {
+    const PRIVATE_KEY: &[u8] = include_bytes!("ecdsa_test_private_key_p256.p8");
+
+    let rng = rand::SystemRandom::new();
+    let alg = &signature::ECDSA_P256_SHA256_FIXED_SIGNING;
+    let key_pair = signature::EcdsaKeyPair::from_pkcs8(alg, PRIVATE_KEY, &rng).unwrap();
+    assert_eq!(key_pair.to_pkcs8_less_safe().as_ref(), PRIVATE_KEY);
+
+    let from_private_key = signature::EcdsaKeyPair::from_private_key_and_public_key(
+        alg,
+        key_pair.private_key_less_safe(),
+        key_pair.public_key().as_ref(),
+        &rng,
+    )
+    .unwrap();
+    assert_eq!(from_private_key.to_pkcs8_less_safe().as_ref(), PRIVATE_KEY);
+
+    for alg in &[
+        &signature::ECDSA_P256_SHA256_ASN1_SIGNING,
+        &signature::ECDSA_P384_SHA384_FIXED_SIGNING,
+    ] {
+        let pkcs8 = signature::EcdsaKeyPair::generate_pkcs8(alg, &rng).unwrap();
+        let key_pair = signature::EcdsaKeyPair::from_pkcs8(alg, pkcs8.as_ref(), &rng).unwrap();
+        assert_eq!(key_pair.to_pkcs8_less_safe().as_ref(), pkcs8.as_ref());
+    }
+}
+
 // Verify that, at least, we generate PKCS#8 documents that we can read.
 #[test]
 fn ecdsa_generate_pkcs8_test() {
@@ -201,6 +305,24 @@ fn ecdsa_test_public_key_coverage() {
     // Test `Copy`.
     let _: <signature::EcdsaKeyPair as KeyPair>::PublicKey = *key_pair.public_key();
 
+    // Test `to_spki()`.
+    #[cfg(feature = "alloc")]
+    {
+        const PUBLIC_KEY_SPKI: &[u8] = include_bytes!("ecdsa_test_public_key_p256_spki.der");
+        assert_eq!(key_pair.public_key().to_spki().as_ref(), PUBLIC_KEY_SPKI);
+    }
+
+    // Test `to_compressed()`, against what `openssl ec -conv_form compressed`
+    // produces.
+    {
+        const PUBLIC_KEY_COMPRESSED: &[u8] =
+            include_bytes!("ecdsa_test_public_key_p256_compressed.der");
+        assert_eq!(
+            key_pair.public_key().to_compressed().as_ref(),
+            PUBLIC_KEY_COMPRESSED
+        );
+    }
+
     // Test `Debug`.
     assert_eq!(PUBLIC_KEY_DEBUG, format!("{:?}", key_pair.public_key()));
     assert_eq!(
@@ -255,8 +377,18 @@ fn signature_ecdsa_sign_fixed_sign_and_verify_test() {
 
             let signature = private_key.sign(&rng, &msg).unwrap();
 
-            let public_key = signature::UnparsedPublicKey::new(verification_alg, q);
+            let public_key = signature::UnparsedPublicKey::new(verification_alg, &q);
+            assert_eq!(public_key.verify(&msg, signature.as_ref()), Ok(()));
+
+            // The compressed form of the public key verifies the signature too,
+            // but not when the y coordinate has the wrong parity.
+            let mut compressed = private_key.public_key().to_compressed().as_ref().to_vec();
+            assert_eq!(compressed.len(), 1 + ((q.len() - 1) / 2));
+            let public_key = signature::UnparsedPublicKey::new(verification_alg, &compressed);
             assert_eq!(public_key.verify(&msg, signature.as_ref()), Ok(()));
+            compressed[0] ^= 1;
+            let public_key = signature::UnparsedPublicKey::new(verification_alg, &compressed);
+            assert!(public_key.verify(&msg, signature.as_ref()).is_err());
 
             Ok(())
         },
@@ -13,7 +13,7 @@
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
 use ring::{
-    error,
+    error, rand,
     signature::{self, Ed25519KeyPair, KeyPair},
     test, test_file,
 };
@@ -118,6 +118,30 @@ fn test_ed25519_from_seed_and_public_key_misuse() {
     assert!(Ed25519KeyPair::from_seed_and_public_key(PUBLIC_KEY, PRIVATE_KEY).is_err());
 }
 
+#[test]
+fn test_ed25519_to_pkcs8() {
+    const PRIVATE_KEY: &[u8] = include_bytes!("ed25519_test_private_key.bin");
+    const PUBLIC_KEY: &[u8] = include_bytes!("ed25519_test_public_key.bin");
+
+    let key_pair = Ed25519KeyPair::from_seed_and_public_key(PRIVATE_KEY, PUBLIC_KEY).unwrap();
+    assert_eq!(key_pair.seed_less_safe(), PRIVATE_KEY);
+
+    let pkcs8 = key_pair.to_pkcs8_less_safe();
+    let from_pkcs8 = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+    assert_eq!(from_pkcs8.seed_less_safe(), PRIVATE_KEY);
+    assert_eq!(from_pkcs8.public_key().as_ref(), PUBLIC_KEY);
+
+    // A key pair constructed from only the seed is exported with the public
+    // key, in the same format that `generate_pkcs8()` uses.
+    let key_pair = Ed25519KeyPair::from_seed_unchecked(PRIVATE_KEY).unwrap();
+    assert_eq!(key_pair.to_pkcs8_less_safe().as_ref(), pkcs8.as_ref());
+
+    let rng = rand::SystemRandom::new();
+    let generated = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+    let key_pair = Ed25519KeyPair::from_pkcs8(generated.as_ref()).unwrap();
+    assert_eq!(key_pair.to_pkcs8_less_safe().as_ref(), generated.as_ref());
+}
+
 enum FromPkcs8Variant {
     Checked,
     MaybeUnchecked,
@@ -206,6 +230,13 @@ fn ed25519_test_public_key_coverage() {
     // Test `Copy`.
     let _: <Ed25519KeyPair as KeyPair>::PublicKey = *key_pair.public_key();
 
+    // Test `to_spki()`.
+    #[cfg(feature = "alloc")]
+    {
+        const PUBLIC_KEY_SPKI: &[u8] = include_bytes!("ed25519_test_public_key_spki.der");
+        assert_eq!(key_pair.public_key().to_spki().as_ref(), PUBLIC_KEY_SPKI);
+    }
+
     // Test `Debug`.
     assert_eq!(PUBLIC_KEY_DEBUG, format!("{:?}", key_pair.public_key()));
     assert_eq!(
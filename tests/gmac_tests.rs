@@ -0,0 +1,108 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use ring::{aead, aead::gmac, test, test_file};
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[test]
+fn gmac_tests() {
+    test::run(test_file!("gmac_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+        let algorithm = match test_case.consume_string("GMAC").as_str() {
+            "AES_128" => &gmac::AES_128,
+            "AES_256" => &gmac::AES_256,
+            _ => unreachable!(),
+        };
+        let key_bytes = test_case.consume_bytes("Key");
+        let nonce = test_case.consume_bytes("Nonce");
+        let mut input = test_case.consume_bytes("Input");
+        let tag = test_case.consume_bytes("Tag");
+
+        let key = gmac::Key::new(algorithm, &key_bytes)?;
+        assert_eq!(key.algorithm(), algorithm);
+        let nonce = || aead::Nonce::try_assume_unique_for_key(&nonce).unwrap();
+
+        // One-shot API.
+        assert_eq!(gmac::sign(&key, nonce(), &input).as_ref(), &tag[..]);
+        assert!(gmac::verify(&key, nonce(), &input, &tag).is_ok());
+
+        // Incremental API, with input split at every position.
+        for split in 0..=input.len() {
+            let (a, b) = input.split_at(split);
+            let mut ctx = gmac::Context::new(&key, nonce());
+            ctx.update(a);
+            ctx.update(&[]);
+            ctx.update(b);
+            assert_eq!(ctx.finish().as_ref(), &tag[..]);
+        }
+        let mut ctx = gmac::Context::new(&key, nonce());
+        input.chunks(7).for_each(|chunk| ctx.update(chunk));
+        assert!(ctx.verify(&tag).is_ok());
+
+        // GMAC is AES-GCM with an empty plaintext.
+        let aead_key = aead::LessSafeKey::new(aead::UnboundKey::new(
+            algorithm_aead(algorithm),
+            &key_bytes,
+        )?);
+        let aead_tag =
+            aead_key.seal_in_place_separate_tag(nonce(), aead::Aad::from(&input), &mut [])?;
+        assert_eq!(aead_tag.as_ref(), &tag[..]);
+
+        // Tamper with the input, the tag, and the nonce.
+        let mut wrong_tag = tag.clone();
+        wrong_tag[0] ^= 1;
+        assert!(gmac::verify(&key, nonce(), &input, &wrong_tag).is_err());
+        assert!(gmac::verify(&key, nonce(), &input, &tag[..15]).is_err());
+        let mut wrong_nonce = [0u8; aead::NONCE_LEN];
+        wrong_nonce.copy_from_slice(&nonce().as_ref()[..]);
+        wrong_nonce[0] ^= 1;
+        assert!(gmac::verify(
+            &key,
+            aead::Nonce::assume_unique_for_key(wrong_nonce),
+            &input,
+            &tag
+        )
+        .is_err());
+        input.push(0);
+        assert!(gmac::verify(&key, nonce(), &input, &tag).is_err());
+
+        Ok(())
+    });
+}
+
+#[test]
+fn gmac_key_len() {
+    for algorithm in [&gmac::AES_128, &gmac::AES_256] {
+        let key_len = algorithm.key_len();
+        assert!(gmac::Key::new(algorithm, &vec![0; key_len]).is_ok());
+        assert!(gmac::Key::new(algorithm, &vec![0; key_len - 1]).is_err());
+        assert!(gmac::Key::new(algorithm, &vec![0; key_len + 1]).is_err());
+    }
+    assert_eq!(gmac::AES_128.key_len(), 16);
+    assert_eq!(gmac::AES_256.key_len(), 32);
+    assert_ne!(gmac::AES_128, gmac::AES_256);
+}
+
+fn algorithm_aead(algorithm: &gmac::Algorithm) -> &'static aead::Algorithm {
+    if algorithm == &gmac::AES_128 {
+        &aead::AES_128_GCM
+    } else {
+        &aead::AES_256_GCM
+    }
+}
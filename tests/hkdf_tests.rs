@@ -38,17 +38,26 @@ fn hkdf_tests() {
         let secret = test_case.consume_bytes("IKM");
         let salt = test_case.consume_bytes("salt");
         let info = test_case.consume_bytes("info");
-        let _ = test_case.consume_bytes("PRK");
+        let expected_prk = test_case.consume_bytes("PRK");
         let expected_out = test_case.consume_bytes("OKM");
 
         let salt = hkdf::Salt::new(alg, &salt);
 
+        let prk = salt.extract(&secret);
+        assert_eq!(prk.value_less_safe(), None);
+
         // TODO: test multi-part info, especially with empty parts.
-        let My(out) = salt
-            .extract(&secret)
-            .expand(&[&info], My(expected_out.len()))
-            .unwrap()
-            .into();
+        let My(out) = prk.expand(&[&info], My(expected_out.len())).unwrap().into();
+        assert_eq!(out, expected_out);
+
+        let prk = salt.extract_exportable(&secret);
+        assert_eq!(prk.value_less_safe(), Some(&expected_prk[..]));
+        let My(out) = prk.expand(&[&info], My(expected_out.len())).unwrap().into();
+        assert_eq!(out, expected_out);
+
+        // The exported PRK is equivalent to the original one.
+        let prk = hkdf::Prk::new_less_safe(alg, prk.value_less_safe().unwrap());
+        let My(out) = prk.expand(&[&info], My(expected_out.len())).unwrap().into();
         assert_eq!(out, expected_out);
 
         Ok(())
@@ -107,6 +116,68 @@ fn hkdf_output_len_tests() {
     }
 }
 
+#[test]
+fn hkdf_prk_value_tests() {
+    for &alg in &[hkdf::HKDF_SHA256, hkdf::HKDF_SHA384, hkdf::HKDF_SHA512] {
+        let digest_alg = alg.hmac_algorithm().digest_algorithm();
+        let salt = hkdf::Salt::new(alg, b"salt");
+        let prk = salt.extract(b"secret");
+        assert_eq!(prk.algorithm(), alg);
+        assert_eq!(prk.value_less_safe(), None);
+        let exportable = salt.extract_exportable(b"secret");
+        assert_eq!(exportable.algorithm(), alg);
+        assert_eq!(
+            exportable.value_less_safe().map(<[u8]>::len),
+            Some(digest_alg.output_len())
+        );
+
+        // A PRK derived by HKDF-Expand isn't exportable, even if the PRK it
+        // was expanded from is, but the expanded value can be exported
+        // explicitly.
+        let expanded = hkdf::Prk::from(exportable.expand(&[b"info"], alg).unwrap());
+        assert_eq!(expanded.value_less_safe(), None);
+        let My(expected) = prk
+            .expand(&[b"info"], My(digest_alg.output_len()))
+            .unwrap()
+            .into();
+        let exported = hkdf::Prk::new_less_safe(alg, &expected);
+        let My(a) = expanded.expand(&[b"info"], My(42)).unwrap().into();
+        let My(b) = exported.expand(&[b"info"], My(42)).unwrap().into();
+        assert_eq!(a, b);
+
+        // Values no longer than the block length are kept as-is.
+        let value = vec![0x0b; digest_alg.block_len()];
+        let prk = hkdf::Prk::new_less_safe(alg, &value);
+        assert_eq!(prk.value_less_safe(), Some(&value[..]));
+
+        // Longer values are replaced by their digest, which is an equivalent
+        // HMAC key.
+        let value = vec![0x0b; digest_alg.block_len() + 1];
+        let long = hkdf::Prk::new_less_safe(alg, &value);
+        let hashed = digest::digest(digest_alg, &value);
+        assert_eq!(long.value_less_safe(), Some(hashed.as_ref()));
+        let short = hkdf::Prk::new_less_safe(alg, hashed.as_ref());
+        let My(a) = long.expand(&[b"info"], My(42)).unwrap().into();
+        let My(b) = short.expand(&[b"info"], My(42)).unwrap().into();
+        assert_eq!(a, b);
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn hkdf_okm_bytes_tests() {
+    let prk = hkdf::Salt::new(hkdf::HKDF_SHA256, b"salt").extract(b"secret");
+    for &len in &[0, 1, 32, 33, 255 * 32] {
+        let My(expected) = prk.expand(&[b"info"], My(len)).unwrap().into();
+        let out: hkdf::OkmBytes = prk.expand(&[b"info"], My(len)).unwrap().into();
+        assert_eq!(out.as_ref(), &expected[..]);
+    }
+
+    // Any `KeyType` can be used.
+    let out: hkdf::OkmBytes = prk.expand(&[b"info"], hkdf::HKDF_SHA384).unwrap().into();
+    assert_eq!(out.as_ref().len(), digest::SHA384_OUTPUT_LEN);
+}
+
 /// Generic newtype wrapper that lets us implement traits for externally-defined
 /// types.
 #[derive(Debug, PartialEq)]
@@ -0,0 +1,274 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#![cfg(feature = "jose")]
+
+use ring::{
+    jose::{self, Curve, Jwk, KeyComponents},
+    rand, rsa,
+    signature::{self, KeyPair},
+    test, test_file,
+};
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[test]
+fn jwk_tests() {
+    test::run(test_file!("jose_jwk_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+
+        let input = test_case.consume_string("Input");
+        let error = test_case.consume_optional_string("Error");
+
+        match (Jwk::from_json(input.as_bytes()), error) {
+            (Ok(jwk), None) => {
+                let output = test_case.consume_optional_string("Output").unwrap_or(input);
+                assert_eq!(jwk.to_json().unwrap(), output);
+                let thumbprint = test_case.consume_bytes("Thumbprint");
+                assert_eq!(jwk.thumbprint().unwrap().as_ref(), &thumbprint[..]);
+                check_key_pair(&jwk.components);
+            }
+            (Err(e), None) => panic!("Failed with error \"{}\", but expected to succeed", e),
+            (Ok(_), Some(e)) => panic!("Succeeded, but expected error \"{}\"", e),
+            (Err(actual), Some(expected)) => assert_eq!(format!("{}", actual), expected),
+        }
+
+        Ok(())
+    });
+}
+
+// Verifies that the private key, if any, is consistent with the public key.
+fn check_key_pair(components: &KeyComponents) {
+    let rng = rand::SystemRandom::new();
+    match components {
+        KeyComponents::RsaKeyPair(components) => {
+            let _ = rsa::KeyPair::from_components(components).unwrap();
+        }
+        KeyComponents::EcKeyPair {
+            curve,
+            private_key,
+            public_key,
+        } => match curve {
+            Curve::P256 | Curve::P384 => {
+                let alg = if *curve == Curve::P256 {
+                    &signature::ECDSA_P256_SHA256_FIXED_SIGNING
+                } else {
+                    &signature::ECDSA_P384_SHA384_FIXED_SIGNING
+                };
+                let _ = signature::EcdsaKeyPair::from_private_key_and_public_key(
+                    alg,
+                    private_key,
+                    public_key,
+                    &rng,
+                )
+                .unwrap();
+            }
+            Curve::Ed25519 => {
+                let _ =
+                    signature::Ed25519KeyPair::from_seed_and_public_key(private_key, public_key)
+                        .unwrap();
+            }
+            _ => unreachable!(),
+        },
+        _ => {}
+    }
+}
+
+#[test]
+fn jwk_from_key_pair_test() {
+    const PKCS8: &[u8] = include_bytes!("ecdsa_test_private_key_p256.p8");
+
+    let rng = rand::SystemRandom::new();
+    let key_pair = signature::EcdsaKeyPair::from_pkcs8(
+        &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+        PKCS8,
+        &rng,
+    )
+    .unwrap();
+    let public_key = key_pair.public_key().as_ref().to_vec();
+
+    let jwk = Jwk {
+        components: KeyComponents::EcKeyPair {
+            curve: Curve::P256,
+            private_key: key_pair.private_key_less_safe().to_vec(),
+            public_key: public_key.clone(),
+        },
+        key_id: None,
+        algorithm: Some("ES256".into()),
+    };
+    let public_jwk = Jwk {
+        components: KeyComponents::EcPublicKey {
+            curve: Curve::P256,
+            public_key,
+        },
+        key_id: None,
+        algorithm: None,
+    };
+    assert_eq!(
+        jwk.thumbprint().unwrap().as_ref(),
+        public_jwk.thumbprint().unwrap().as_ref()
+    );
+
+    let parsed = Jwk::from_json(jwk.to_json().unwrap().as_bytes()).unwrap();
+    assert_eq!(parsed.algorithm.as_deref(), Some("ES256"));
+    check_key_pair(&parsed.components);
+    assert_eq!(
+        format!("{:?}", parsed.components),
+        format!("{:?}", public_jwk.components).replace("EcPublicKey", "EcKeyPair")
+    );
+}
+
+#[test]
+fn jwk_invalid_components_test() {
+    let jwk = Jwk {
+        components: KeyComponents::EcPublicKey {
+            curve: Curve::P256,
+            public_key: vec![4; 65],
+        },
+        key_id: None,
+        algorithm: None,
+    };
+    assert!(jwk.to_json().is_err());
+    assert!(jwk.thumbprint().is_err());
+}
+
+#[test]
+fn jws_verify_tests() {
+    test::run(test_file!("jose_jws_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+
+        let alg = test_case.consume_string("Alg");
+        let alg = jose::Algorithm::from_name(&alg).unwrap();
+        assert_eq!(
+            jose::Algorithm::from_name(alg.name()).unwrap().name(),
+            alg.name()
+        );
+        let public_key = test_case.consume_bytes("PublicKey");
+        let input = test_case.consume_string("Input");
+        let expected_result = test_case.consume_string("Result");
+
+        let result = jose::CompactJws::parse(&input)
+            .and_then(|jws| jws.verify(alg, &public_key).map(|v| v.payload().to_vec()));
+        match (result, expected_result.as_str()) {
+            (Ok(payload), "P") => assert_eq!(payload, test_case.consume_bytes("Payload")),
+            (Err(_), "F") => {}
+            (result, expected) => panic!("Expected {} but got {:?}", expected, result),
+        }
+
+        Ok(())
+    });
+}
+
+#[test]
+fn jws_algorithm_from_name_test() {
+    assert!(jose::Algorithm::from_name("none").is_none());
+    assert!(jose::Algorithm::from_name("HS256").is_none());
+    assert!(jose::Algorithm::from_name("es256").is_none());
+    assert_eq!(jose::Algorithm::from_name("ES256").unwrap().name(), "ES256");
+}
+
+#[test]
+fn jws_sign_test() {
+    // RFC 8037 Appendix A.4.
+    const ED25519_SEED: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+    const ED25519_JWS: &str = "eyJhbGciOiJFZERTQSJ9.RXhhbXBsZSBvZiBFZDI1NTE5IHNpZ25pbmc.hgyY0il_MGCjP0JzlnLWG1PPOt7-09PGcvMg3AIbQR6dWbhijcNR4ki4iylGjg5BhVsPt9g7sVvpAr_MuM0KAg";
+
+    let rng = rand::SystemRandom::new();
+
+    let ed25519 =
+        signature::Ed25519KeyPair::from_seed_unchecked(&test::from_hex(ED25519_SEED).unwrap())
+            .unwrap();
+    let rsa = rsa::KeyPair::from_pkcs8(include_bytes!("rsa_test_private_key_2048.p8")).unwrap();
+    let p256 = signature::EcdsaKeyPair::from_pkcs8(
+        &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+        include_bytes!("ecdsa_test_private_key_p256.p8"),
+        &rng,
+    )
+    .unwrap();
+    let p384_pkcs8 =
+        signature::EcdsaKeyPair::generate_pkcs8(&signature::ECDSA_P384_SHA384_FIXED_SIGNING, &rng)
+            .unwrap();
+    let p384 = signature::EcdsaKeyPair::from_pkcs8(
+        &signature::ECDSA_P384_SHA384_FIXED_SIGNING,
+        p384_pkcs8.as_ref(),
+        &rng,
+    )
+    .unwrap();
+    let p256_asn1 = signature::EcdsaKeyPair::from_pkcs8(
+        &signature::ECDSA_P256_SHA256_ASN1_SIGNING,
+        include_bytes!("ecdsa_test_private_key_p256.p8"),
+        &rng,
+    )
+    .unwrap();
+
+    let jws = jose::sign_compact(
+        &jose::EDDSA,
+        jose::SigningKey::Ed25519(&ed25519),
+        &jose::Header::default(),
+        b"Example of Ed25519 signing",
+        &rng,
+    )
+    .unwrap();
+    assert_eq!(jws, ED25519_JWS);
+
+    let header = jose::Header {
+        key_id: Some("k1"),
+        media_type: Some("JWT"),
+    };
+    let payload = br#"{"iss":"joe","exp":1300819380}"#;
+    let rsa_public_key = rsa.public_key().as_ref();
+    for (alg, key, public_key) in [
+        (&jose::RS256, jose::SigningKey::Rsa(&rsa), rsa_public_key),
+        (&jose::PS256, jose::SigningKey::Rsa(&rsa), rsa_public_key),
+        (
+            &jose::ES256,
+            jose::SigningKey::Ecdsa(&p256),
+            p256.public_key().as_ref(),
+        ),
+        (
+            &jose::ES384,
+            jose::SigningKey::Ecdsa(&p384),
+            p384.public_key().as_ref(),
+        ),
+        (
+            &jose::EDDSA,
+            jose::SigningKey::Ed25519(&ed25519),
+            ed25519.public_key().as_ref(),
+        ),
+    ] {
+        let jws = jose::sign_compact(alg, key, &header, payload, &rng).unwrap();
+        let jws = jose::CompactJws::parse(&jws).unwrap();
+        assert_eq!(jws.key_id_unverified(), Some("k1"));
+        let verified = jws.verify(alg, public_key).unwrap();
+        assert_eq!(verified.payload(), payload);
+        assert_eq!(verified.key_id(), Some("k1"));
+        assert_eq!(verified.media_type(), Some("JWT"));
+    }
+
+    // Keys that don't match the algorithm.
+    for (alg, key) in [
+        (&jose::RS256, jose::SigningKey::Ed25519(&ed25519)),
+        (&jose::ES256, jose::SigningKey::Rsa(&rsa)),
+        (&jose::ES256, jose::SigningKey::Ecdsa(&p384)),
+        (&jose::ES384, jose::SigningKey::Ecdsa(&p256)),
+        (&jose::ES256, jose::SigningKey::Ecdsa(&p256_asn1)),
+        (&jose::EDDSA, jose::SigningKey::Ecdsa(&p256)),
+    ] {
+        assert!(jose::sign_compact(alg, key, &header, payload, &rng).is_err());
+    }
+}
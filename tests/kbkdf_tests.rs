@@ -0,0 +1,227 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use ring::{aead, digest, error, hkdf, hmac, kbkdf, test, test_file};
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[test]
+fn kbkdf_tests() {
+    test::run(test_file!("kbkdf_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+        let alg = hmac_alg(test_case.consume_digest_alg("Hash").unwrap());
+        let encoding = test_case.consume_optional_string("Fields").map(|fields| {
+            let fields: Vec<kbkdf::Field> = fields.split(", ").map(field).collect();
+            let counter_len = test_case.consume_usize("CounterLen");
+            let length_len = test_case.consume_usize("LengthLen");
+            kbkdf::Encoding::new(&fields, counter_len, length_len).unwrap()
+        });
+        let key = test_case.consume_bytes("KI");
+        let label = test_case.consume_bytes("Label");
+        let context = test_case.consume_bytes("Context");
+        let expected_out = test_case.consume_bytes("KO");
+
+        let key = hmac::Key::new(alg, &key);
+        let okm = match &encoding {
+            Some(encoding) => kbkdf::derive_with_encoding(
+                encoding,
+                &key,
+                &label,
+                &context,
+                My(expected_out.len()),
+            ),
+            None => kbkdf::derive(&key, &label, &context, My(expected_out.len())),
+        };
+        let My(out) = okm.unwrap().into();
+        assert_eq!(out, expected_out);
+
+        Ok(())
+    });
+}
+
+#[test]
+fn kbkdf_output_len_tests() {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, &[0x0b; 32]);
+
+    {
+        // Test zero length.
+        let okm = kbkdf::derive(&key, b"label", b"context", My(0)).unwrap();
+        let result: My<Vec<u8>> = okm.into();
+        assert_eq!(&result.0, &[]);
+    }
+
+    {
+        // The length in bits must fit in 32 bits.
+        const MAX_LEN: usize = (u32::MAX / 8) as usize;
+        assert!(kbkdf::derive(&key, b"label", b"context", My(MAX_LEN)).is_ok());
+        assert!(kbkdf::derive(&key, b"label", b"context", My(MAX_LEN + 1)).is_err());
+    }
+
+    {
+        // With an 8-bit counter, at most 255 blocks can be output, and with a
+        // 16-bit length, at most 2**16 - 1 bits.
+        use kbkdf::Field::*;
+        let encoding = kbkdf::Encoding::new(&[Label, Counter, Context, Length], 1, 2).unwrap();
+        const MAX_LEN: usize = 255 * 32;
+        assert!(kbkdf::derive_with_encoding(&encoding, &key, b"", b"", My(MAX_LEN)).is_ok());
+        assert!(kbkdf::derive_with_encoding(&encoding, &key, b"", b"", My(MAX_LEN + 1)).is_err());
+
+        let encoding = kbkdf::Encoding::new(&[Label, Counter, Context, Length], 2, 2).unwrap();
+        const MAX_LEN_BITS: usize = (1 << 16) - 1;
+        assert!(
+            kbkdf::derive_with_encoding(&encoding, &key, b"", b"", My(MAX_LEN_BITS / 8)).is_ok()
+        );
+        assert!(
+            kbkdf::derive_with_encoding(&encoding, &key, b"", b"", My(MAX_LEN_BITS / 8 + 1))
+                .is_err()
+        );
+    }
+
+    {
+        // Test length mismatch.
+        let okm = kbkdf::derive(&key, b"label", b"context", My(2)).unwrap();
+        let mut buf = [0u8; 3];
+        assert_eq!(okm.fill(&mut buf), Err(error::Unspecified));
+    }
+}
+
+#[test]
+fn kbkdf_key_type_tests() {
+    let key = hmac::Key::new(hmac::HMAC_SHA384, &[0x0b; 48]);
+
+    // Keys constructed directly from the `Okm` use the same bytes as when
+    // the output is filled manually.
+    for &alg in &[
+        &aead::AES_128_GCM,
+        &aead::AES_256_GCM,
+        &aead::CHACHA20_POLY1305,
+    ] {
+        let My(key_bytes) = kbkdf::derive(&key, b"label", b"context", My(alg.key_len()))
+            .unwrap()
+            .into();
+        let expected = aead::LessSafeKey::new(aead::UnboundKey::new(alg, &key_bytes).unwrap());
+        let actual = aead::LessSafeKey::new(
+            kbkdf::derive(&key, b"label", b"context", alg)
+                .unwrap()
+                .into(),
+        );
+        assert_eq!(seal(&expected), seal(&actual));
+    }
+
+    let My(key_bytes) = kbkdf::derive(&key, b"label", b"context", My(32))
+        .unwrap()
+        .into();
+    let expected = hmac::Key::new(hmac::HMAC_SHA256, &key_bytes);
+    let actual: hmac::Key = kbkdf::derive(&key, b"label", b"context", hmac::HMAC_SHA256)
+        .unwrap()
+        .into();
+    assert_eq!(
+        hmac::sign(&expected, b"data").as_ref(),
+        hmac::sign(&actual, b"data").as_ref()
+    );
+}
+
+#[test]
+fn kbkdf_encoding_tests() {
+    use kbkdf::Field::*;
+
+    assert!(kbkdf::Encoding::new(&[Counter, Label, Separator, Context, Length], 4, 4).is_ok());
+    assert!(kbkdf::Encoding::new(&[Label, Separator, Length, Counter, Context], 1, 2).is_ok());
+    assert!(kbkdf::Encoding::new(&[Counter], 1, 1).is_ok());
+
+    // The counter is required.
+    assert!(kbkdf::Encoding::new(&[], 4, 4).is_err());
+    assert!(kbkdf::Encoding::new(&[Label, Separator, Context, Length], 4, 4).is_err());
+
+    // Each field may appear only once.
+    assert!(kbkdf::Encoding::new(&[Counter, Label, Label], 4, 4).is_err());
+    assert!(kbkdf::Encoding::new(&[Counter, Label, Counter], 4, 4).is_err());
+    assert!(
+        kbkdf::Encoding::new(&[Counter, Label, Separator, Context, Length, Length], 4, 4).is_err()
+    );
+
+    // The counter and the length are 1 to 4 bytes long.
+    for len in [0, 5, 8] {
+        assert!(kbkdf::Encoding::new(&[Counter, Length], len, 4).is_err());
+        assert!(kbkdf::Encoding::new(&[Counter, Length], 4, len).is_err());
+    }
+
+    // `derive()` uses `DEFAULT_ENCODING`.
+    let key = hmac::Key::new(hmac::HMAC_SHA256, &[0x0b; 32]);
+    let My(expected) = kbkdf::derive(&key, b"label", b"context", My(40))
+        .unwrap()
+        .into();
+    let My(actual) =
+        kbkdf::derive_with_encoding(&kbkdf::DEFAULT_ENCODING, &key, b"label", b"context", My(40))
+            .unwrap()
+            .into();
+    assert_eq!(actual, expected);
+}
+
+fn seal(key: &aead::LessSafeKey) -> Vec<u8> {
+    let mut in_out = b"plaintext".to_vec();
+    key.seal_in_place_append_tag(
+        aead::Nonce::assume_unique_for_key([0; aead::NONCE_LEN]),
+        aead::Aad::empty(),
+        &mut in_out,
+    )
+    .unwrap();
+    in_out
+}
+
+fn hmac_alg(digest_alg: &'static digest::Algorithm) -> hmac::Algorithm {
+    if digest_alg == &digest::SHA256 {
+        hmac::HMAC_SHA256
+    } else if digest_alg == &digest::SHA384 {
+        hmac::HMAC_SHA384
+    } else if digest_alg == &digest::SHA512 {
+        hmac::HMAC_SHA512
+    } else {
+        panic!("unsupported algorithm: {:?}", digest_alg);
+    }
+}
+
+fn field(name: &str) -> kbkdf::Field {
+    match name {
+        "Counter" => kbkdf::Field::Counter,
+        "Label" => kbkdf::Field::Label,
+        "Separator" => kbkdf::Field::Separator,
+        "Context" => kbkdf::Field::Context,
+        "Length" => kbkdf::Field::Length,
+        _ => panic!("unknown field: {}", name),
+    }
+}
+
+/// Generic newtype wrapper that lets us implement traits for externally-defined
+/// types.
+#[derive(Debug, PartialEq)]
+struct My<T: core::fmt::Debug + PartialEq>(T);
+
+impl hkdf::KeyType for My<usize> {
+    fn len(&self) -> usize {
+        self.0
+    }
+}
+
+impl From<kbkdf::Okm<'_, My<usize>>> for My<Vec<u8>> {
+    fn from(okm: kbkdf::Okm<My<usize>>) -> Self {
+        let mut r = vec![0u8; okm.len().0];
+        okm.fill(&mut r).unwrap();
+        Self(r)
+    }
+}
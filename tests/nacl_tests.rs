@@ -0,0 +1,206 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use ring::{aead::nacl, error, rand, test, test_file};
+
+#[test]
+fn nacl_tests() {
+    test::run(test_file!("nacl_tests.txt"), |section, test_case| {
+        match section {
+            "secretbox" => {
+                let key = test_case.consume_bytes("Key");
+                let key = nacl::Key::new(key.as_slice().try_into()?);
+                let nonce = test_case.consume_bytes("Nonce");
+                let plaintext = test_case.consume_bytes("Plaintext");
+                let ciphertext = test_case.consume_bytes("Ciphertext");
+                test_key(&key, &key, &nonce, &plaintext, &ciphertext)
+            }
+            "box" => {
+                let sender_private_key = private_key(test_case, "SenderPrivateKey")?;
+                let sender_public_key = public_key(test_case, "SenderPublicKey")?;
+                let recipient_private_key = private_key(test_case, "RecipientPrivateKey")?;
+                let recipient_public_key = public_key(test_case, "RecipientPublicKey")?;
+                let nonce = test_case.consume_bytes("Nonce");
+                let plaintext = test_case.consume_bytes("Plaintext");
+                let ciphertext = test_case.consume_bytes("Ciphertext");
+
+                assert_eq!(
+                    sender_private_key.compute_public_key()?.as_ref(),
+                    sender_public_key.as_ref()
+                );
+                assert_eq!(
+                    recipient_private_key.compute_public_key()?.as_ref(),
+                    recipient_public_key.as_ref()
+                );
+
+                let sealing_key =
+                    nacl::Key::precompute(&sender_private_key, &recipient_public_key)?;
+                let opening_key =
+                    nacl::Key::precompute(&recipient_private_key, &sender_public_key)?;
+                test_key(&sealing_key, &opening_key, &nonce, &plaintext, &ciphertext)
+            }
+            "sealed_box" => {
+                let recipient_private_key = private_key(test_case, "RecipientPrivateKey")?;
+                let recipient_public_key = public_key(test_case, "RecipientPublicKey")?;
+                let plaintext = test_case.consume_bytes("Plaintext");
+                let sealed_box = test_case.consume_bytes("SealedBox");
+                assert_eq!(
+                    sealed_box.len(),
+                    nacl::SEALED_BOX_OVERHEAD_LEN + plaintext.len()
+                );
+
+                let mut in_out = sealed_box.clone();
+                let opened = nacl::open_anonymous_in_place(&recipient_private_key, &mut in_out)?;
+                assert_eq!(opened, &plaintext[..]);
+
+                // Sealed boxes are randomized, so check that ours round-trip.
+                let rng = rand::SystemRandom::new();
+                let mut in_out = vec![0u8; nacl::SEALED_BOX_OVERHEAD_LEN];
+                in_out.extend_from_slice(&plaintext);
+                nacl::seal_anonymous_in_place(&recipient_public_key, &mut in_out, &rng)?;
+                assert_ne!(
+                    &in_out[..nacl::PUBLIC_KEY_LEN],
+                    &sealed_box[..nacl::PUBLIC_KEY_LEN]
+                );
+                let opened = nacl::open_anonymous_in_place(&recipient_private_key, &mut in_out)?;
+                assert_eq!(opened, &plaintext[..]);
+
+                // Tamper with every part of the sealed box.
+                for i in [0, nacl::PUBLIC_KEY_LEN, sealed_box.len() - 1] {
+                    let mut in_out = sealed_box.clone();
+                    in_out[i] ^= 1;
+                    let tampered = in_out.clone();
+                    assert!(
+                        nacl::open_anonymous_in_place(&recipient_private_key, &mut in_out).is_err()
+                    );
+                    assert_eq!(in_out, tampered);
+                }
+                Ok(())
+            }
+            _ => unreachable!(),
+        }
+    });
+}
+
+fn test_key(
+    sealing_key: &nacl::Key,
+    opening_key: &nacl::Key,
+    nonce: &[u8],
+    plaintext: &[u8],
+    ciphertext: &[u8],
+) -> Result<(), error::Unspecified> {
+    let nonce = || nacl::Nonce::assume_unique_for_key(nonce.try_into().unwrap());
+    let (expected_tag, expected_ciphertext) = ciphertext.split_at(nacl::TAG_LEN);
+
+    // Combined format.
+    let mut in_out = vec![0u8; nacl::TAG_LEN];
+    in_out.extend_from_slice(plaintext);
+    sealing_key.seal_in_place(nonce(), &mut in_out)?;
+    assert_eq!(in_out, ciphertext);
+    let opened = opening_key.open_in_place(nonce(), &mut in_out)?;
+    assert_eq!(opened, plaintext);
+
+    // Detached format.
+    let mut in_out = plaintext.to_vec();
+    let tag = sealing_key.seal_in_place_separate_tag(nonce(), &mut in_out);
+    assert_eq!(tag.as_ref(), expected_tag);
+    assert_eq!(in_out, expected_ciphertext);
+    opening_key.open_in_place_separate_tag(nonce(), tag, &mut in_out)?;
+    assert_eq!(in_out, plaintext);
+
+    // Tampering is detected before anything is decrypted.
+    for i in [0, ciphertext.len() - 1] {
+        let mut in_out = ciphertext.to_vec();
+        in_out[i] ^= 1;
+        let tampered = in_out.clone();
+        assert!(opening_key.open_in_place(nonce(), &mut in_out).is_err());
+        assert_eq!(in_out, tampered);
+    }
+    let mut in_out = ciphertext.to_vec();
+    assert!(opening_key
+        .open_in_place(
+            nacl::Nonce::assume_unique_for_key([0; nacl::NONCE_LEN]),
+            &mut in_out
+        )
+        .is_err());
+    assert!(opening_key
+        .open_in_place(nonce(), &mut in_out[..nacl::TAG_LEN - 1])
+        .is_err());
+
+    Ok(())
+}
+
+fn private_key(
+    test_case: &mut test::TestCase,
+    key: &str,
+) -> Result<nacl::PrivateKey, error::Unspecified> {
+    let bytes = test_case.consume_bytes(key);
+    nacl::PrivateKey::from_bytes(bytes.as_slice().try_into()?)
+}
+
+fn public_key(
+    test_case: &mut test::TestCase,
+    key: &str,
+) -> Result<nacl::PublicKey, error::Unspecified> {
+    let bytes = test_case.consume_bytes(key);
+    Ok(nacl::PublicKey::new(bytes.as_slice().try_into()?))
+}
+
+#[test]
+fn nacl_box_round_trip() {
+    let rng = rand::SystemRandom::new();
+    let alice = nacl::PrivateKey::generate(&rng).unwrap();
+    let bob = nacl::PrivateKey::generate(&rng).unwrap();
+    let alice_to_bob = nacl::Key::precompute(&alice, &bob.compute_public_key().unwrap()).unwrap();
+    let bob_from_alice = nacl::Key::precompute(&bob, &alice.compute_public_key().unwrap()).unwrap();
+
+    let mut in_out = vec![0u8; nacl::TAG_LEN];
+    in_out.extend_from_slice(b"hello");
+    let nonce = [7u8; nacl::NONCE_LEN];
+    alice_to_bob
+        .seal_in_place(nacl::Nonce::assume_unique_for_key(nonce), &mut in_out)
+        .unwrap();
+    let opened = bob_from_alice
+        .open_in_place(nacl::Nonce::assume_unique_for_key(nonce), &mut in_out)
+        .unwrap();
+    assert_eq!(opened, b"hello");
+}
+
+#[test]
+fn nacl_box_small_order_public_key() {
+    let rng = rand::SystemRandom::new();
+    let private_key = nacl::PrivateKey::generate(&rng).unwrap();
+    let zero = nacl::PublicKey::new([0; nacl::PUBLIC_KEY_LEN]);
+    assert!(nacl::Key::precompute(&private_key, &zero).is_err());
+
+    let mut in_out = [0u8; nacl::SEALED_BOX_OVERHEAD_LEN];
+    assert!(nacl::seal_anonymous_in_place(&zero, &mut in_out, &rng).is_err());
+    assert!(nacl::seal_anonymous_in_place(
+        &private_key.compute_public_key().unwrap(),
+        &mut in_out[1..],
+        &rng
+    )
+    .is_err());
+}
+
+#[test]
+fn nacl_secretbox_in_out_too_short() {
+    let key = nacl::Key::new(&[0; nacl::KEY_LEN]);
+    let nonce = || nacl::Nonce::assume_unique_for_key([0; nacl::NONCE_LEN]);
+    let mut in_out = [0u8; nacl::TAG_LEN - 1];
+    assert!(key.seal_in_place(nonce(), &mut in_out).is_err());
+    assert!(key.open_in_place(nonce(), &mut in_out).is_err());
+}
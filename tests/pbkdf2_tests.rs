@@ -66,3 +66,105 @@ pub fn pbkdf2_tests() {
         Ok(())
     });
 }
+
+#[cfg(feature = "alloc")]
+#[test]
+pub fn pbkdf2_phc_string_tests() {
+    let min_iterations = NonZeroU32::new(4096).unwrap();
+    let max_iterations = NonZeroU32::new(4096).unwrap();
+
+    for &(phc, algorithm, iterations) in &[
+        (
+            "$pbkdf2$i=2$c2FsdA$6mwBTcctb4zNHtkqzh1B8NjeiVc",
+            pbkdf2::PBKDF2_HMAC_SHA1,
+            2,
+        ),
+        (
+            "$pbkdf2-sha256$i=4096$c2FsdA$xeR41ZKIyEGqUw22hFxMjZYok6ABzk4RpJY4c6qYE0o",
+            pbkdf2::PBKDF2_HMAC_SHA256,
+            4096,
+        ),
+        (
+            "$pbkdf2-sha512$i=1000$c2FsdHNhbHQ$Q6v4xwJ8a9nWPp2BeEoAYYhHSo2xRmPWART17vTpSxt2q6iNp7BOozW557qqa95eNjUO4gKs0CyvJbYGGku1tA",
+            pbkdf2::PBKDF2_HMAC_SHA512,
+            1000,
+        ),
+    ] {
+        let parsed = pbkdf2::PhcString::parse(phc, max_iterations).unwrap();
+        assert!(parsed.algorithm() == algorithm);
+        assert_eq!(parsed.iterations().get(), iterations);
+        assert_eq!(parsed.to_string(), phc);
+
+        let expected_rehash = if iterations < min_iterations.get() {
+            pbkdf2::Rehash::Needed
+        } else {
+            pbkdf2::Rehash::NotNeeded
+        };
+        assert_eq!(
+            pbkdf2::verify_phc_string(phc, b"password", min_iterations, max_iterations),
+            Ok(expected_rehash)
+        );
+        assert_eq!(
+            pbkdf2::verify_phc_string(phc, b"passwore", min_iterations, max_iterations),
+            Err(error::Unspecified)
+        );
+
+        let derived = pbkdf2::PhcString::derive(
+            algorithm,
+            parsed.iterations(),
+            parsed.salt(),
+            b"password",
+            parsed.hash().len(),
+        );
+        assert_eq!(derived.to_string(), phc);
+    }
+
+    // An explicit output length is accepted when it matches.
+    assert!(pbkdf2::PhcString::parse(
+        "$pbkdf2-sha256$i=4096,l=32$c2FsdA$xeR41ZKIyEGqUw22hFxMjZYok6ABzk4RpJY4c6qYE0o",
+        max_iterations
+    )
+    .is_ok());
+
+    // Iteration counts above the maximum are rejected without running PBKDF2.
+    let phc = "$pbkdf2-sha256$i=4096$c2FsdA$xeR41ZKIyEGqUw22hFxMjZYok6ABzk4RpJY4c6qYE0o";
+    let lower_max = NonZeroU32::new(4095).unwrap();
+    assert!(pbkdf2::PhcString::parse(phc, lower_max).is_err());
+    assert_eq!(
+        pbkdf2::verify_phc_string(phc, b"password", min_iterations, lower_max),
+        Err(error::Unspecified)
+    );
+
+    for &invalid in &[
+        "",
+        "$",
+        "pbkdf2-sha256$i=4096$c2FsdA$xeR41ZKIyEGqUw22hFxMjZYok6ABzk4RpJY4c6qYE0o",
+        "$pbkdf2-sha224$i=4096$c2FsdA$xeR41ZKIyEGqUw22hFxMjZYok6ABzk4RpJY4c6qYE0o",
+        "$scrypt$i=4096$c2FsdA$xeR41ZKIyEGqUw22hFxMjZYok6ABzk4RpJY4c6qYE0o",
+        // Missing, zero, non-canonical, or out-of-range iteration counts.
+        "$pbkdf2-sha256$c2FsdA$xeR41ZKIyEGqUw22hFxMjZYok6ABzk4RpJY4c6qYE0o",
+        "$pbkdf2-sha256$i=0$c2FsdA$xeR41ZKIyEGqUw22hFxMjZYok6ABzk4RpJY4c6qYE0o",
+        "$pbkdf2-sha256$i=04096$c2FsdA$xeR41ZKIyEGqUw22hFxMjZYok6ABzk4RpJY4c6qYE0o",
+        "$pbkdf2-sha256$i=+4096$c2FsdA$xeR41ZKIyEGqUw22hFxMjZYok6ABzk4RpJY4c6qYE0o",
+        "$pbkdf2-sha256$i=4294967296$c2FsdA$xeR41ZKIyEGqUw22hFxMjZYok6ABzk4RpJY4c6qYE0o",
+        // Unknown parameters and mismatched lengths.
+        "$pbkdf2-sha256$i=4096,x=1$c2FsdA$xeR41ZKIyEGqUw22hFxMjZYok6ABzk4RpJY4c6qYE0o",
+        "$pbkdf2-sha256$i=4096,l=31$c2FsdA$xeR41ZKIyEGqUw22hFxMjZYok6ABzk4RpJY4c6qYE0o",
+        "$pbkdf2-sha256$l=32,i=4096$c2FsdA$xeR41ZKIyEGqUw22hFxMjZYok6ABzk4RpJY4c6qYE0o",
+        // Missing, empty, padded, or extra fields.
+        "$pbkdf2-sha256$i=4096$c2FsdA",
+        "$pbkdf2-sha256$i=4096$c2FsdA$",
+        "$pbkdf2-sha256$i=4096$c2FsdA==$xeR41ZKIyEGqUw22hFxMjZYok6ABzk4RpJY4c6qYE0o",
+        "$pbkdf2-sha256$i=4096$c2FsdA$xeR41ZKIyEGqUw22hFxMjZYok6ABzk4RpJY4c6qYE0o$",
+    ] {
+        assert!(
+            pbkdf2::PhcString::parse(invalid, max_iterations).is_err(),
+            "{}",
+            invalid
+        );
+        assert_eq!(
+            pbkdf2::verify_phc_string(invalid, b"password", min_iterations, max_iterations),
+            Err(error::Unspecified)
+        );
+    }
+}
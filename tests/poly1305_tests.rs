@@ -0,0 +1,72 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use ring::{aead::poly1305, test, test_file};
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[test]
+fn poly1305_tests() {
+    test::run(
+        test_file!("../src/aead/poly1305_test.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+            let key = test_case.consume_bytes("Key");
+            let key: [u8; poly1305::KEY_LEN] = key.as_slice().try_into().unwrap();
+            let mut input = test_case.consume_bytes("Input");
+            let mac = test_case.consume_bytes("MAC");
+            assert_eq!(mac.len(), poly1305::TAG_LEN);
+
+            // One-shot API.
+            assert_eq!(
+                poly1305::sign(poly1305::Key::new(key), &input).as_ref(),
+                &mac[..]
+            );
+            assert!(poly1305::verify(poly1305::Key::new(key), &input, &mac).is_ok());
+
+            // Incremental API.
+            for split in [0, 1, 15, 16, 17, 63, 64, 65, input.len()] {
+                if split > input.len() {
+                    continue;
+                }
+                let (a, b) = input.split_at(split);
+                let mut ctx = poly1305::Context::new(poly1305::Key::new(key));
+                ctx.update(a);
+                ctx.update(&[]);
+                ctx.update(b);
+                assert_eq!(ctx.finish().as_ref(), &mac[..]);
+            }
+            let mut ctx = poly1305::Context::new(poly1305::Key::new(key));
+            input.chunks(3).for_each(|chunk| ctx.update(chunk));
+            assert!(ctx.verify(&mac).is_ok());
+
+            // Tamper with the tag and the input.
+            let mut wrong_mac = mac.clone();
+            wrong_mac[15] ^= 0x80;
+            assert!(poly1305::verify(poly1305::Key::new(key), &input, &wrong_mac).is_err());
+            assert!(poly1305::verify(poly1305::Key::new(key), &input, &mac[..15]).is_err());
+            // When `r` is zero the tag doesn't depend on the input.
+            if key[..16] != [0; 16] {
+                input.push(0);
+                assert!(poly1305::verify(poly1305::Key::new(key), &input, &mac).is_err());
+            }
+
+            Ok(())
+        },
+    );
+}
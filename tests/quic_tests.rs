@@ -12,7 +12,7 @@
 // OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
 // CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
 
-use ring::{aead::quic, test, test_file};
+use ring::{aead, aead::quic, error, hkdf, test, test_file};
 
 #[test]
 fn quic_aes_128() {
@@ -68,3 +68,130 @@ fn test_sample_len(alg: &'static quic::Algorithm) {
     // Sample is empty.
     assert!(key.new_mask(&[]).is_err());
 }
+
+#[test]
+fn quic_packet_protection() {
+    test::run(
+        test_file!("quic_packet_protection_tests.txt"),
+        |section, test_case| {
+            assert_eq!(section, "");
+            let version = match test_case.consume_usize("VERSION") {
+                1 => quic::Version::V1,
+                2 => quic::Version::V2,
+                _ => unreachable!(),
+            };
+            let algorithm = match test_case.consume_string("ALGORITHM").as_str() {
+                "AES_128_GCM" => &aead::AES_128_GCM,
+                "AES_256_GCM" => &aead::AES_256_GCM,
+                "CHACHA20_POLY1305" => &aead::CHACHA20_POLY1305,
+                _ => unreachable!(),
+            };
+            let hkdf_algorithm = match test_case.consume_string("HASH").as_str() {
+                "SHA256" => hkdf::HKDF_SHA256,
+                "SHA384" => hkdf::HKDF_SHA384,
+                _ => unreachable!(),
+            };
+            let secret = test_case.consume_bytes("SECRET");
+            let key_updates = test_case.consume_usize("KEY_UPDATES");
+            let packet_number: u64 = test_case.consume_string("PACKET_NUMBER").parse().unwrap();
+            let header = test_case.consume_bytes("HEADER");
+            let plaintext = test_case.consume_bytes("IN");
+            let ct = test_case.consume_bytes("CT");
+            let sample = test_case.consume_bytes("SAMPLE");
+            let mask = test_case.consume_bytes("MASK");
+
+            let secret = hkdf::Prk::new_less_safe(hkdf_algorithm, &secret);
+            let mut keys = quic::DirectionalKeys::new(version, algorithm, &secret)?;
+            for _ in 0..key_updates {
+                keys.packet = keys.packet.next();
+            }
+            assert_eq!(keys.packet.version(), version);
+            assert_eq!(keys.packet.algorithm(), algorithm);
+
+            // The header protection key isn't affected by key updates.
+            assert_eq!(&keys.header.new_mask(&sample)?, &mask[..]);
+
+            let mut in_out = plaintext.clone();
+            let tag = keys
+                .packet
+                .seal_in_place(packet_number, &header, &mut in_out)?;
+            in_out.extend_from_slice(tag.as_ref());
+            assert_eq!(in_out, ct);
+
+            // The wrong packet number uses the wrong nonce.
+            assert_eq!(
+                keys.packet
+                    .open_in_place(packet_number ^ 1, &header, &mut ct.clone()),
+                Err(error::Unspecified)
+            );
+
+            // The header is authenticated.
+            let mut wrong_header = header.clone();
+            wrong_header[0] ^= 1;
+            assert_eq!(
+                keys.packet
+                    .open_in_place(packet_number, &wrong_header, &mut ct.clone()),
+                Err(error::Unspecified)
+            );
+
+            let opened = keys
+                .packet
+                .open_in_place(packet_number, &header, &mut in_out)?;
+            assert_eq!(opened, &plaintext[..]);
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn quic_retry_integrity_tag() {
+    // From RFC 9001 Appendix A.4 and RFC 9369 Appendix A.4.
+    const ODCID: &[u8] = &[0x83, 0x94, 0xc8, 0xf0, 0x3e, 0x51, 0x57, 0x08];
+    let tests: &[(quic::Version, &str)] = &[
+        (
+            quic::Version::V1,
+            "ff000000010008f067a5502a4262b5746f6b656e04a265ba2eff4d829058fb3f0f2496ba",
+        ),
+        (
+            quic::Version::V2,
+            "cf6b3343cf0008f067a5502a4262b5746f6b656ec8646ce8bfe33952d955543665dcc7b6",
+        ),
+    ];
+    for &(version, retry_packet) in tests {
+        let mut retry_packet = test::from_hex(retry_packet).unwrap();
+        let (without_tag, expected_tag) = retry_packet.split_at(retry_packet.len() - 16);
+        let tag = version.retry_integrity_tag(ODCID, without_tag).unwrap();
+        assert_eq!(tag.as_ref(), expected_tag);
+        assert!(version
+            .verify_retry_integrity_tag(ODCID, &retry_packet)
+            .is_ok());
+
+        // The tag depends on the version and on the original destination
+        // connection ID.
+        let other_version = match version {
+            quic::Version::V1 => quic::Version::V2,
+            quic::Version::V2 => quic::Version::V1,
+        };
+        assert!(other_version
+            .verify_retry_integrity_tag(ODCID, &retry_packet)
+            .is_err());
+        assert!(version
+            .verify_retry_integrity_tag(&ODCID[1..], &retry_packet)
+            .is_err());
+
+        // The connection ID length must fit in a byte.
+        assert!(version.retry_integrity_tag(&[0; 256], without_tag).is_err());
+
+        let last = retry_packet.len() - 1;
+        retry_packet[last] ^= 1;
+        assert!(version
+            .verify_retry_integrity_tag(ODCID, &retry_packet)
+            .is_err());
+
+        // Too short to contain a tag.
+        assert!(version
+            .verify_retry_integrity_tag(ODCID, &retry_packet[..15])
+            .is_err());
+    }
+}
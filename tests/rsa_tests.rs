@@ -342,4 +342,8 @@ fn test_public_key_coverage(key: &rsa::PublicKey) {
     assert_eq!(PUBLIC_KEY_MODULUS_BE_BYTES, &components.n);
     const _65537: &[u8] = &[0x01, 0x00, 0x01];
     assert_eq!(_65537, &components.e);
+
+    // Test `to_spki()`.
+    const PUBLIC_KEY_SPKI: &[u8] = include_bytes!("rsa_test_public_key_2048_spki.der");
+    assert_eq!(key.to_spki().as_ref(), PUBLIC_KEY_SPKI);
 }
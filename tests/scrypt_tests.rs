@@ -0,0 +1,90 @@
+// Copyright 2015-2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#![cfg(feature = "alloc")]
+
+use ring::{error, scrypt, test, test_file};
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MAX_MEMORY_LEN: usize = 32 * 1024 * 1024;
+
+#[test]
+pub fn scrypt_tests() {
+    test::run(test_file!("scrypt_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+        let n = test_case.consume_usize("N");
+        assert!(n.is_power_of_two());
+        let log_n = n.trailing_zeros() as u8;
+        let r = test_case.consume_usize("r") as u32;
+        let p = test_case.consume_usize("p") as u32;
+        let secret = test_case.consume_bytes("P");
+        let salt = test_case.consume_bytes("S");
+        let dk = test_case.consume_bytes("DK");
+        let verify_expected_result = test_case.consume_string("Verify");
+        let verify_expected_result = match verify_expected_result.as_str() {
+            "OK" => Ok(()),
+            "Err" => Err(error::Unspecified),
+            _ => panic!("Unsupported value of \"Verify\""),
+        };
+
+        let params = scrypt::Params::new(log_n, r, p, MAX_MEMORY_LEN).unwrap();
+        assert_eq!(params.log_n(), log_n);
+        assert_eq!(params.r(), r);
+        assert_eq!(params.p(), p);
+
+        {
+            let mut out = vec![0u8; dk.len()];
+            scrypt::derive(params, &salt, &secret, &mut out);
+            assert_eq!(dk == out, verify_expected_result.is_ok() || dk.is_empty());
+        }
+
+        assert_eq!(
+            scrypt::verify(params, &salt, &secret, &dk),
+            verify_expected_result
+        );
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn scrypt_params_tests() {
+    // N must be at least 2.
+    assert!(scrypt::Params::new(0, 8, 1, MAX_MEMORY_LEN).is_err());
+    assert!(scrypt::Params::new(1, 8, 1, MAX_MEMORY_LEN).is_ok());
+
+    // r and p must be nonzero.
+    assert!(scrypt::Params::new(10, 0, 1, MAX_MEMORY_LEN).is_err());
+    assert!(scrypt::Params::new(10, 8, 0, MAX_MEMORY_LEN).is_err());
+
+    // N must be less than 2**(16 * r).
+    assert!(scrypt::Params::new(15, 1, 1, usize::MAX).is_ok());
+    assert!(scrypt::Params::new(16, 1, 1, usize::MAX).is_err());
+
+    // r * p must be less than 2**30.
+    assert!(scrypt::Params::new(1, 1 << 15, 1 << 15, usize::MAX).is_err());
+
+    // The memory limit is enforced. N = 2**20, r = 8 requires over 1 GiB.
+    assert!(scrypt::Params::new(20, 8, 1, MAX_MEMORY_LEN).is_err());
+    let params = scrypt::Params::new(14, 8, 1, MAX_MEMORY_LEN).unwrap();
+    assert_eq!(params.memory_len(), 128 * 8 * ((1 << 14) + 1 + 2));
+
+    // Parameters whose memory requirement overflows are rejected.
+    assert!(scrypt::Params::new(63, 8, 1, usize::MAX).is_err());
+}
@@ -0,0 +1,91 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+#![cfg(feature = "alloc")]
+
+use ring::{spki, test, test_file};
+
+#[cfg(not(target_arch = "wasm32"))]
+use ring::{agreement, rand};
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[test]
+fn spki_from_der_test() {
+    test::run(test_file!("spki_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+        spki_from_der(test_case);
+        Ok(())
+    });
+}
+
+// X25519 isn't available for wasm32.
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn spki_from_der_x25519_test() {
+    test::run(test_file!("spki_x25519_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+        spki_from_der(test_case);
+        Ok(())
+    });
+}
+
+fn spki_from_der(test_case: &mut test::TestCase) {
+    let input = test_case.consume_bytes("Input");
+    let error = test_case.consume_optional_string("Error");
+
+    match (spki::SubjectPublicKeyInfo::from_der(&input), error) {
+        (Ok(actual), None) => {
+            let key_type = match test_case.consume_string("KeyType").as_str() {
+                "Rsa" => spki::KeyType::Rsa,
+                "EcP256" => spki::KeyType::EcP256,
+                "EcP384" => spki::KeyType::EcP384,
+                "Ed25519" => spki::KeyType::Ed25519,
+                #[cfg(not(target_arch = "wasm32"))]
+                "X25519" => spki::KeyType::X25519,
+                _ => unreachable!(),
+            };
+            let public_key = test_case.consume_bytes("PublicKey");
+            assert_eq!(actual.key_type(), key_type);
+            assert_eq!(actual.public_key(), &public_key[..]);
+        }
+        (Err(e), None) => panic!("Failed with error \"{}\", but expected to succeed", e),
+        (Ok(_), Some(e)) => panic!("Succeeded, but expected error \"{}\"", e),
+        (Err(actual), Some(expected)) => assert_eq!(format!("{}", actual), expected),
+    };
+}
+
+// X25519 isn't available for wasm32.
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn spki_agreement_public_key_to_spki() {
+    let rng = rand::SystemRandom::new();
+
+    for (alg, key_type) in [
+        (&agreement::X25519, spki::KeyType::X25519),
+        (&agreement::ECDH_P256, spki::KeyType::EcP256),
+        (&agreement::ECDH_P384, spki::KeyType::EcP384),
+    ] {
+        let private_key = agreement::EphemeralPrivateKey::generate(alg, &rng).unwrap();
+        let public_key = private_key.compute_public_key().unwrap();
+        let document = public_key.to_spki();
+        let parsed = spki::SubjectPublicKeyInfo::from_der(document.as_ref()).unwrap();
+        assert_eq!(parsed.key_type(), key_type);
+        assert_eq!(parsed.public_key(), public_key.as_ref());
+    }
+}
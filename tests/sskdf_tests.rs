@@ -0,0 +1,173 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use ring::{aead, digest, error, hkdf, hmac, sskdf, test, test_file};
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[test]
+fn sskdf_tests() {
+    test::run(test_file!("sskdf_tests.txt"), |section, test_case| {
+        assert_eq!(section, "");
+        let digest_alg = test_case.consume_digest_alg("Hash").unwrap();
+        let salt = test_case.consume_optional_bytes("Salt");
+        let z = test_case.consume_bytes("Z");
+        let fixed_info = test_case.consume_bytes("FixedInfo");
+        let expected_out = test_case.consume_bytes("DKM");
+
+        let My(out) = match salt {
+            Some(salt) => {
+                let salt = hmac::Key::new(hmac_alg(digest_alg), &salt);
+                sskdf::derive_with_hmac(&salt, &z, &[&fixed_info], My(expected_out.len()))
+                    .unwrap()
+                    .into()
+            }
+            None => {
+                sskdf::derive_with_digest(digest_alg, &z, &[&fixed_info], My(expected_out.len()))
+                    .unwrap()
+                    .into()
+            }
+        };
+        assert_eq!(out, expected_out);
+
+        // Splitting FixedInfo into multiple parts, including empty ones,
+        // doesn't affect the output.
+        if fixed_info.len() >= 2 {
+            let (a, b) = fixed_info.split_at(fixed_info.len() / 2);
+            let My(out) =
+                sskdf::derive_with_digest(digest_alg, &z, &[a, &[], b], My(expected_out.len()))
+                    .unwrap()
+                    .into();
+            let My(expected_out) =
+                sskdf::derive_with_digest(digest_alg, &z, &[&fixed_info], My(expected_out.len()))
+                    .unwrap()
+                    .into();
+            assert_eq!(out, expected_out);
+        }
+
+        Ok(())
+    });
+}
+
+#[test]
+fn sskdf_output_len_tests() {
+    let z = [0x0b; 32];
+
+    {
+        // Test zero length.
+        let okm = sskdf::derive_with_digest(&digest::SHA256, &z, &[b"info"], My(0)).unwrap();
+        let result: My<Vec<u8>> = okm.into();
+        assert_eq!(&result.0, &[]);
+    }
+
+    {
+        // Test length mismatch.
+        let okm = sskdf::derive_with_digest(&digest::SHA256, &z, &[b"info"], My(2)).unwrap();
+        let mut buf = [0u8; 1];
+        assert_eq!(okm.fill(&mut buf), Err(error::Unspecified));
+    }
+
+    // The 32-bit counter must not wrap.
+    #[cfg(target_pointer_width = "64")]
+    {
+        let max_len = (u32::MAX as usize) * digest::SHA256_OUTPUT_LEN;
+        assert!(sskdf::derive_with_digest(&digest::SHA256, &z, &[], My(max_len)).is_ok());
+        assert!(sskdf::derive_with_digest(&digest::SHA256, &z, &[], My(max_len + 1)).is_err());
+    }
+}
+
+#[test]
+fn sskdf_key_type_tests() {
+    let z = [0x0b; 32];
+    let fixed_info: &[&[u8]] = &[b"fixed", b"info"];
+
+    // Keys constructed directly from the `Okm` use the same bytes as when
+    // the output is filled manually.
+    for &alg in &[
+        &aead::AES_128_GCM,
+        &aead::AES_256_GCM,
+        &aead::CHACHA20_POLY1305,
+    ] {
+        let My(key_bytes) =
+            sskdf::derive_with_digest(&digest::SHA256, &z, fixed_info, My(alg.key_len()))
+                .unwrap()
+                .into();
+        let expected = aead::LessSafeKey::new(aead::UnboundKey::new(alg, &key_bytes).unwrap());
+        let actual = aead::LessSafeKey::new(
+            sskdf::derive_with_digest(&digest::SHA256, &z, fixed_info, alg)
+                .unwrap()
+                .into(),
+        );
+        assert_eq!(seal(&expected), seal(&actual));
+    }
+
+    let salt = hmac::Key::new(hmac::HMAC_SHA256, b"salt");
+    let My(key_bytes) = sskdf::derive_with_hmac(&salt, &z, fixed_info, My(48))
+        .unwrap()
+        .into();
+    let expected = hmac::Key::new(hmac::HMAC_SHA384, &key_bytes);
+    let actual: hmac::Key = sskdf::derive_with_hmac(&salt, &z, fixed_info, hmac::HMAC_SHA384)
+        .unwrap()
+        .into();
+    assert_eq!(
+        hmac::sign(&expected, b"data").as_ref(),
+        hmac::sign(&actual, b"data").as_ref()
+    );
+}
+
+fn seal(key: &aead::LessSafeKey) -> Vec<u8> {
+    let mut in_out = b"plaintext".to_vec();
+    key.seal_in_place_append_tag(
+        aead::Nonce::assume_unique_for_key([0; aead::NONCE_LEN]),
+        aead::Aad::empty(),
+        &mut in_out,
+    )
+    .unwrap();
+    in_out
+}
+
+fn hmac_alg(digest_alg: &'static digest::Algorithm) -> hmac::Algorithm {
+    if digest_alg == &digest::SHA256 {
+        hmac::HMAC_SHA256
+    } else if digest_alg == &digest::SHA384 {
+        hmac::HMAC_SHA384
+    } else if digest_alg == &digest::SHA512 {
+        hmac::HMAC_SHA512
+    } else {
+        panic!("unsupported algorithm: {:?}", digest_alg);
+    }
+}
+
+/// Generic newtype wrapper that lets us implement traits for externally-defined
+/// types.
+#[derive(Debug, PartialEq)]
+struct My<T: core::fmt::Debug + PartialEq>(T);
+
+impl hkdf::KeyType for My<usize> {
+    fn len(&self) -> usize {
+        self.0
+    }
+}
+
+impl From<sskdf::Okm<'_, My<usize>>> for My<Vec<u8>> {
+    fn from(okm: sskdf::Okm<My<usize>>) -> Self {
+        let mut r = vec![0u8; okm.len().0];
+        okm.fill(&mut r).unwrap();
+        Self(r)
+    }
+}
@@ -0,0 +1,232 @@
+// Copyright 2023 Brian Smith.
+//
+// Permission to use, copy, modify, and/or distribute this software for any
+// purpose with or without fee is hereby granted, provided that the above
+// copyright notice and this permission notice appear in all copies.
+//
+// THE SOFTWARE IS PROVIDED "AS IS" AND THE AUTHORS DISCLAIM ALL WARRANTIES
+// WITH REGARD TO THIS SOFTWARE INCLUDING ALL IMPLIED WARRANTIES OF
+// MERCHANTABILITY AND FITNESS. IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY
+// SPECIAL, DIRECT, INDIRECT, OR CONSEQUENTIAL DAMAGES OR ANY DAMAGES
+// WHATSOEVER RESULTING FROM LOSS OF USE, DATA OR PROFITS, WHETHER IN AN ACTION
+// OF CONTRACT, NEGLIGENCE OR OTHER TORTIOUS ACTION, ARISING OUT OF OR IN
+// CONNECTION WITH THE USE OR PERFORMANCE OF THIS SOFTWARE.
+
+use ring::{aead, digest, error, hkdf, test, tls13};
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test_configure!(run_in_browser);
+
+// RFC 9001 Appendix A.1, which uses `HKDF-Expand-Label` to derive the QUIC
+// Initial secrets, keys, and IVs, and Appendix A.2 for the header protection
+// mask.
+#[test]
+fn tls13_expand_label_quic_initial_test() {
+    let initial_salt = test::from_hex("38762cf7f55934b34d179ae6a4c80cadccbb7f0a").unwrap();
+    let dcid = test::from_hex("8394c8f03e515708").unwrap();
+    let initial_secret = hkdf::Salt::new(hkdf::HKDF_SHA256, &initial_salt).extract(&dcid);
+
+    let My(client_initial_secret) =
+        tls13::expand_label(&initial_secret, b"client in", &[], My(32)).unwrap();
+    assert_eq!(
+        client_initial_secret,
+        test::from_hex("c00cf151ca5be075ed0ebfb5c80323c42d6b7db67881289af4008f1f6c357aea").unwrap()
+    );
+
+    let client_initial_secret: hkdf::Prk =
+        tls13::expand_label(&initial_secret, b"client in", &[], hkdf::HKDF_SHA256).unwrap();
+    for &(label, expected) in &[
+        (&b"quic key"[..], "1f369613dd76d5467730efcbe3b1a22d"),
+        (&b"quic iv"[..], "fa044b2f42a3fd3b46fb255c"),
+        (&b"quic hp"[..], "9f50449e04a0e810283a1e9933adedd2"),
+    ] {
+        let My(actual) =
+            tls13::expand_label(&client_initial_secret, label, &[], My(expected.len() / 2))
+                .unwrap();
+        assert_eq!(actual, test::from_hex(expected).unwrap());
+    }
+
+    let hp: aead::quic::HeaderProtectionKey = tls13::expand_label(
+        &client_initial_secret,
+        b"quic hp",
+        &[],
+        &aead::quic::AES_128,
+    )
+    .unwrap();
+    let sample = test::from_hex("d1b1c98dd7689fb8ec11d242b123dc9b").unwrap();
+    assert_eq!(
+        &hp.new_mask(&sample).unwrap()[..],
+        &test::from_hex("437b9aec36").unwrap()[..]
+    );
+}
+
+#[test]
+fn tls13_expand_label_len_tests() {
+    let secret = hkdf::Prk::new_less_safe(hkdf::HKDF_SHA256, &[0x0b; 32]);
+
+    // The label, including the "tls13 " prefix, must fit in 255 bytes.
+    let label = [b'a'; 255 - 6];
+    assert!(tls13::expand_label::<My<Vec<u8>>, _>(&secret, &label, &[], My(32)).is_ok());
+    let label = [b'a'; 255 - 6 + 1];
+    assert!(tls13::expand_label::<My<Vec<u8>>, _>(&secret, &label, &[], My(32)).is_err());
+
+    // The context must fit in 255 bytes.
+    let context = [0u8; 256];
+    assert!(tls13::expand_label::<My<Vec<u8>>, _>(&secret, b"a", &context, My(32)).is_err());
+
+    // The output is limited by HKDF.
+    assert!(tls13::expand_label::<My<Vec<u8>>, _>(&secret, b"a", &[], My(255 * 32)).is_ok());
+    assert!(tls13::expand_label::<My<Vec<u8>>, _>(&secret, b"a", &[], My(255 * 32 + 1)).is_err());
+}
+
+// Runs a full handshake key schedule on both sides and checks that they
+// agree and that records and Finished messages round-trip.
+#[test]
+fn tls13_key_schedule_test() {
+    for &(alg, digest_alg) in &[
+        (hkdf::HKDF_SHA256, &digest::SHA256),
+        (hkdf::HKDF_SHA384, &digest::SHA384),
+    ] {
+        let mut transcript = digest::Context::new(digest_alg);
+        transcript.update(b"ClientHello");
+
+        let early_secret = tls13::EarlySecret::new(alg, None);
+        transcript.update(b"ServerHello");
+        let handshake_secret = early_secret.into_handshake_secret(Some(&[0x42; 32]));
+        let client_hs = handshake_secret.client_handshake_traffic_secret(&transcript);
+        let server_hs = handshake_secret.server_handshake_traffic_secret(&transcript);
+
+        assert_round_trip(&server_hs, &server_hs, &aead::AES_128_GCM);
+        assert_round_trip(&client_hs, &client_hs, &aead::CHACHA20_POLY1305);
+        assert!(!round_trips(&client_hs, &server_hs, &aead::AES_256_GCM));
+
+        transcript.update(b"EncryptedExtensions..CertificateVerify");
+        let server_finished = server_hs.finished_verify_data(&transcript);
+        assert_eq!(server_finished.as_ref().len(), digest_alg.output_len());
+        assert_eq!(
+            server_hs.verify_finished(&transcript, server_finished.as_ref()),
+            Ok(())
+        );
+        assert_eq!(
+            client_hs.verify_finished(&transcript, server_finished.as_ref()),
+            Err(error::Unspecified)
+        );
+        transcript.update(server_finished.as_ref());
+
+        let master_secret = handshake_secret.into_master_secret();
+        let client_ap = master_secret.client_application_traffic_secret_0(&transcript);
+        let server_ap = master_secret.server_application_traffic_secret_0(&transcript);
+        let exporter = master_secret.exporter_master_secret(&transcript);
+        assert_round_trip(&client_ap, &client_ap, &aead::AES_128_GCM);
+        assert!(!round_trips(&client_ap, &client_hs, &aead::AES_128_GCM));
+
+        // Key update.
+        let client_ap_1 = client_ap.next_application_traffic_secret();
+        assert_round_trip(&client_ap_1, &client_ap_1, &aead::AES_128_GCM);
+        assert!(!round_trips(&client_ap, &client_ap_1, &aead::AES_128_GCM));
+        assert!(!round_trips(&server_ap, &client_ap_1, &aead::AES_128_GCM));
+
+        // Exporters are deterministic and depend on the label and context.
+        let mut a = [0u8; 42];
+        let mut b = [0u8; 42];
+        exporter
+            .export_keying_material(b"EXPORTER-test", b"context", &mut a)
+            .unwrap();
+        exporter
+            .export_keying_material(b"EXPORTER-test", b"context", &mut b)
+            .unwrap();
+        assert_eq!(a, b);
+        exporter
+            .export_keying_material(b"EXPORTER-test", b"", &mut b)
+            .unwrap();
+        assert_ne!(a, b);
+
+        // Resumption.
+        let client_finished = client_hs.finished_verify_data(&transcript);
+        transcript.update(client_finished.as_ref());
+        let resumption_master_secret = master_secret.resumption_master_secret(&transcript);
+        let resumed_a =
+            tls13::EarlySecret::from_resumption_master_secret(&resumption_master_secret, &[0, 1])
+                .unwrap();
+        let resumed_b =
+            tls13::EarlySecret::from_resumption_master_secret(&resumption_master_secret, &[0, 1])
+                .unwrap();
+        let mut ch = digest::Context::new(digest_alg);
+        ch.update(b"ClientHello with PSK");
+        let binder = resumed_a.resumption_binder_key().finished_verify_data(&ch);
+        assert_eq!(
+            resumed_b
+                .resumption_binder_key()
+                .verify_finished(&ch, binder.as_ref()),
+            Ok(())
+        );
+        assert_eq!(
+            resumed_b
+                .external_binder_key()
+                .verify_finished(&ch, binder.as_ref()),
+            Err(error::Unspecified)
+        );
+        assert_round_trip(
+            &resumed_a.client_early_traffic_secret(&ch),
+            &resumed_b.client_early_traffic_secret(&ch),
+            &aead::AES_128_GCM,
+        );
+    }
+}
+
+fn assert_round_trip(
+    sender: &tls13::Secret,
+    receiver: &tls13::Secret,
+    algorithm: &'static aead::Algorithm,
+) {
+    assert!(round_trips(sender, receiver, algorithm));
+}
+
+fn round_trips(
+    sender: &tls13::Secret,
+    receiver: &tls13::Secret,
+    algorithm: &'static aead::Algorithm,
+) -> bool {
+    const SEQUENCE_NUMBER: u64 = 7;
+
+    let sealing_key = aead::LessSafeKey::new(sender.traffic_key(algorithm));
+    let mut record = b"record".to_vec();
+    sealing_key
+        .seal_in_place_append_tag(
+            sender.traffic_iv().nonce_for(SEQUENCE_NUMBER),
+            aead::Aad::empty(),
+            &mut record,
+        )
+        .unwrap();
+
+    let opening_key = aead::LessSafeKey::new(receiver.traffic_key(algorithm));
+    opening_key
+        .open_in_place(
+            receiver.traffic_iv().nonce_for(SEQUENCE_NUMBER),
+            aead::Aad::empty(),
+            &mut record,
+        )
+        .is_ok()
+}
+
+/// Generic newtype wrapper that lets us implement traits for externally-defined
+/// types.
+#[derive(Debug, PartialEq)]
+struct My<T: core::fmt::Debug + PartialEq>(T);
+
+impl hkdf::KeyType for My<usize> {
+    fn len(&self) -> usize {
+        self.0
+    }
+}
+
+impl From<hkdf::Okm<'_, My<usize>>> for My<Vec<u8>> {
+    fn from(okm: hkdf::Okm<My<usize>>) -> Self {
+        let mut r = vec![0u8; okm.len().0];
+        okm.fill(&mut r).unwrap();
+        Self(r)
+    }
+}